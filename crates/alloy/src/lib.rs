@@ -67,6 +67,11 @@ macro_rules! sol {
 
 /* --------------------------------------- Main re-exports -------------------------------------- */
 
+/// HTTP clients for the beacon node and MEV-Boost relay APIs.
+#[cfg(feature = "beacon-client")]
+#[doc(inline)]
+pub use alloy_beacon_client as beacon_client;
+
 /// Interact with on-chain contracts.
 #[cfg(feature = "contract")]
 #[doc(inline)]
@@ -0,0 +1,505 @@
+//! See <https://ethereum.github.io/beacon-APIs> for the beacon node API specification.
+
+use crate::{http::HttpClient, ClientError, Encoding};
+use alloy_primitives::B256;
+use alloy_rpc_types_beacon::{
+    block::BlockResponse,
+    config::{DepositContractResponse, ForkScheduleResponse, SpecResponse},
+    duties::{AttesterDutiesResponse, SyncCommitteeDutiesResponse},
+    events::{BeaconEvent, BeaconNodeEventTopic},
+    fork::ForkResponse,
+    genesis::GenesisResponse,
+    header::{HeaderResponse, HeadersResponse},
+    id::{BlockId, StateId},
+    node::{HealthStatus, NodeIdentity, PeerCount, PeersResponse, SyncStatus, VersionData},
+    proposer::ProposerDutiesResponse,
+    rewards::{AttestationRewardsResponse, BlockRewardsResponse, SyncCommitteeRewardsResponse},
+    sidecar::{BeaconBlobBundle, GetBlobsResponse},
+    state::{
+        CommitteesResponse, FinalityCheckpointsResponse, RandaoResponse, StateRootResponse,
+        SyncCommitteesResponse, ValidatorBalancesResponse,
+    },
+    validator::{ValidatorResponse, ValidatorsResponse},
+};
+use alloy_transport_http::sse::{SseEvent, SseParser};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::fmt;
+use url::Url;
+
+/// The header that carries the fork version of SSZ encoded responses.
+const CONSENSUS_VERSION_HEADER: &str = "eth-consensus-version";
+
+/// A client for the `beacon`, `validator`, `node`, `config` and `events` APIs of a beacon node.
+///
+/// ```no_run
+/// # async fn example() -> Result<(), alloy_beacon_client::ClientError> {
+/// use alloy_beacon_client::BeaconClient;
+/// use alloy_rpc_types_beacon::{events::BeaconNodeEventTopic, id::BlockId};
+///
+/// let beacon = BeaconClient::new("http://localhost:5052".parse().unwrap());
+/// let header = beacon.header(BlockId::Finalized).await?;
+///
+/// let mut events = beacon.events(&[BeaconNodeEventTopic::Head]).await?;
+/// while let Some(event) = events.next_event().await {
+///     println!("{:?}", event?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct BeaconClient {
+    http: HttpClient,
+}
+
+impl BeaconClient {
+    /// Creates a new client for the beacon node at the given URL.
+    pub fn new(url: Url) -> Self {
+        Self { http: HttpClient::new(url) }
+    }
+
+    /// Sets the underlying [`reqwest::Client`], e.g. to configure timeouts.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.http = self.http.with_client(client);
+        self
+    }
+
+    /// Returns the URL of the beacon node.
+    pub const fn url(&self) -> &Url {
+        self.http.url()
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, ClientError> {
+        let request = self.http.get(path, Encoding::Json)?;
+        self.http.send_json(request).await
+    }
+
+    async fn get_query<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, ClientError> {
+        let request = self.http.get(path, Encoding::Json)?.query(query);
+        self.http.send_json(request).await
+    }
+
+    async fn get_data<T: DeserializeOwned>(&self, path: &str) -> Result<T, ClientError> {
+        self.get::<Data<T>>(path).await.map(|response| response.data)
+    }
+
+    async fn get_ssz(&self, path: &str) -> Result<SszResponse, ClientError> {
+        let request = self.http.get(path, Encoding::Ssz)?;
+        let response = self.http.send(request).await?;
+        let version = response
+            .headers()
+            .get(CONSENSUS_VERSION_HEADER)
+            .and_then(|version| version.to_str().ok())
+            .map(str::to_string);
+        Ok(SszResponse { version, body: response.bytes().await?.to_vec() })
+    }
+
+    async fn post<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &impl serde::Serialize,
+    ) -> Result<T, ClientError> {
+        let request = self.http.post(path, serde_json::to_vec(body)?, Encoding::Json, false)?;
+        self.http.send_json(request).await
+    }
+
+    // --- beacon ---
+
+    /// Returns the genesis details of the chain.
+    pub async fn genesis(&self) -> Result<GenesisResponse, ClientError> {
+        self.get("/eth/v1/beacon/genesis").await
+    }
+
+    /// Returns the header of the given block.
+    pub async fn header(&self, block: BlockId) -> Result<HeaderResponse, ClientError> {
+        self.get(&format!("/eth/v1/beacon/headers/{block}")).await
+    }
+
+    /// Returns the headers of the canonical head and its non-canonical siblings.
+    pub async fn headers(&self) -> Result<HeadersResponse, ClientError> {
+        self.get("/eth/v1/beacon/headers").await
+    }
+
+    /// Returns the given block, with the message deserialized as `T`.
+    ///
+    /// The message varies by fork, see [`BlockResponse::version`].
+    pub async fn block<T: DeserializeOwned>(
+        &self,
+        block: BlockId,
+    ) -> Result<BlockResponse<T>, ClientError> {
+        self.get(&format!("/eth/v2/beacon/blocks/{block}")).await
+    }
+
+    /// Returns the given block, SSZ encoded.
+    pub async fn block_ssz(&self, block: BlockId) -> Result<SszResponse, ClientError> {
+        self.get_ssz(&format!("/eth/v2/beacon/blocks/{block}")).await
+    }
+
+    /// Returns the root of the given block.
+    pub async fn block_root(&self, block: BlockId) -> Result<B256, ClientError> {
+        let data: Root = self.get_data(&format!("/eth/v1/beacon/blocks/{block}/root")).await?;
+        Ok(data.root)
+    }
+
+    /// Returns the blob sidecars of the given block, optionally filtered by index.
+    pub async fn blob_sidecars(
+        &self,
+        block: BlockId,
+        indices: &[u64],
+    ) -> Result<BeaconBlobBundle, ClientError> {
+        let path = format!("/eth/v1/beacon/blob_sidecars/{block}");
+        if indices.is_empty() {
+            return self.get(&path).await;
+        }
+        self.get_query(&path, &[("indices", join(indices))]).await
+    }
+
+    /// Returns the blobs of the given block, optionally filtered by versioned hash.
+    pub async fn blobs(
+        &self,
+        block: BlockId,
+        versioned_hashes: &[B256],
+    ) -> Result<GetBlobsResponse, ClientError> {
+        let path = format!("/eth/v1/beacon/blobs/{block}");
+        if versioned_hashes.is_empty() {
+            return self.get(&path).await;
+        }
+        self.get_query(&path, &[("versioned_hashes", join(versioned_hashes))]).await
+    }
+
+    /// Returns the root of the given state.
+    pub async fn state_root(&self, state: StateId) -> Result<StateRootResponse, ClientError> {
+        self.get(&format!("/eth/v1/beacon/states/{state}/root")).await
+    }
+
+    /// Returns the fork of the given state.
+    pub async fn state_fork(&self, state: StateId) -> Result<ForkResponse, ClientError> {
+        self.get(&format!("/eth/v1/beacon/states/{state}/fork")).await
+    }
+
+    /// Returns the finality checkpoints of the given state.
+    pub async fn finality_checkpoints(
+        &self,
+        state: StateId,
+    ) -> Result<FinalityCheckpointsResponse, ClientError> {
+        self.get(&format!("/eth/v1/beacon/states/{state}/finality_checkpoints")).await
+    }
+
+    /// Returns the validators of the given state, optionally filtered by index or public key.
+    pub async fn validators<I: fmt::Display>(
+        &self,
+        state: StateId,
+        ids: &[I],
+    ) -> Result<ValidatorsResponse, ClientError> {
+        let path = format!("/eth/v1/beacon/states/{state}/validators");
+        if ids.is_empty() {
+            return self.get(&path).await;
+        }
+        self.get_query(&path, &[("id", join(ids))]).await
+    }
+
+    /// Returns a validator of the given state by index or public key.
+    pub async fn validator(
+        &self,
+        state: StateId,
+        id: impl fmt::Display,
+    ) -> Result<ValidatorResponse, ClientError> {
+        self.get(&format!("/eth/v1/beacon/states/{state}/validators/{id}")).await
+    }
+
+    /// Returns the balances of the validators of the given state, optionally filtered by index or
+    /// public key.
+    pub async fn validator_balances<I: fmt::Display>(
+        &self,
+        state: StateId,
+        ids: &[I],
+    ) -> Result<ValidatorBalancesResponse, ClientError> {
+        let path = format!("/eth/v1/beacon/states/{state}/validator_balances");
+        if ids.is_empty() {
+            return self.get(&path).await;
+        }
+        self.get_query(&path, &[("id", join(ids))]).await
+    }
+
+    /// Returns the committees of the given state, for the epoch of the state unless `epoch` is
+    /// set.
+    pub async fn committees(
+        &self,
+        state: StateId,
+        epoch: Option<u64>,
+    ) -> Result<CommitteesResponse, ClientError> {
+        let path = format!("/eth/v1/beacon/states/{state}/committees");
+        self.get_query(&path, &epoch_query(epoch)).await
+    }
+
+    /// Returns the sync committee of the given state, for the epoch of the state unless `epoch`
+    /// is set.
+    pub async fn sync_committees(
+        &self,
+        state: StateId,
+        epoch: Option<u64>,
+    ) -> Result<SyncCommitteesResponse, ClientError> {
+        let path = format!("/eth/v1/beacon/states/{state}/sync_committees");
+        self.get_query(&path, &epoch_query(epoch)).await
+    }
+
+    /// Returns the RANDAO mix of the given state, for the epoch of the state unless `epoch` is
+    /// set.
+    pub async fn randao(
+        &self,
+        state: StateId,
+        epoch: Option<u64>,
+    ) -> Result<RandaoResponse, ClientError> {
+        let path = format!("/eth/v1/beacon/states/{state}/randao");
+        self.get_query(&path, &epoch_query(epoch)).await
+    }
+
+    /// Returns the rewards of the proposer of the given block.
+    pub async fn block_rewards(&self, block: BlockId) -> Result<BlockRewardsResponse, ClientError> {
+        self.get(&format!("/eth/v1/beacon/rewards/blocks/{block}")).await
+    }
+
+    /// Returns the sync committee rewards of the given block, optionally filtered by validator
+    /// index or public key.
+    pub async fn sync_committee_rewards<I: fmt::Display>(
+        &self,
+        block: BlockId,
+        ids: &[I],
+    ) -> Result<SyncCommitteeRewardsResponse, ClientError> {
+        self.post(&format!("/eth/v1/beacon/rewards/sync_committee/{block}"), &strings(ids)).await
+    }
+
+    /// Returns the attestation rewards of the given epoch, optionally filtered by validator index
+    /// or public key.
+    pub async fn attestation_rewards<I: fmt::Display>(
+        &self,
+        epoch: u64,
+        ids: &[I],
+    ) -> Result<AttestationRewardsResponse, ClientError> {
+        self.post(&format!("/eth/v1/beacon/rewards/attestations/{epoch}"), &strings(ids)).await
+    }
+
+    // --- validator ---
+
+    /// Returns the proposers of the slots of the given epoch.
+    pub async fn proposer_duties(&self, epoch: u64) -> Result<ProposerDutiesResponse, ClientError> {
+        self.get(&format!("/eth/v1/validator/duties/proposer/{epoch}")).await
+    }
+
+    /// Returns the attester duties of the given validators in the given epoch.
+    pub async fn attester_duties(
+        &self,
+        epoch: u64,
+        indices: &[u64],
+    ) -> Result<AttesterDutiesResponse, ClientError> {
+        self.post(&format!("/eth/v1/validator/duties/attester/{epoch}"), &strings(indices)).await
+    }
+
+    /// Returns the sync committee duties of the given validators in the given epoch.
+    pub async fn sync_committee_duties(
+        &self,
+        epoch: u64,
+        indices: &[u64],
+    ) -> Result<SyncCommitteeDutiesResponse, ClientError> {
+        self.post(&format!("/eth/v1/validator/duties/sync/{epoch}"), &strings(indices)).await
+    }
+
+    // --- node ---
+
+    /// Returns the sync status of the node.
+    pub async fn syncing(&self) -> Result<SyncStatus, ClientError> {
+        self.get_data("/eth/v1/node/syncing").await
+    }
+
+    /// Returns the health of the node, derived from the status code of the response.
+    pub async fn health(&self) -> Result<HealthStatus, ClientError> {
+        let response = self.http.get("/eth/v1/node/health", Encoding::Json)?.send().await?;
+        Ok(match response.status().as_u16() {
+            200 => HealthStatus::Ready,
+            206 => HealthStatus::Syncing,
+            503 => HealthStatus::NotInitialized,
+            _ => HealthStatus::Unknown,
+        })
+    }
+
+    /// Returns the version of the node.
+    pub async fn version(&self) -> Result<String, ClientError> {
+        let data: VersionData = self.get_data("/eth/v1/node/version").await?;
+        Ok(data.version)
+    }
+
+    /// Returns the network identity of the node.
+    pub async fn identity(&self) -> Result<NodeIdentity, ClientError> {
+        self.get_data("/eth/v1/node/identity").await
+    }
+
+    /// Returns the peers of the node.
+    pub async fn peers(&self) -> Result<PeersResponse, ClientError> {
+        self.get("/eth/v1/node/peers").await
+    }
+
+    /// Returns the number of peers of the node, by connection state.
+    pub async fn peer_count(&self) -> Result<PeerCount, ClientError> {
+        self.get_data("/eth/v1/node/peer_count").await
+    }
+
+    // --- config ---
+
+    /// Returns the chain configuration of the node.
+    pub async fn spec(&self) -> Result<SpecResponse, ClientError> {
+        self.get("/eth/v1/config/spec").await
+    }
+
+    /// Returns the scheduled forks of the chain.
+    pub async fn fork_schedule(&self) -> Result<ForkScheduleResponse, ClientError> {
+        self.get("/eth/v1/config/fork_schedule").await
+    }
+
+    /// Returns the deposit contract of the chain.
+    pub async fn deposit_contract(&self) -> Result<DepositContractResponse, ClientError> {
+        self.get("/eth/v1/config/deposit_contract").await
+    }
+
+    // --- events ---
+
+    /// Subscribes to the event stream of the given topics.
+    pub async fn events(
+        &self,
+        topics: &[BeaconNodeEventTopic],
+    ) -> Result<BeaconEventStream, ClientError> {
+        let topics = topics.iter().map(|topic| topic.query_value()).collect::<Vec<_>>().join(",");
+        let request = self.http.get_event_stream("/eth/v1/events")?.query(&[("topics", topics)]);
+        let response = self.http.send(request).await?;
+        Ok(BeaconEventStream { response, parser: SseParser::default() })
+    }
+}
+
+/// An SSZ encoded response.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SszResponse {
+    /// The fork of the encoded object, from the `Eth-Consensus-Version` header.
+    pub version: Option<String>,
+    /// The SSZ encoded object.
+    pub body: Vec<u8>,
+}
+
+/// A stream of beacon node events, see [`BeaconClient::events`].
+#[derive(Debug)]
+pub struct BeaconEventStream {
+    response: reqwest::Response,
+    parser: SseParser,
+}
+
+impl BeaconEventStream {
+    /// Waits for the next event, or returns `None` if the node closed the stream.
+    pub async fn next_event(&mut self) -> Option<Result<BeaconEvent, ClientError>> {
+        loop {
+            if let Some(SseEvent { event, data }) = self.parser.next_event() {
+                return Some(BeaconEvent::from_sse(&event, &data).map_err(Into::into));
+            }
+            match self.response.chunk().await {
+                Ok(Some(chunk)) => self.parser.push(&chunk),
+                Ok(None) => return None,
+                Err(err) => return Some(Err(err.into())),
+            }
+        }
+    }
+}
+
+/// The `{"data": ...}` envelope of responses without metadata.
+#[derive(Deserialize)]
+struct Data<T> {
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct Root {
+    root: B256,
+}
+
+fn join<T: fmt::Display>(items: &[T]) -> String {
+    strings(items).join(",")
+}
+
+fn strings<T: fmt::Display>(items: &[T]) -> Vec<String> {
+    items.iter().map(ToString::to_string).collect()
+}
+
+fn epoch_query(epoch: Option<u64>) -> Vec<(&'static str, String)> {
+    epoch.map(|epoch| ("epoch", epoch.to_string())).into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockResponse, MockServer};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn beacon_endpoints() {
+        let root = B256::with_last_byte(1);
+        let server = MockServer::spawn(vec![
+            MockResponse::json(json!({ "data": { "root": root } })),
+            MockResponse::json(json!({ "data": [] })),
+            MockResponse::json(json!({
+                "execution_optimistic": false,
+                "dependent_root": root,
+                "data": []
+            })),
+            MockResponse::raw(206, "application/json", ""),
+            MockResponse::raw(200, "application/octet-stream", "\x01\x02"),
+        ])
+        .await;
+        let beacon = BeaconClient::new(server.url());
+
+        assert_eq!(beacon.block_root(BlockId::Finalized).await.unwrap(), root);
+        assert!(beacon.blob_sidecars(BlockId::Slot(10), &[0, 2]).await.unwrap().is_empty());
+        assert!(beacon.attester_duties(3, &[1, 2]).await.unwrap().data.is_empty());
+        assert_eq!(beacon.health().await.unwrap(), HealthStatus::Syncing);
+        let block = beacon.block_ssz(BlockId::Root(root)).await.unwrap();
+        assert_eq!(block.body, vec![1, 2]);
+
+        let requests = server.requests();
+        assert_eq!(requests[0].path, "/eth/v1/beacon/blocks/finalized/root");
+        assert_eq!(requests[1].path, "/eth/v1/beacon/blob_sidecars/10?indices=0%2C2");
+        assert_eq!(requests[2].method, "POST");
+        assert_eq!(requests[2].path, "/eth/v1/validator/duties/attester/3");
+        assert_eq!(serde_json::from_slice::<Vec<String>>(&requests[2].body).unwrap(), ["1", "2"]);
+        assert_eq!(requests[4].path, format!("/eth/v2/beacon/blocks/{root}"));
+        assert_eq!(requests[4].header("accept"), Some("application/octet-stream"));
+    }
+
+    #[tokio::test]
+    async fn event_stream() {
+        let body = concat!(
+            ": connected\n\n",
+            "event: finalized_checkpoint\n",
+            "data: {\"block\":\"0x9a2fefd2fdb57f74993c7780ea5b9030d2897b615b89f808011ca5aebed54eaf\",\"state\":\"0x600e852a08c1200654ddf11025f1ceacb3c2e74bdd5c630cde0838b2591b69f9\",\"epoch\":\"2\",\"execution_optimistic\":false}\n\n",
+            "event: unknown_topic\n",
+            "data: {}\n\n",
+        );
+        let server =
+            MockServer::spawn(vec![MockResponse::raw(200, "text/event-stream", body)]).await;
+        let beacon = BeaconClient::new(server.url());
+
+        let mut events = beacon
+            .events(&[BeaconNodeEventTopic::Head, BeaconNodeEventTopic::FinalizedCheckpoint])
+            .await
+            .unwrap();
+        let event = events.next_event().await.unwrap().unwrap();
+        assert_eq!(event.topic(), Some(BeaconNodeEventTopic::FinalizedCheckpoint));
+        let event = events.next_event().await.unwrap().unwrap();
+        assert_eq!(
+            event,
+            BeaconEvent::Unknown { event: "unknown_topic".to_string(), data: "{}".to_string() }
+        );
+        assert!(events.next_event().await.is_none());
+
+        let requests = server.requests();
+        assert_eq!(requests[0].path, "/eth/v1/events?topics=head%2Cfinalized_checkpoint");
+        assert_eq!(requests[0].header("accept"), Some("text/event-stream"));
+    }
+}
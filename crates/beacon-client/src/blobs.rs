@@ -0,0 +1,534 @@
+//! Retrieval of the blobs of an execution block from a beacon node.
+//!
+//! The execution block is mapped to its beacon block by its timestamp, and the beacon block is
+//! checked against the `parent_beacon_block_root` of the execution block. Blobs are fetched from
+//! `/eth/v1/beacon/blob_sidecars` before the Fulu fork, and from `/eth/v1/beacon/blobs` after it,
+//! where nodes reconstruct them from the [EIP-7594] cells they custody.
+//!
+//! [EIP-7594]: https://eips.ethereum.org/EIPS/eip-7594
+
+use crate::{BeaconClient, ClientError};
+use alloy_consensus::{BlockHeader, Transaction};
+use alloy_eips::eip4844::{
+    c_kzg, env_settings::EnvKzgSettings, kzg_to_versioned_hash, AsCkzg, Blob,
+    BlobTransactionValidationError, IndexedBlobHash,
+};
+use alloy_primitives::B256;
+use alloy_rpc_types_beacon::{
+    header::{BeaconBlockHeader, HeaderData},
+    id::BlockId,
+    sidecar::BlobData,
+};
+use sha2::{Digest, Sha256};
+
+/// The depth of the inclusion proof of a KZG commitment in the beacon block body.
+const KZG_COMMITMENT_INCLUSION_PROOF_DEPTH: usize = 17;
+
+/// The generalized index of `blob_kzg_commitments[0]` in the beacon block body, relative to the
+/// depth of the inclusion proof.
+const KZG_COMMITMENT_SUBTREE_INDEX: u64 = 54 * 4096 - (1 << KZG_COMMITMENT_INCLUSION_PROOF_DEPTH);
+
+/// Errors that can occur when fetching blobs, see [`BlobFetcher`].
+#[derive(Debug, thiserror::Error)]
+pub enum BlobError {
+    /// An error of the beacon node API.
+    #[error(transparent)]
+    Client(#[from] ClientError),
+    /// The execution block predates the Cancun fork.
+    #[error("block has no parent beacon block root")]
+    MissingParentBeaconBlockRoot,
+    /// The execution block predates the beacon chain genesis.
+    #[error("block timestamp {0} is before the beacon chain genesis")]
+    BeforeGenesis(u64),
+    /// The seconds per slot or slots per epoch of the [`BlobFetcher`] are zero.
+    #[error("{0} is zero")]
+    ZeroSlotTiming(&'static str),
+    /// The beacon block at the slot of the execution block does not descend from the parent
+    /// beacon block root of the execution block.
+    #[error("beacon block at slot {slot} has parent root {got}, expected {expected}")]
+    ParentRootMismatch {
+        /// The slot of the beacon block.
+        slot: u64,
+        /// The parent beacon block root of the execution block.
+        expected: B256,
+        /// The parent root of the beacon block.
+        got: B256,
+    },
+    /// The root returned for the beacon block does not match its header.
+    #[error("beacon block root {got} does not match its header root {expected}")]
+    BlockRootMismatch {
+        /// The root of the header.
+        expected: B256,
+        /// The root returned by the node.
+        got: B256,
+    },
+    /// The node did not return a requested blob.
+    #[error("missing blob {0}")]
+    MissingBlob(u64),
+    /// A blob sidecar belongs to a different beacon block.
+    #[error("blob sidecar {0} belongs to a different block")]
+    SidecarHeaderMismatch(u64),
+    /// The inclusion proof of a blob sidecar is invalid.
+    #[error("invalid inclusion proof for blob sidecar {0}")]
+    InvalidInclusionProof(u64),
+    /// A blob does not match its versioned hash or KZG proof.
+    #[error("invalid blob {index}: {source}")]
+    InvalidBlob {
+        /// The index of the blob in the block.
+        index: u64,
+        /// The validation error.
+        source: BlobTransactionValidationError,
+    },
+    /// A KZG commitment could not be computed for a blob.
+    #[error("KZG error: {0:?}")]
+    Kzg(c_kzg::Error),
+}
+
+/// Fetches and verifies the blobs of execution blocks from a beacon node.
+///
+/// ```no_run
+/// # async fn example(
+/// #     header: alloy_consensus::Header,
+/// #     transactions: Vec<alloy_consensus::TxEnvelope>,
+/// # ) -> Result<(), alloy_beacon_client::blobs::BlobError> {
+/// use alloy_beacon_client::{blobs::BlobFetcher, BeaconClient};
+///
+/// let beacon = BeaconClient::new("http://localhost:5052".parse().unwrap());
+/// let fetcher = BlobFetcher::connect(beacon).await?;
+/// let blobs = fetcher.block_blobs(&header, &transactions).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct BlobFetcher {
+    beacon: BeaconClient,
+    genesis_time: u64,
+    seconds_per_slot: u64,
+    slots_per_epoch: u64,
+    fulu_fork_epoch: u64,
+    kzg_settings: EnvKzgSettings,
+}
+
+impl BlobFetcher {
+    /// Creates a new fetcher for a chain with the given genesis time and mainnet slot timing,
+    /// without a scheduled Fulu fork.
+    pub const fn new(beacon: BeaconClient, genesis_time: u64) -> Self {
+        Self {
+            beacon,
+            genesis_time,
+            seconds_per_slot: 12,
+            slots_per_epoch: 32,
+            fulu_fork_epoch: u64::MAX,
+            kzg_settings: EnvKzgSettings::Default,
+        }
+    }
+
+    /// Creates a new fetcher, reading the genesis time, slot timing and Fulu fork epoch from the
+    /// beacon node.
+    pub async fn connect(beacon: BeaconClient) -> Result<Self, ClientError> {
+        let genesis = beacon.genesis().await?;
+        let spec = beacon.spec().await?;
+        let value = |key: &str| spec.data.get(key).and_then(|value| value.parse().ok());
+
+        let mut fetcher = Self::new(beacon, genesis.data.genesis_time);
+        if let Some(seconds_per_slot) = value("SECONDS_PER_SLOT") {
+            fetcher.seconds_per_slot = seconds_per_slot;
+        }
+        if let Some(slots_per_epoch) = value("SLOTS_PER_EPOCH") {
+            fetcher.slots_per_epoch = slots_per_epoch;
+        }
+        if let Some(fulu_fork_epoch) = value("FULU_FORK_EPOCH") {
+            fetcher.fulu_fork_epoch = fulu_fork_epoch;
+        }
+        Ok(fetcher)
+    }
+
+    /// Sets the duration of a slot in seconds.
+    pub const fn with_seconds_per_slot(mut self, seconds_per_slot: u64) -> Self {
+        self.seconds_per_slot = seconds_per_slot;
+        self
+    }
+
+    /// Sets the number of slots per epoch.
+    pub const fn with_slots_per_epoch(mut self, slots_per_epoch: u64) -> Self {
+        self.slots_per_epoch = slots_per_epoch;
+        self
+    }
+
+    /// Sets the epoch of the Fulu fork, from which blobs are fetched from `/eth/v1/beacon/blobs`.
+    pub const fn with_fulu_fork_epoch(mut self, fulu_fork_epoch: u64) -> Self {
+        self.fulu_fork_epoch = fulu_fork_epoch;
+        self
+    }
+
+    /// Sets the KZG settings used to verify blobs.
+    pub fn with_kzg_settings(mut self, kzg_settings: EnvKzgSettings) -> Self {
+        self.kzg_settings = kzg_settings;
+        self
+    }
+
+    /// Returns the beacon client.
+    pub const fn beacon(&self) -> &BeaconClient {
+        &self.beacon
+    }
+
+    /// Returns the slot of the beacon block that contains the execution block with the given
+    /// timestamp.
+    pub const fn slot(&self, timestamp: u64) -> Result<u64, BlobError> {
+        let Some(elapsed) = timestamp.checked_sub(self.genesis_time) else {
+            return Err(BlobError::BeforeGenesis(timestamp));
+        };
+        match elapsed.checked_div(self.seconds_per_slot) {
+            Some(slot) => Ok(slot),
+            None => Err(BlobError::ZeroSlotTiming("seconds per slot")),
+        }
+    }
+
+    /// Returns the header of the beacon block that contains the given execution block.
+    pub async fn beacon_block<H: BlockHeader>(&self, header: &H) -> Result<HeaderData, BlobError> {
+        let parent_root =
+            header.parent_beacon_block_root().ok_or(BlobError::MissingParentBeaconBlockRoot)?;
+        let slot = self.slot(header.timestamp())?;
+
+        let block = self.beacon.header(BlockId::Slot(slot)).await?.data;
+        let message = &block.header.message;
+        if message.parent_root != parent_root {
+            return Err(BlobError::ParentRootMismatch {
+                slot,
+                expected: parent_root,
+                got: message.parent_root,
+            });
+        }
+        let root = header_root(message);
+        if block.root != root {
+            return Err(BlobError::BlockRootMismatch { expected: root, got: block.root });
+        }
+        Ok(block)
+    }
+
+    /// Fetches the blobs of all blob transactions of the given execution block, in transaction
+    /// order.
+    pub async fn block_blobs<H: BlockHeader, T: Transaction>(
+        &self,
+        header: &H,
+        transactions: &[T],
+    ) -> Result<Vec<Blob>, BlobError> {
+        self.blobs(header, &indexed_blob_hashes(transactions)).await
+    }
+
+    /// Fetches the blobs with the given versioned hashes of the given execution block, in the
+    /// order of the hashes.
+    ///
+    /// The index of each hash is the position of the blob in the block, see
+    /// [`indexed_blob_hashes`].
+    pub async fn blobs<H: BlockHeader>(
+        &self,
+        header: &H,
+        hashes: &[IndexedBlobHash],
+    ) -> Result<Vec<Blob>, BlobError> {
+        if hashes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let block = self.beacon_block(header).await?;
+        let epoch = block
+            .header
+            .message
+            .slot
+            .checked_div(self.slots_per_epoch)
+            .ok_or(BlobError::ZeroSlotTiming("slots per epoch"))?;
+        if epoch >= self.fulu_fork_epoch {
+            self.reconstructed_blobs(block.root, hashes).await
+        } else {
+            self.sidecar_blobs(&block, hashes).await
+        }
+    }
+
+    /// Fetches blob sidecars and verifies their inclusion proofs and KZG proofs.
+    async fn sidecar_blobs(
+        &self,
+        block: &HeaderData,
+        hashes: &[IndexedBlobHash],
+    ) -> Result<Vec<Blob>, BlobError> {
+        let indices = hashes.iter().map(|hash| hash.index).collect::<Vec<_>>();
+        let sidecars = self.beacon.blob_sidecars(BlockId::Root(block.root), &indices).await?;
+
+        // blobs are cloned in place, moving them through the stack can overflow it
+        let mut blobs = Vec::with_capacity(hashes.len());
+        for hash in hashes {
+            let sidecar =
+                sidecars.get_blob(hash.index).ok_or(BlobError::MissingBlob(hash.index))?;
+            self.verify_sidecar(sidecar, &block.header.message, hash)?;
+            blobs.extend_from_slice(std::slice::from_ref(sidecar.blob.as_ref()));
+        }
+        Ok(blobs)
+    }
+
+    fn verify_sidecar(
+        &self,
+        sidecar: &BlobData,
+        header: &BeaconBlockHeader,
+        hash: &IndexedBlobHash,
+    ) -> Result<(), BlobError> {
+        if sidecar.signed_block_header.message != *header {
+            return Err(BlobError::SidecarHeaderMismatch(sidecar.index));
+        }
+        if !verify_inclusion_proof(sidecar) {
+            return Err(BlobError::InvalidInclusionProof(sidecar.index));
+        }
+
+        let invalid = |source| BlobError::InvalidBlob { index: hash.index, source };
+        let versioned_hash = kzg_to_versioned_hash(sidecar.kzg_commitment.as_slice());
+        if versioned_hash != hash.hash {
+            return Err(invalid(BlobTransactionValidationError::WrongVersionedHash {
+                have: versioned_hash,
+                expected: hash.hash,
+            }));
+        }
+        let valid = self
+            .kzg_settings
+            .get()
+            .verify_blob_kzg_proof(
+                sidecar.blob.as_ckzg(),
+                sidecar.kzg_commitment.as_ckzg(),
+                sidecar.kzg_proof.as_ckzg(),
+            )
+            .map_err(|err| invalid(BlobTransactionValidationError::KZGError(err)))?;
+        valid.then_some(()).ok_or_else(|| invalid(BlobTransactionValidationError::InvalidProof))
+    }
+
+    /// Fetches blobs reconstructed from cells and verifies them against their versioned hashes.
+    async fn reconstructed_blobs(
+        &self,
+        root: B256,
+        hashes: &[IndexedBlobHash],
+    ) -> Result<Vec<Blob>, BlobError> {
+        let requested = hashes.iter().map(|hash| hash.hash).collect::<Vec<_>>();
+        let blobs = self.beacon.blobs(BlockId::Root(root), &requested).await?.data;
+
+        let mut versioned_hashes = Vec::with_capacity(blobs.len());
+        for blob in &blobs {
+            let commitment = self
+                .kzg_settings
+                .get()
+                .blob_to_kzg_commitment(blob.as_ckzg())
+                .map_err(BlobError::Kzg)?;
+            versioned_hashes.push(kzg_to_versioned_hash(commitment.to_bytes().as_slice()));
+        }
+
+        let mut ordered = Vec::with_capacity(hashes.len());
+        for hash in hashes {
+            let position = versioned_hashes
+                .iter()
+                .position(|versioned_hash| *versioned_hash == hash.hash)
+                .ok_or(BlobError::MissingBlob(hash.index))?;
+            ordered.extend_from_slice(&blobs[position..=position]);
+        }
+        Ok(ordered)
+    }
+}
+
+/// Returns the versioned hashes of the blobs of the given transactions, indexed by their position
+/// in the block.
+pub fn indexed_blob_hashes<T: Transaction>(transactions: &[T]) -> Vec<IndexedBlobHash> {
+    transactions
+        .iter()
+        .filter_map(|tx| tx.blob_versioned_hashes())
+        .flatten()
+        .enumerate()
+        .map(|(index, hash)| IndexedBlobHash { index: index as u64, hash: *hash })
+        .collect()
+}
+
+/// Verifies the inclusion proof of the KZG commitment of a blob sidecar against the body root of
+/// its block header.
+///
+/// See [`verify_blob_sidecar_inclusion_proof`](https://github.com/ethereum/consensus-specs/blob/v1.5.0/specs/deneb/p2p-interface.md#verify_blob_sidecar_inclusion_proof)
+/// in the CL spec.
+pub fn verify_inclusion_proof(sidecar: &BlobData) -> bool {
+    let proof = &sidecar.kzg_commitment_inclusion_proof;
+    if proof.len() != KZG_COMMITMENT_INCLUSION_PROOF_DEPTH {
+        return false;
+    }
+
+    let commitment = sidecar.kzg_commitment.as_slice();
+    let mut last_chunk = [0u8; 32];
+    last_chunk[..16].copy_from_slice(&commitment[32..]);
+    let leaf = hash_pair(&B256::from_slice(&commitment[..32]), &B256::from(last_chunk));
+
+    let index = KZG_COMMITMENT_SUBTREE_INDEX + sidecar.index;
+    let root = proof.iter().enumerate().fold(leaf, |node, (depth, sibling)| {
+        if (index >> depth) & 1 == 1 {
+            hash_pair(sibling, &node)
+        } else {
+            hash_pair(&node, sibling)
+        }
+    });
+    root == sidecar.signed_block_header.message.body_root
+}
+
+/// Returns the hash tree root of a beacon block header, which is the root of the block.
+pub fn header_root(header: &BeaconBlockHeader) -> B256 {
+    let mut chunks = [B256::ZERO; 8];
+    chunks[0][..8].copy_from_slice(&header.slot.to_le_bytes());
+    chunks[1][..8].copy_from_slice(&header.proposer_index.to_le_bytes());
+    chunks[2] = header.parent_root;
+    chunks[3] = header.state_root;
+    chunks[4] = header.body_root;
+
+    let mut layer = chunks.to_vec();
+    while layer.len() > 1 {
+        layer = layer.chunks(2).map(|pair| hash_pair(&pair[0], &pair[1])).collect();
+    }
+    layer[0]
+}
+
+fn hash_pair(left: &B256, right: &B256) -> B256 {
+    B256::from_slice(&Sha256::new().chain_update(left).chain_update(right).finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockResponse, MockServer};
+    use alloy_consensus::Header;
+    use alloy_rpc_types_beacon::sidecar::BeaconBlobBundle;
+    use serde_json::json;
+
+    const GENESIS_TIME: u64 = 1_606_824_023;
+
+    fn bundle() -> BeaconBlobBundle {
+        serde_json::from_str(include_str!("../../rpc-types-beacon/src/examples/sidecar.json"))
+            .unwrap()
+    }
+
+    fn header_response(message: &BeaconBlockHeader) -> MockResponse {
+        MockResponse::json(json!({
+            "execution_optimistic": false,
+            "finalized": true,
+            "data": {
+                "root": header_root(message),
+                "canonical": true,
+                "header": { "message": message, "signature": format!("0x{}", "00".repeat(96)) }
+            }
+        }))
+    }
+
+    #[test]
+    fn slot() {
+        let beacon = BeaconClient::new("http://localhost:5052".parse().unwrap());
+        let fetcher = BlobFetcher::new(beacon, GENESIS_TIME);
+        assert_eq!(fetcher.slot(GENESIS_TIME + 25).unwrap(), 2);
+        assert!(matches!(fetcher.slot(GENESIS_TIME - 1), Err(BlobError::BeforeGenesis(_))));
+
+        let fetcher = fetcher.with_seconds_per_slot(0);
+        assert!(matches!(fetcher.slot(GENESIS_TIME), Err(BlobError::ZeroSlotTiming(_))));
+    }
+
+    #[test]
+    fn inclusion_proofs() {
+        let mut bundle = bundle();
+        assert!(bundle.data.iter().all(verify_inclusion_proof));
+
+        bundle.data[0].index = 1;
+        assert!(!verify_inclusion_proof(&bundle.data[0]));
+    }
+
+    #[tokio::test]
+    async fn fetch_sidecar_blobs() {
+        let bundle = bundle();
+        let message = bundle.data[0].signed_block_header.message.clone();
+        let hashes = [3, 1]
+            .map(|index: usize| IndexedBlobHash {
+                index: index as u64,
+                hash: kzg_to_versioned_hash(bundle.data[index].kzg_commitment.as_slice()),
+            })
+            .to_vec();
+
+        let server = MockServer::spawn(vec![
+            header_response(&message),
+            MockResponse::json(BeaconBlobBundle::new(vec![
+                bundle.data[1].clone(),
+                bundle.data[3].clone(),
+            ])),
+            header_response(&message),
+            MockResponse::json(BeaconBlobBundle::new(vec![
+                bundle.data[1].clone(),
+                bundle.data[1].clone(),
+            ])),
+        ])
+        .await;
+        let fetcher = BlobFetcher::new(BeaconClient::new(server.url()), GENESIS_TIME);
+
+        let header = Header {
+            timestamp: GENESIS_TIME + message.slot * 12 + 5,
+            parent_beacon_block_root: Some(message.parent_root),
+            ..Default::default()
+        };
+        let blobs = fetcher.blobs(&header, &hashes).await.unwrap();
+        assert_eq!(blobs.len(), 2);
+        assert!(blobs[0] == *bundle.data[3].blob && blobs[1] == *bundle.data[1].blob);
+
+        let err = fetcher.blobs(&header, &hashes).await.unwrap_err();
+        assert!(matches!(err, BlobError::MissingBlob(3)));
+
+        let requests = server.requests();
+        assert_eq!(requests[0].path, format!("/eth/v1/beacon/headers/{}", message.slot));
+        assert_eq!(
+            requests[1].path,
+            format!("/eth/v1/beacon/blob_sidecars/{}?indices=3%2C1", header_root(&message))
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_reconstructed_blobs() {
+        let bundle = bundle();
+        let message = bundle.data[0].signed_block_header.message.clone();
+        let hash = IndexedBlobHash {
+            index: 2,
+            hash: kzg_to_versioned_hash(bundle.data[2].kzg_commitment.as_slice()),
+        };
+
+        let server = MockServer::spawn(vec![
+            header_response(&message),
+            MockResponse::json(json!({ "data": [bundle.data[2].blob] })),
+        ])
+        .await;
+        let fetcher =
+            BlobFetcher::new(BeaconClient::new(server.url()), GENESIS_TIME).with_fulu_fork_epoch(0);
+
+        let header = Header {
+            timestamp: GENESIS_TIME + message.slot * 12,
+            parent_beacon_block_root: Some(message.parent_root),
+            ..Default::default()
+        };
+        let blobs = fetcher.blobs(&header, std::slice::from_ref(&hash)).await.unwrap();
+        assert!(blobs.len() == 1 && blobs[0] == *bundle.data[2].blob);
+
+        let requests = server.requests();
+        assert_eq!(
+            requests[1].path,
+            format!(
+                "/eth/v1/beacon/blobs/{}?versioned_hashes={}",
+                header_root(&message),
+                hash.hash
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn parent_root_mismatch() {
+        let message = bundle().data[0].signed_block_header.message.clone();
+        let server = MockServer::spawn(vec![header_response(&message)]).await;
+        let fetcher = BlobFetcher::new(BeaconClient::new(server.url()), GENESIS_TIME);
+
+        let header = Header {
+            timestamp: GENESIS_TIME + message.slot * 12,
+            parent_beacon_block_root: Some(B256::ZERO),
+            ..Default::default()
+        };
+        let hashes = [IndexedBlobHash { index: 0, hash: B256::ZERO }];
+        let err = fetcher.blobs(&header, &hashes).await.unwrap_err();
+        assert!(matches!(err, BlobError::ParentRootMismatch { expected: B256::ZERO, .. }));
+    }
+}
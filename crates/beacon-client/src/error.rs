@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The error message returned by beacon node and relay APIs for unsuccessful requests.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiErrorMessage {
+    /// The HTTP status code.
+    pub code: u16,
+    /// The error message.
+    pub message: String,
+}
+
+impl fmt::Display for ApiErrorMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.message, self.code)
+    }
+}
+
+/// Errors that may occur when talking to a beacon node or relay.
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    /// The HTTP request failed.
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    /// The endpoint URL could not be constructed.
+    #[error(transparent)]
+    Url(#[from] url::ParseError),
+    /// The response could not be deserialized.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// The request body could not be compressed.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// The SSZ response could not be decoded.
+    #[cfg(feature = "ssz")]
+    #[error("invalid SSZ response: {0:?}")]
+    Ssz(ssz::DecodeError),
+    /// The server responded with an error status.
+    #[error("server responded with {0}")]
+    Api(ApiErrorMessage),
+}
+
+impl ClientError {
+    /// Returns the HTTP status code if the server responded with an error status.
+    pub const fn status(&self) -> Option<u16> {
+        match self {
+            Self::Api(err) => Some(err.code),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{ApiErrorMessage, ClientError};
+use flate2::{write::GzEncoder, Compression};
+use reqwest::{
+    header::{ACCEPT, CONTENT_ENCODING, CONTENT_TYPE},
+    RequestBuilder, Response,
+};
+use serde::de::DeserializeOwned;
+use std::io::Write;
+use url::Url;
+
+/// The encoding of request and response bodies.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// JSON, the default encoding of all APIs.
+    #[default]
+    Json,
+    /// SSZ, supported by some endpoints for large payloads.
+    Ssz,
+}
+
+impl Encoding {
+    /// Returns the content type of the encoding.
+    pub const fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Ssz => "application/octet-stream",
+        }
+    }
+
+    /// Returns the encoding of a `Content-Type` header value, ignoring any parameters.
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        match content_type.split(';').next()?.trim() {
+            "application/json" => Some(Self::Json),
+            "application/octet-stream" => Some(Self::Ssz),
+            _ => None,
+        }
+    }
+}
+
+/// The `Accept` header of requests that prefer SSZ but also accept JSON responses.
+#[cfg(feature = "ssz")]
+const ACCEPT_SSZ_OR_JSON: &str = "application/octet-stream;q=1, application/json;q=0.9";
+
+/// A thin wrapper around [`reqwest::Client`] that resolves endpoints against a base URL and turns
+/// error responses into [`ClientError::Api`].
+#[derive(Clone, Debug)]
+pub(crate) struct HttpClient {
+    client: reqwest::Client,
+    url: Url,
+}
+
+impl HttpClient {
+    pub(crate) fn new(url: Url) -> Self {
+        Self { client: reqwest::Client::new(), url }
+    }
+
+    pub(crate) fn with_client(self, client: reqwest::Client) -> Self {
+        Self { client, ..self }
+    }
+
+    pub(crate) const fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Returns the URL of the endpoint at `path`, relative to the path of the base URL.
+    fn endpoint(&self, path: &str) -> Result<Url, ClientError> {
+        let base = self.url.as_str().trim_end_matches('/');
+        Ok(Url::parse(&format!("{base}{path}"))?)
+    }
+
+    /// Starts a `GET` request that accepts the given encoding.
+    pub(crate) fn get(&self, path: &str, accept: Encoding) -> Result<RequestBuilder, ClientError> {
+        Ok(self.client.get(self.endpoint(path)?).header(ACCEPT, accept.content_type()))
+    }
+
+    /// Starts a `GET` request that prefers an SSZ response, but also accepts JSON, see
+    /// [`send_negotiated`](Self::send_negotiated).
+    #[cfg(feature = "ssz")]
+    pub(crate) fn get_negotiated(&self, path: &str) -> Result<RequestBuilder, ClientError> {
+        Ok(self.client.get(self.endpoint(path)?).header(ACCEPT, ACCEPT_SSZ_OR_JSON))
+    }
+
+    /// Starts a `GET` request for a `text/event-stream` response.
+    pub(crate) fn get_event_stream(&self, path: &str) -> Result<RequestBuilder, ClientError> {
+        Ok(self.client.get(self.endpoint(path)?).header(ACCEPT, "text/event-stream"))
+    }
+
+    /// Starts a `POST` request with the given body, optionally gzip compressed.
+    pub(crate) fn post(
+        &self,
+        path: &str,
+        body: Vec<u8>,
+        encoding: Encoding,
+        gzip: bool,
+    ) -> Result<RequestBuilder, ClientError> {
+        let request =
+            self.client.post(self.endpoint(path)?).header(CONTENT_TYPE, encoding.content_type());
+        if !gzip {
+            return Ok(request.body(body));
+        }
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&body)?;
+        Ok(request.header(CONTENT_ENCODING, "gzip").body(encoder.finish()?))
+    }
+
+    /// Sends the request and returns the response if it has a success status.
+    pub(crate) async fn send(&self, request: RequestBuilder) -> Result<Response, ClientError> {
+        let response = request.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let body = response.bytes().await?;
+        let error =
+            serde_json::from_slice::<ApiErrorMessage>(&body).unwrap_or_else(|_| ApiErrorMessage {
+                code: status.as_u16(),
+                message: String::from_utf8_lossy(&body).into_owned(),
+            });
+        Err(ClientError::Api(error))
+    }
+
+    /// Sends the request and deserializes the JSON response.
+    pub(crate) async fn send_json<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<T, ClientError> {
+        let body = self.send(request).await?.bytes().await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// Sends the request and decodes the response according to its `Content-Type`, as SSZ or
+    /// JSON. Responses without a known content type are decoded as JSON.
+    #[cfg(feature = "ssz")]
+    pub(crate) async fn send_negotiated<T: DeserializeOwned + ssz::Decode>(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<T, ClientError> {
+        let response = self.send(request).await?;
+        let encoding = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .and_then(Encoding::from_content_type)
+            .unwrap_or_default();
+        let body = response.bytes().await?;
+        match encoding {
+            Encoding::Json => Ok(serde_json::from_slice(&body)?),
+            Encoding::Ssz => T::from_ssz_bytes(&body).map_err(ClientError::Ssz),
+        }
+    }
+}
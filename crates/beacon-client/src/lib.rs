@@ -0,0 +1,29 @@
+#![doc = include_str!("../README.md")]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/alloy-rs/core/main/assets/alloy.jpg",
+    html_favicon_url = "https://raw.githubusercontent.com/alloy-rs/core/main/assets/favicon.ico"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
+mod error;
+pub use error::{ApiErrorMessage, ClientError};
+
+mod http;
+pub use http::Encoding;
+
+/// Client for the [beacon node API](https://ethereum.github.io/beacon-APIs).
+pub mod beacon;
+pub use beacon::{BeaconClient, BeaconEventStream, SszResponse};
+
+/// Retrieval and verification of the blobs of execution blocks.
+#[cfg(feature = "kzg")]
+pub mod blobs;
+
+/// Client for the [MEV-Boost relay](https://flashbots.github.io/relay-specs) data, builder and
+/// proposer APIs.
+pub mod relay;
+pub use relay::RelayClient;
+
+#[cfg(test)]
+mod mock;
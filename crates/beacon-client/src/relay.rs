@@ -0,0 +1,382 @@
+//! See <https://flashbots.github.io/relay-specs> for the relay API specification.
+
+use crate::{http::HttpClient, ClientError, Encoding};
+use alloy_rpc_types_beacon::{
+    relay::{
+        BuilderBlockReceived, BuilderBlocksReceivedQuery, ProposerPayloadDelivered,
+        ProposerPayloadsDeliveredQuery, SubmitBlockRequest, SubmitBlockRequestQuery, Validator,
+        ValidatorRegistration,
+    },
+    BlsPublicKey,
+};
+use serde::de::DeserializeOwned;
+use url::Url;
+
+/// A client for the data, builder and proposer APIs of a MEV-Boost relay.
+///
+/// ```no_run
+/// # async fn example() -> Result<(), alloy_beacon_client::ClientError> {
+/// use alloy_beacon_client::RelayClient;
+/// use alloy_rpc_types_beacon::relay::ProposerPayloadsDeliveredQuery;
+///
+/// let relay = RelayClient::new("https://boost-relay.flashbots.net".parse().unwrap());
+/// let delivered =
+///     relay.proposer_payloads_delivered(&ProposerPayloadsDeliveredQuery::default()).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct RelayClient {
+    http: HttpClient,
+    gzip: bool,
+}
+
+impl RelayClient {
+    /// Creates a new client for the relay at the given URL.
+    pub fn new(url: Url) -> Self {
+        Self { http: HttpClient::new(url), gzip: false }
+    }
+
+    /// Sets the underlying [`reqwest::Client`], e.g. to configure timeouts.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.http = self.http.with_client(client);
+        self
+    }
+
+    /// Sets whether block submissions are gzip compressed.
+    ///
+    /// Responses are always decompressed if the relay compresses them.
+    pub const fn with_gzip(mut self, gzip: bool) -> Self {
+        self.gzip = gzip;
+        self
+    }
+
+    /// Returns the URL of the relay.
+    pub const fn url(&self) -> &Url {
+        self.http.url()
+    }
+
+    /// Fetches an endpoint whose response has an SSZ encoding, preferring SSZ over JSON.
+    #[cfg(feature = "ssz")]
+    async fn get_negotiated<T: DeserializeOwned + ssz::Decode>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, ClientError> {
+        let request = self.http.get_negotiated(path)?.query(query);
+        self.http.send_negotiated(request).await
+    }
+
+    /// Fetches an endpoint whose response has an SSZ encoding, as JSON since SSZ is disabled.
+    #[cfg(not(feature = "ssz"))]
+    async fn get_negotiated<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, ClientError> {
+        let request = self.http.get(path, Encoding::Json)?.query(query);
+        self.http.send_json(request).await
+    }
+
+    /// Returns the payloads delivered to proposers, matching the query.
+    ///
+    /// See also [`proposer_payloads_delivered_pages`](Self::proposer_payloads_delivered_pages) to
+    /// fetch more entries than the relay returns at once.
+    pub async fn proposer_payloads_delivered(
+        &self,
+        query: &ProposerPayloadsDeliveredQuery,
+    ) -> Result<Vec<ProposerPayloadDelivered>, ClientError> {
+        let request = self
+            .http
+            .get("/relay/v1/data/bidtraces/proposer_payload_delivered", Encoding::Json)?
+            .query(query);
+        self.http.send_json(request).await
+    }
+
+    /// Returns a paginator over the payloads delivered to proposers, from the latest slot
+    /// matching the query backwards.
+    ///
+    /// The `cursor` of the query is advanced after every page, so the query must not be ordered
+    /// by value.
+    pub const fn proposer_payloads_delivered_pages(
+        &self,
+        query: ProposerPayloadsDeliveredQuery,
+    ) -> ProposerPayloadsDeliveredPages<'_> {
+        ProposerPayloadsDeliveredPages { client: self, query, done: false }
+    }
+
+    /// Returns the blocks received from builders, matching the query.
+    pub async fn builder_blocks_received(
+        &self,
+        query: &BuilderBlocksReceivedQuery,
+    ) -> Result<Vec<BuilderBlockReceived>, ClientError> {
+        let request = self
+            .http
+            .get("/relay/v1/data/bidtraces/builder_blocks_received", Encoding::Json)?
+            .query(query);
+        self.http.send_json(request).await
+    }
+
+    /// Returns the latest validator registration of the given validator.
+    ///
+    /// With the `ssz` feature, an SSZ response is preferred.
+    pub async fn validator_registration(
+        &self,
+        pubkey: &BlsPublicKey,
+    ) -> Result<ValidatorRegistration, ClientError> {
+        self.get_negotiated(
+            "/relay/v1/data/validator_registration",
+            &[("pubkey", pubkey.to_string())],
+        )
+        .await
+    }
+
+    /// Returns the registered validators scheduled to propose in the current and next epoch.
+    ///
+    /// With the `ssz` feature, an SSZ response is preferred.
+    pub async fn validators(&self) -> Result<Vec<Validator>, ClientError> {
+        self.get_negotiated("/relay/v1/builder/validators", &[]).await
+    }
+
+    /// Submits a block to the relay, JSON encoded.
+    pub async fn submit_block(
+        &self,
+        request: &SubmitBlockRequest,
+        query: &SubmitBlockRequestQuery,
+    ) -> Result<(), ClientError> {
+        self.submit_block_encoded(serde_json::to_vec(request)?, Encoding::Json, query).await
+    }
+
+    /// Submits a block to the relay, SSZ encoded.
+    #[cfg(feature = "ssz")]
+    pub async fn submit_block_ssz(
+        &self,
+        request: &SubmitBlockRequest,
+        query: &SubmitBlockRequestQuery,
+    ) -> Result<(), ClientError> {
+        let body = ssz::Encode::as_ssz_bytes(request);
+        self.submit_block_encoded(body, Encoding::Ssz, query).await
+    }
+
+    /// Submits an already encoded block to the relay.
+    pub async fn submit_block_encoded(
+        &self,
+        body: Vec<u8>,
+        encoding: Encoding,
+        query: &SubmitBlockRequestQuery,
+    ) -> Result<(), ClientError> {
+        let request =
+            self.http.post("/relay/v1/builder/blocks", body, encoding, self.gzip)?.query(query);
+        self.http.send(request).await?;
+        Ok(())
+    }
+
+    /// Registers validators with the relay, as done by MEV-Boost on behalf of proposers.
+    pub async fn register_validators(
+        &self,
+        registrations: &[ValidatorRegistration],
+    ) -> Result<(), ClientError> {
+        let body = serde_json::to_vec(registrations)?;
+        let request = self.http.post("/eth/v1/builder/validators", body, Encoding::Json, false)?;
+        self.http.send(request).await?;
+        Ok(())
+    }
+
+    /// Checks that the relay is available.
+    pub async fn status(&self) -> Result<(), ClientError> {
+        let request = self.http.get("/eth/v1/builder/status", Encoding::Json)?;
+        self.http.send(request).await?;
+        Ok(())
+    }
+}
+
+/// Paginates over the payloads delivered to proposers, see
+/// [`RelayClient::proposer_payloads_delivered_pages`].
+#[derive(Debug)]
+pub struct ProposerPayloadsDeliveredPages<'a> {
+    client: &'a RelayClient,
+    query: ProposerPayloadsDeliveredQuery,
+    done: bool,
+}
+
+impl ProposerPayloadsDeliveredPages<'_> {
+    /// Fetches the next page, or returns `None` if all entries were fetched.
+    pub async fn next_page(
+        &mut self,
+    ) -> Result<Option<Vec<ProposerPayloadDelivered>>, ClientError> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let page = self.client.proposer_payloads_delivered(&self.query).await?;
+        let last_slot = page.iter().map(|payload| payload.slot).min();
+        let is_last_page = self.query.limit.is_some_and(|limit| (page.len() as u64) < limit);
+        match last_slot {
+            Some(slot) if slot > 0 && !is_last_page => self.query.cursor = Some(slot - 1),
+            _ => self.done = true,
+        }
+
+        Ok((!page.is_empty()).then_some(page))
+    }
+
+    /// Fetches pages until all entries were fetched, or at least `max` entries.
+    pub async fn collect(
+        mut self,
+        max: usize,
+    ) -> Result<Vec<ProposerPayloadDelivered>, ClientError> {
+        let mut payloads = Vec::new();
+        while payloads.len() < max {
+            match self.next_page().await? {
+                Some(page) => payloads.extend(page),
+                None => break,
+            }
+        }
+        Ok(payloads)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockResponse, MockServer};
+    use alloy_primitives::{Address, B256, U256};
+    use alloy_rpc_types_beacon::relay::{SignedBidSubmissionV4, ValidatorRegistrationMessage};
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    fn delivered(slot: u64) -> ProposerPayloadDelivered {
+        ProposerPayloadDelivered {
+            slot,
+            parent_hash: B256::ZERO,
+            block_hash: B256::with_last_byte(slot as u8),
+            builder_pubkey: BlsPublicKey::ZERO,
+            proposer_pubkey: BlsPublicKey::ZERO,
+            proposer_fee_recipient: Address::ZERO,
+            gas_limit: 30_000_000,
+            gas_used: 15_000_000,
+            value: U256::from(1),
+            block_number: slot,
+            num_tx: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn paginate_payloads_delivered() {
+        let server = MockServer::spawn(vec![
+            MockResponse::json(vec![delivered(10), delivered(9)]),
+            MockResponse::json(vec![delivered(7)]),
+        ])
+        .await;
+        let relay = RelayClient::new(server.url());
+
+        let query = ProposerPayloadsDeliveredQuery::default().limit(2);
+        let payloads = relay.proposer_payloads_delivered_pages(query).collect(10).await.unwrap();
+        assert_eq!(payloads, vec![delivered(10), delivered(9), delivered(7)]);
+
+        let requests = server.requests();
+        assert_eq!(requests[0].path, "/relay/v1/data/bidtraces/proposer_payload_delivered?limit=2");
+        assert_eq!(
+            requests[1].path,
+            "/relay/v1/data/bidtraces/proposer_payload_delivered?cursor=8&limit=2"
+        );
+    }
+
+    fn registration() -> ValidatorRegistration {
+        ValidatorRegistration {
+            message: ValidatorRegistrationMessage {
+                fee_recipient: Address::with_last_byte(1),
+                gas_limit: 30_000_000,
+                timestamp: 1,
+                pubkey: BlsPublicKey::with_last_byte(2),
+            },
+            signature: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn registrations_and_errors() {
+        let registration = registration();
+        let server = MockServer::spawn(vec![
+            MockResponse::json(&registration),
+            MockResponse::raw(200, "application/json", ""),
+            MockResponse::raw(
+                400,
+                "application/json",
+                r#"{"code":400,"message":"invalid signature"}"#,
+            ),
+        ])
+        .await;
+        let relay = RelayClient::new(server.url());
+
+        let fetched = relay.validator_registration(&BlsPublicKey::ZERO).await.unwrap();
+        assert_eq!(fetched, registration);
+        relay.register_validators(std::slice::from_ref(&registration)).await.unwrap();
+        let err = relay.register_validators(std::slice::from_ref(&registration)).await.unwrap_err();
+        assert_eq!(err.status(), Some(400));
+        assert!(err.to_string().contains("invalid signature"));
+
+        let requests = server.requests();
+        assert_eq!(requests[1].method, "POST");
+        assert_eq!(requests[1].path, "/eth/v1/builder/validators");
+        let body: Vec<ValidatorRegistration> = serde_json::from_slice(&requests[1].body).unwrap();
+        assert_eq!(body, vec![registration]);
+    }
+
+    #[tokio::test]
+    async fn validators_json() {
+        let validator = Validator { slot: 1, validator_index: 2, entry: registration() };
+        let server = MockServer::spawn(vec![MockResponse::json(vec![&validator])]).await;
+        let relay = RelayClient::new(server.url());
+
+        assert_eq!(relay.validators().await.unwrap(), vec![validator]);
+        let accept = server.requests()[0].header("accept").unwrap().to_string();
+        #[cfg(feature = "ssz")]
+        assert_eq!(accept, "application/octet-stream;q=1, application/json;q=0.9");
+        #[cfg(not(feature = "ssz"))]
+        assert_eq!(accept, "application/json");
+    }
+
+    #[cfg(feature = "ssz")]
+    #[tokio::test]
+    async fn validators_ssz() {
+        use ssz::Encode;
+
+        let validators = vec![Validator { slot: 1, validator_index: 2, entry: registration() }];
+        let registration = registration();
+        let server = MockServer::spawn(vec![
+            MockResponse::raw(200, "application/octet-stream", validators.as_ssz_bytes()),
+            MockResponse::raw(200, "application/octet-stream", registration.as_ssz_bytes()),
+            MockResponse::raw(200, "application/octet-stream", vec![0; 3]),
+        ])
+        .await;
+        let relay = RelayClient::new(server.url());
+
+        assert_eq!(relay.validators().await.unwrap(), validators);
+        let fetched = relay.validator_registration(&BlsPublicKey::ZERO).await.unwrap();
+        assert_eq!(fetched, registration);
+        let err = relay.validators().await.unwrap_err();
+        assert!(matches!(err, ClientError::Ssz(_)));
+    }
+
+    #[tokio::test]
+    async fn submit_block_gzip() {
+        let server = MockServer::spawn(vec![MockResponse::raw(200, "application/json", "")]).await;
+        let relay = RelayClient::new(server.url()).with_gzip(true);
+
+        let submission: SignedBidSubmissionV4 = serde_json::from_str(include_str!(
+            "../../rpc-types-beacon/src/examples/relay_builder_block_validation_request_v4.json"
+        ))
+        .unwrap();
+        let request = SubmitBlockRequest::Electra(submission);
+        relay.submit_block(&request, &SubmitBlockRequestQuery::cancellations()).await.unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests[0].path, "/relay/v1/builder/blocks?cancellations=1");
+        assert_eq!(requests[0].header("content-encoding"), Some("gzip"));
+        assert_eq!(requests[0].header("content-type"), Some("application/json"));
+
+        let mut body = Vec::new();
+        GzDecoder::new(requests[0].body.as_slice()).read_to_end(&mut body).unwrap();
+        assert_eq!(body, serde_json::to_vec(&request).unwrap());
+    }
+}
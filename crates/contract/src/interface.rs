@@ -1,9 +1,11 @@
 use crate::{ContractInstance, Error, Result};
-use alloy_dyn_abi::{DynSolValue, FunctionExt, JsonAbiExt};
-use alloy_json_abi::{Function, JsonAbi};
+use alloy_dyn_abi::{
+    DecodedError, DecodedEvent, DynSolValue, ErrorExt, EventExt, FunctionExt, JsonAbiExt,
+};
+use alloy_json_abi::{Error as AbiError, Event, Function, JsonAbi};
 use alloy_primitives::{
-    map::{FbHashMap, SelectorHashMap},
-    Address, FixedBytes, Selector,
+    map::{B256HashMap, FbHashMap, SelectorHashMap},
+    Address, FixedBytes, LogData, Selector, B256,
 };
 use std::collections::BTreeMap;
 
@@ -12,14 +14,17 @@ use std::collections::BTreeMap;
 pub struct Interface {
     abi: JsonAbi,
     functions: SelectorHashMap<(String, usize)>,
+    events: B256HashMap<(String, usize)>,
+    errors: SelectorHashMap<(String, usize)>,
 }
 
-// TODO: events/errors
 impl Interface {
     /// Creates a new contract interface from the provided ABI.
     pub fn new(abi: JsonAbi) -> Self {
         let functions = create_mapping(&abi.functions, Function::selector);
-        Self { abi, functions }
+        let events = create_mapping(&abi.events, Event::selector);
+        let errors = create_mapping(&abi.errors, AbiError::selector);
+        Self { abi, functions, events, errors }
     }
 
     /// Returns the ABI encoded data (including the selector) for the provided function and
@@ -82,6 +87,18 @@ impl Interface {
         self.get_from_selector(selector)?.abi_decode_output(data).map_err(Into::into)
     }
 
+    /// Decode the provided log according to the event matching its first topic.
+    pub fn decode_log(&self, log: &LogData) -> Result<DecodedEvent> {
+        let topic = log.topics().first().copied().unwrap_or_default();
+        self.get_event_from_topic(&topic)?.decode_log(log).map_err(Into::into)
+    }
+
+    /// Decode the provided revert data according to the custom error matching its selector.
+    pub fn decode_error(&self, data: &[u8]) -> Result<DecodedError> {
+        let selector = data.get(..4).map(Selector::from_slice).unwrap_or_default();
+        self.get_error_from_selector(&selector)?.decode_error(data).map_err(Into::into)
+    }
+
     /// Returns a reference to the contract's ABI.
     pub const fn abi(&self) -> &JsonAbi {
         &self.abi
@@ -106,12 +123,32 @@ impl Interface {
             .ok_or_else(|| Error::UnknownSelector(*selector))
     }
 
+    pub(crate) fn get_event_from_topic(&self, topic: &B256) -> Result<&Event> {
+        self.events
+            .get(topic)
+            .map(|(name, index)| &self.abi.events[name][*index])
+            .ok_or_else(|| unknown(format!("event with topic {topic} does not exist")))
+    }
+
+    pub(crate) fn get_error_from_selector(&self, selector: &Selector) -> Result<&AbiError> {
+        self.errors
+            .get(selector)
+            .map(|(name, index)| &self.abi.errors[name][*index])
+            .ok_or_else(|| unknown(format!("error with selector {selector} does not exist")))
+    }
+
     /// Create a [`ContractInstance`] from this ABI for a contract at the given address.
     pub const fn connect<P, N>(self, address: Address, provider: P) -> ContractInstance<P, N> {
         ContractInstance::new(address, provider, self)
     }
 }
 
+/// Returns the error for an event or custom error that is not in the ABI.
+#[cold]
+fn unknown(message: String) -> Error {
+    alloy_dyn_abi::Error::custom(message).into()
+}
+
 /// Utility function for creating a mapping between a unique signature and a
 /// name-index pair for accessing contract ABI items.
 fn create_mapping<const N: usize, T, F>(
@@ -35,6 +35,9 @@ pub use call::*;
 
 mod multicall;
 
+#[cfg(feature = "trace")]
+pub mod trace;
+
 // Not public API.
 // NOTE: please avoid changing the API of this module due to its use in the `sol!` macro.
 #[doc(hidden)]
@@ -0,0 +1,196 @@
+use super::{DecodedCallTrace, DecodedLog, DecodedParam};
+use alloy_dyn_abi::DynSolValue;
+use alloy_primitives::hex;
+use std::fmt::{self, Write};
+
+impl fmt::Display for DecodedCallTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_call(f, self)?;
+        writeln!(f)?;
+        write_children(f, self, "  ")
+    }
+}
+
+impl fmt::Display for DecodedLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "emit {}", format_call(name, &self.params)),
+            None => {
+                f.write_str("emit ")?;
+                for (index, topic) in self.data.topics().iter().enumerate() {
+                    write!(f, "topic {index}: {topic}, ")?;
+                }
+                write!(f, "data: {}", self.data.data)
+            }
+        }
+    }
+}
+
+/// Formats a call of a function, event or error, e.g. `transfer(to: 0x…, amount: 100)`.
+pub(super) fn format_call(name: &str, params: &[DecodedParam]) -> String {
+    let mut s = format!("{name}(");
+    for (index, param) in params.iter().enumerate() {
+        if index > 0 {
+            s.push_str(", ");
+        }
+        if !param.name.is_empty() {
+            let _ = write!(s, "{}: ", param.name);
+        }
+        write_value(&mut s, &param.value);
+    }
+    s.push(')');
+    s
+}
+
+/// Writes the head line of a call, without a trailing newline.
+fn write_call(f: &mut fmt::Formatter<'_>, trace: &DecodedCallTrace) -> fmt::Result {
+    write!(f, "[{}] ", trace.gas_used)?;
+    let label = trace.label.as_deref();
+
+    if trace.kind.starts_with("CREATE") {
+        f.write_str("→ new ")?;
+        return match trace.to {
+            Some(to) => write!(f, "{}@{to}", label.unwrap_or("<unknown>")),
+            None => f.write_str("<unknown>"),
+        };
+    }
+
+    match (label, trace.to) {
+        (Some(label), _) => f.write_str(label)?,
+        (None, Some(to)) => write!(f, "{to}")?,
+        (None, None) => f.write_str("<unknown>")?,
+    }
+    f.write_str("::")?;
+
+    let value = (!trace.value.is_zero()).then(|| format!("{{value: {}}}", trace.value));
+    let value = value.as_deref().unwrap_or_default();
+    match &trace.function {
+        Some(function) => {
+            let call = format_call(&function.name, &function.inputs);
+            let (name, args) = call.split_at(function.name.len());
+            write!(f, "{name}{value}{args}")?;
+        }
+        None if matches!(trace.kind.as_str(), "SELFDESTRUCT" | "REWARD") => {
+            write!(f, "{}{value}", trace.kind.to_lowercase())?;
+        }
+        None if trace.input.is_empty() => write!(f, "fallback{value}()")?,
+        None => {
+            let (selector, args) = trace.input.split_at(trace.input.len().min(4));
+            write!(f, "{}{value}({})", hex::encode(selector), hex::encode(args))?;
+        }
+    }
+
+    if !matches!(trace.kind.as_str(), "CALL" | "SELFDESTRUCT" | "REWARD") {
+        write!(f, " [{}]", trace.kind.to_lowercase())?;
+    }
+    Ok(())
+}
+
+/// Writes the subcalls and logs of a call in execution order, followed by its result.
+fn write_children(
+    f: &mut fmt::Formatter<'_>,
+    trace: &DecodedCallTrace,
+    prefix: &str,
+) -> fmt::Result {
+    enum Child<'a> {
+        Call(&'a DecodedCallTrace),
+        Log(&'a DecodedLog),
+        Result,
+    }
+
+    let mut children = Vec::with_capacity(trace.calls.len() + trace.logs.len() + 1);
+    let mut logs = trace.logs.iter().peekable();
+    for (index, call) in trace.calls.iter().enumerate() {
+        while let Some(log) = logs.next_if(|log| log.position <= index) {
+            children.push(Child::Log(log));
+        }
+        children.push(Child::Call(call));
+    }
+    children.extend(logs.map(Child::Log));
+    children.push(Child::Result);
+
+    let count = children.len();
+    for (index, child) in children.into_iter().enumerate() {
+        let last = index + 1 == count;
+        f.write_str(prefix)?;
+        f.write_str(if last { "└─ " } else { "├─ " })?;
+        match child {
+            Child::Call(call) => {
+                write_call(f, call)?;
+                writeln!(f)?;
+                let prefix = format!("{prefix}{}", if last { "    " } else { "│   " });
+                write_children(f, call, &prefix)?;
+            }
+            Child::Log(log) => writeln!(f, "{log}")?,
+            Child::Result => {
+                f.write_str("← ")?;
+                write_result(f, trace)?;
+                writeln!(f)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_result(f: &mut fmt::Formatter<'_>, trace: &DecodedCallTrace) -> fmt::Result {
+    if let Some(error) = &trace.error {
+        return write!(f, "[Revert] {}", trace.revert.as_deref().unwrap_or(error));
+    }
+    if trace.kind.starts_with("CREATE") {
+        return write!(f, "[Return] {} bytes of code", trace.output.len());
+    }
+    if trace.output.is_empty() {
+        return f.write_str("[Stop]");
+    }
+    match &trace.returns {
+        Some(returns) => {
+            f.write_str("[Return] ")?;
+            let mut s = String::new();
+            for (index, param) in returns.iter().enumerate() {
+                if index > 0 {
+                    s.push_str(", ");
+                }
+                write_value(&mut s, &param.value);
+            }
+            f.write_str(&s)
+        }
+        None => write!(f, "[Return] {}", trace.output),
+    }
+}
+
+/// Writes a value the way it would be written in Solidity.
+fn write_value(s: &mut String, value: &DynSolValue) {
+    fn write_seq(s: &mut String, values: &[DynSolValue], open: char, close: char) {
+        s.push(open);
+        for (index, value) in values.iter().enumerate() {
+            if index > 0 {
+                s.push_str(", ");
+            }
+            write_value(s, value);
+        }
+        s.push(close);
+    }
+
+    let _ = match value {
+        DynSolValue::Bool(value) => write!(s, "{value}"),
+        DynSolValue::Int(value, _) => write!(s, "{value}"),
+        DynSolValue::Uint(value, _) => write!(s, "{value}"),
+        DynSolValue::FixedBytes(word, size) => {
+            write!(s, "{}", hex::encode_prefixed(&word[..*size]))
+        }
+        DynSolValue::Address(address) => write!(s, "{address}"),
+        DynSolValue::Function(function) => write!(s, "{function}"),
+        DynSolValue::Bytes(bytes) => write!(s, "{}", hex::encode_prefixed(bytes)),
+        DynSolValue::String(string) => write!(s, "{string:?}"),
+        DynSolValue::Array(values) | DynSolValue::FixedArray(values) => {
+            write_seq(s, values, '[', ']');
+            Ok(())
+        }
+        DynSolValue::Tuple(values) => {
+            write_seq(s, values, '(', ')');
+            Ok(())
+        }
+        #[allow(unreachable_patterns)]
+        value => write!(s, "{value:?}"),
+    };
+}
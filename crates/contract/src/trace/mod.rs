@@ -0,0 +1,534 @@
+//! Decoding of call traces against contract interfaces and signature databases.
+//!
+//! The [`TraceDecoder`] resolves the selectors, events and custom errors of geth [`CallFrame`]s
+//! and parity [`TransactionTrace`]s into a [`DecodedCallTrace`] tree, which renders like the call
+//! traces of foundry-style tools:
+//!
+//! ```text
+//! [30000] 0x5FbDB2315678afecb367f032d93F642f64180aa3::transfer(to: 0x70997970C51812dc3A010C7d01b50e0d17dc79C8, amount: 100)
+//!   ├─ emit Transfer(from: 0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266, to: 0x70997970C51812dc3A010C7d01b50e0d17dc79C8, value: 100)
+//!   └─ ← [Return] true
+//! ```
+//!
+//! The [`TransferAnalysis`] extracts the native and token transfers of a transaction from its
+//! call trace and receipt logs, and derives the net [`BalanceChanges`] of every account.
+//!
+//! The [`OpcodeTrace`] folds geth struct logs or a parity `vmTrace` into a tree of call frames for
+//! gas attribution per opcode and contract, storage and memory reconstruction and flame graphs.
+
+use crate::Interface;
+use alloy_dyn_abi::{DynSolValue, ErrorExt, EventExt, FunctionExt, JsonAbiExt};
+use alloy_json_abi::{Error as AbiError, Event, Function, Param};
+use alloy_primitives::{map::AddressHashMap, Address, Bytes, LogData, Selector, U256};
+use alloy_rpc_types_trace::{
+    geth::CallFrame,
+    parity::{Action, TransactionTrace},
+};
+use alloy_sol_types::decode_revert_reason;
+
+mod format;
+
+mod signatures;
+pub use signatures::{SignatureDatabase, SignatureDatabaseError};
+
+mod opcodes;
+pub use opcodes::{
+    OpcodeFrame, OpcodeGas, OpcodeTrace, Step, StorageAccess, StorageAccessKind, StorageAccessSet,
+};
+
+mod transfers;
+pub use transfers::{Asset, BalanceChanges, BalanceMismatch, Transfer, TransferAnalysis};
+
+/// Decodes call traces against registered contract interfaces and a signature database, see the
+/// [module docs](self).
+///
+/// Calls to a contract with a registered [`Interface`] are decoded with its ABI, including the
+/// names of parameters. All other calls are decoded with the functions, events and errors of the
+/// [`SignatureDatabase`], which also contains the items of all registered interfaces.
+#[derive(Clone, Debug, Default)]
+pub struct TraceDecoder {
+    contracts: AddressHashMap<Interface>,
+    labels: AddressHashMap<String>,
+    signatures: SignatureDatabase,
+}
+
+impl TraceDecoder {
+    /// Creates a decoder without any known contracts or signatures.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the interface of the contract at the given address.
+    pub fn with_contract(mut self, address: Address, interface: Interface) -> Self {
+        self.signatures.extend_from_abi(interface.abi());
+        self.contracts.insert(address, interface);
+        self
+    }
+
+    /// Sets the label that is rendered instead of the given address, e.g. the contract name.
+    pub fn with_label(mut self, address: Address, label: impl Into<String>) -> Self {
+        self.labels.insert(address, label.into());
+        self
+    }
+
+    /// Adds the signatures of the database.
+    pub fn with_signatures(mut self, signatures: SignatureDatabase) -> Self {
+        self.signatures.extend(signatures);
+        self
+    }
+
+    /// Returns the label of the address, if any.
+    pub fn label(&self, address: &Address) -> Option<&str> {
+        self.labels.get(address).map(String::as_str)
+    }
+
+    /// Decodes a geth call trace, as returned by the `callTracer`.
+    ///
+    /// Logs are only included if the tracer was configured with `withLog`.
+    pub fn decode_call_frame(&self, frame: &CallFrame) -> DecodedCallTrace {
+        let mut trace = self.decode_call(DecodedCallTrace {
+            gas_used: frame.gas_used.saturating_to(),
+            input: frame.input.clone(),
+            output: frame.output.clone().unwrap_or_default(),
+            error: frame.error.clone(),
+            ..DecodedCallTrace::new(
+                frame.typ.clone(),
+                frame.from,
+                frame.to,
+                frame.value.unwrap_or_default(),
+            )
+        });
+        if trace.revert.is_none() && frame.error.is_some() {
+            trace.revert = frame.revert_reason.clone();
+        }
+
+        trace.logs = frame
+            .logs
+            .iter()
+            .map(|log| {
+                let data = LogData::new_unchecked(
+                    log.topics.clone().unwrap_or_default(),
+                    log.data.clone().unwrap_or_default(),
+                );
+                let mut decoded = self.decode_log(log.address.unwrap_or_default(), data);
+                decoded.position = log.position.map_or(frame.calls.len(), |p| p as usize);
+                decoded
+            })
+            .collect();
+        trace.calls = frame.calls.iter().map(|call| self.decode_call_frame(call)).collect();
+        trace
+    }
+
+    /// Decodes the parity traces of a transaction, as returned by `trace_transaction`, into the
+    /// call tree rooted at the first trace.
+    ///
+    /// The traces must be in the order returned by the node. Parity traces don't contain logs.
+    pub fn decode_parity_traces(&self, traces: &[TransactionTrace]) -> Option<DecodedCallTrace> {
+        CallFrame::from_parity_traces(traces).map(|frame| self.decode_call_frame(&frame))
+    }
+
+    /// Decodes a single parity trace, without its subtraces.
+    pub fn decode_parity_trace(&self, trace: &TransactionTrace) -> DecodedCallTrace {
+        match CallFrame::from_parity_traces(core::slice::from_ref(trace)) {
+            Some(frame) => self.decode_call_frame(&frame),
+            // only block rewards have no call frame
+            None => {
+                let (author, value) = match &trace.action {
+                    Action::Reward(reward) => (Some(reward.author), reward.value),
+                    _ => (None, U256::ZERO),
+                };
+                DecodedCallTrace::new("REWARD".to_string(), Address::ZERO, author, value)
+            }
+        }
+    }
+
+    /// Decodes the function, return values and revert reason of a call with raw fields.
+    fn decode_call(&self, mut trace: DecodedCallTrace) -> DecodedCallTrace {
+        trace.label = trace.to.and_then(|to| self.labels.get(&to).cloned());
+        if trace.kind.starts_with("CREATE") {
+            if trace.error.is_some() {
+                trace.revert = self.decode_revert(None, &trace.output);
+            }
+            return trace;
+        }
+
+        if let Some((function, inputs)) = self.resolve_function(trace.to, &trace.input) {
+            if trace.error.is_none() {
+                trace.returns = function
+                    .abi_decode_output(&trace.output)
+                    .ok()
+                    .map(|values| named_params(&function.outputs, values));
+            }
+            trace.function = Some(DecodedFunction {
+                name: function.name.clone(),
+                signature: function.signature(),
+                inputs: named_params(&function.inputs, inputs),
+            });
+        }
+        if trace.error.is_some() {
+            trace.revert = self.decode_revert(trace.to, &trace.output);
+        }
+        trace
+    }
+
+    fn resolve_function(
+        &self,
+        to: Option<Address>,
+        input: &[u8],
+    ) -> Option<(&Function, Vec<DynSolValue>)> {
+        let selector = Selector::try_from(input.get(..4)?).ok()?;
+        let data = &input[4..];
+        let known = to
+            .and_then(|to| self.contracts.get(&to))
+            .and_then(|interface| interface.get_from_selector(&selector).ok());
+        known
+            .into_iter()
+            .chain(self.signatures.functions(&selector))
+            .find_map(|function| function.abi_decode_input(data).ok().map(|args| (function, args)))
+    }
+
+    /// Decodes the log with the event of the emitting contract, or any known event with the same
+    /// topic.
+    pub fn decode_log(&self, address: Address, data: LogData) -> DecodedLog {
+        let mut log = DecodedLog { address, name: None, params: Vec::new(), data, position: 0 };
+        let Some(topic) = log.data.topics().first() else { return log };
+
+        let known = self
+            .contracts
+            .get(&address)
+            .and_then(|interface| interface.get_event_from_topic(topic).ok());
+        let decoded = known.into_iter().chain(self.signatures.events(topic)).find_map(|event| {
+            let event = with_indexed_params(event, log.data.topics().len() - 1);
+            let decoded = event.decode_log(&log.data).ok()?;
+            let (mut indexed, mut body) = (decoded.indexed.into_iter(), decoded.body.into_iter());
+            let params = event
+                .inputs
+                .iter()
+                .map(|param| {
+                    let value = if param.indexed { indexed.next() } else { body.next() };
+                    value.map(|value| DecodedParam { name: param.name.clone(), value })
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some((event.name.clone(), params))
+        });
+        if let Some((name, params)) = decoded {
+            log.name = Some(name);
+            log.params = params;
+        }
+        log
+    }
+
+    /// Decodes the revert data of a call to the given contract into a human-readable reason.
+    ///
+    /// Custom errors are rendered as `Name(args)`, and `Error(string)` and `Panic(uint256)` as
+    /// their message. Returns `None` if the call reverted without data.
+    pub fn decode_revert(&self, to: Option<Address>, output: &[u8]) -> Option<String> {
+        if output.is_empty() {
+            return None;
+        }
+
+        if let Ok(selector) = Selector::try_from(output.get(..4).unwrap_or_default()) {
+            let known = to
+                .and_then(|to| self.contracts.get(&to))
+                .and_then(|interface| interface.get_error_from_selector(&selector).ok());
+            let decoded = known.into_iter().chain(self.signatures.errors(&selector)).find_map(
+                |error: &AbiError| {
+                    let decoded = error.decode_error(output).ok()?;
+                    Some(format::format_call(
+                        &error.name,
+                        &named_params(&error.inputs, decoded.body),
+                    ))
+                },
+            );
+            if decoded.is_some() {
+                return decoded;
+            }
+        }
+
+        Some(
+            decode_revert_reason(output)
+                .unwrap_or_else(|| Bytes::copy_from_slice(output).to_string()),
+        )
+    }
+}
+
+/// Returns the event, with its first `count` parameters marked as indexed if it doesn't declare
+/// any indexed parameters, since signature databases usually don't record them.
+fn with_indexed_params(event: &Event, count: usize) -> std::borrow::Cow<'_, Event> {
+    if event.anonymous || count == 0 || event.inputs.iter().any(|param| param.indexed) {
+        return std::borrow::Cow::Borrowed(event);
+    }
+    let mut event = event.clone();
+    event.inputs.iter_mut().take(count).for_each(|param| param.indexed = true);
+    std::borrow::Cow::Owned(event)
+}
+
+fn named_params(params: &[Param], values: Vec<DynSolValue>) -> Vec<DecodedParam> {
+    params
+        .iter()
+        .map(|param| param.name.clone())
+        .chain(std::iter::repeat(String::new()))
+        .zip(values)
+        .map(|(name, value)| DecodedParam { name, value })
+        .collect()
+}
+
+/// A decoded call and its subcalls.
+///
+/// The [`Display`](std::fmt::Display) implementation renders the call tree.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedCallTrace {
+    /// The type of the call, e.g. `CALL`, `DELEGATECALL` or `CREATE2`.
+    pub kind: String,
+    /// The caller.
+    pub from: Address,
+    /// The callee, or the created contract. `None` if a creation failed.
+    pub to: Option<Address>,
+    /// The label of the callee, if any.
+    pub label: Option<String>,
+    /// The value transferred by the call.
+    pub value: U256,
+    /// The gas used by the call.
+    pub gas_used: u64,
+    /// The calldata, or the init code of a creation.
+    pub input: Bytes,
+    /// The return or revert data, or the code of a created contract.
+    pub output: Bytes,
+    /// The decoded function call, if the selector is known.
+    pub function: Option<DecodedFunction>,
+    /// The decoded return values, if the call succeeded and the function is known.
+    pub returns: Option<Vec<DecodedParam>>,
+    /// The error reported by the tracer, if the call failed.
+    pub error: Option<String>,
+    /// The decoded revert reason, if the call reverted with data.
+    pub revert: Option<String>,
+    /// The logs emitted by the call.
+    pub logs: Vec<DecodedLog>,
+    /// The subcalls.
+    pub calls: Vec<Self>,
+}
+
+impl DecodedCallTrace {
+    const fn new(kind: String, from: Address, to: Option<Address>, value: U256) -> Self {
+        Self {
+            kind,
+            from,
+            to,
+            label: None,
+            value,
+            gas_used: 0,
+            input: Bytes::new(),
+            output: Bytes::new(),
+            function: None,
+            returns: None,
+            error: None,
+            revert: None,
+            logs: Vec::new(),
+            calls: Vec::new(),
+        }
+    }
+
+    /// Returns `true` if the call did not fail.
+    pub const fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Returns an iterator over this call and all nested subcalls in depth-first pre-order.
+    pub fn iter(&self) -> impl Iterator<Item = &Self> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let trace = stack.pop()?;
+            stack.extend(trace.calls.iter().rev());
+            Some(trace)
+        })
+    }
+}
+
+/// A decoded function call.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedFunction {
+    /// The name of the function.
+    pub name: String,
+    /// The signature of the function, e.g. `transfer(address,uint256)`.
+    pub signature: String,
+    /// The decoded arguments.
+    pub inputs: Vec<DecodedParam>,
+}
+
+/// A decoded parameter of a function, event or error.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedParam {
+    /// The name of the parameter, empty if unknown.
+    pub name: String,
+    /// The decoded value.
+    pub value: DynSolValue,
+}
+
+/// A decoded log.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedLog {
+    /// The emitting contract.
+    pub address: Address,
+    /// The name of the event, if the topic is known.
+    pub name: Option<String>,
+    /// The decoded parameters in declaration order, if the topic is known.
+    pub params: Vec<DecodedParam>,
+    /// The raw log.
+    pub data: LogData,
+    /// The number of subcalls of the emitting call made before the log was emitted.
+    pub position: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_json_abi::JsonAbi;
+    use alloy_primitives::{address, hex};
+    use alloy_rpc_types_trace::{
+        geth::CallLogFrame,
+        parity::{CallAction, CallOutput, CallType, TraceOutput},
+    };
+    use alloy_sol_types::{sol, SolCall, SolError, SolEvent, SolValue};
+
+    sol! {
+        function transfer(address to, uint256 amount) returns (bool);
+        function balanceOf(address owner) returns (uint256);
+        event Transfer(address indexed from, address indexed to, uint256 value);
+        error InsufficientBalance(uint256 balance, uint256 needed);
+    }
+
+    const TOKEN: Address = address!("0x5FbDB2315678afecb367f032d93F642f64180aa3");
+    const ALICE: Address = address!("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266");
+    const BOB: Address = address!("0x70997970C51812dc3A010C7d01b50e0d17dc79C8");
+
+    fn token() -> Interface {
+        Interface::new(
+            JsonAbi::parse([
+                "function transfer(address to, uint256 amount) returns (bool)",
+                "function balanceOf(address owner) returns (uint256)",
+                "event Transfer(address indexed from, address indexed to, uint256 value)",
+                "error InsufficientBalance(uint256 balance, uint256 needed)",
+            ])
+            .unwrap(),
+        )
+    }
+
+    fn transfer_frame() -> CallFrame {
+        let log = Transfer { from: ALICE, to: BOB, value: U256::from(100) }.encode_log_data();
+        CallFrame {
+            from: ALICE,
+            to: Some(TOKEN),
+            gas_used: U256::from(30_000),
+            input: transferCall { to: BOB, amount: U256::from(100) }.abi_encode().into(),
+            output: Some(true.abi_encode().into()),
+            calls: vec![CallFrame {
+                from: TOKEN,
+                to: Some(BOB),
+                gas_used: U256::from(2_600),
+                input: balanceOfCall { owner: BOB }.abi_encode().into(),
+                output: Some(U256::from(5).abi_encode().into()),
+                typ: "STATICCALL".to_string(),
+                ..Default::default()
+            }],
+            logs: vec![CallLogFrame {
+                address: Some(TOKEN),
+                topics: Some(log.topics().to_vec()),
+                data: Some(log.data),
+                position: Some(1),
+                index: Some(0),
+            }],
+            typ: "CALL".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn decode_and_render_call_frame() {
+        let decoder = TraceDecoder::new().with_contract(TOKEN, token()).with_label(TOKEN, "Token");
+        let trace = decoder.decode_call_frame(&transfer_frame());
+
+        let function = trace.function.as_ref().unwrap();
+        assert_eq!(function.signature, "transfer(address,uint256)");
+        assert_eq!(function.inputs[0], DecodedParam { name: "to".into(), value: BOB.into() });
+        assert_eq!(trace.returns.as_ref().unwrap()[0].value, DynSolValue::Bool(true));
+        assert_eq!(trace.logs[0].name.as_deref(), Some("Transfer"));
+        // decoded from the signatures of the token interface
+        assert_eq!(trace.calls[0].function.as_ref().unwrap().name, "balanceOf");
+
+        similar_asserts::assert_eq!(
+            trace.to_string(),
+            "\
+[30000] Token::transfer(to: 0x70997970C51812dc3A010C7d01b50e0d17dc79C8, amount: 100)
+  ├─ [2600] 0x70997970C51812dc3A010C7d01b50e0d17dc79C8::balanceOf(owner: 0x70997970C51812dc3A010C7d01b50e0d17dc79C8) [staticcall]
+  │   └─ ← [Return] 5
+  ├─ emit Transfer(from: 0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266, to: 0x70997970C51812dc3A010C7d01b50e0d17dc79C8, value: 100)
+  └─ ← [Return] true
+"
+        );
+    }
+
+    #[test]
+    fn decode_parity_traces_with_signatures() {
+        let signatures = SignatureDatabase::parse(
+            "# token\ntransfer(address,uint256)\n\nerror InsufficientBalance(uint256,uint256)\n",
+        )
+        .unwrap();
+        let decoder = TraceDecoder::new().with_signatures(signatures);
+
+        let revert = InsufficientBalance { balance: U256::from(1), needed: U256::from(2) };
+        let call = |from, to, input: Vec<u8>, output: Vec<u8>, trace_address: Vec<usize>| {
+            TransactionTrace {
+                action: Action::Call(CallAction {
+                    from,
+                    call_type: CallType::Call,
+                    to,
+                    input: input.into(),
+                    ..Default::default()
+                }),
+                error: Some("Reverted".to_string()),
+                result: Some(TraceOutput::Call(CallOutput {
+                    gas_used: 100,
+                    output: output.into(),
+                })),
+                subtraces: 0,
+                trace_address,
+            }
+        };
+        let traces = [
+            call(
+                ALICE,
+                TOKEN,
+                transferCall { to: BOB, amount: U256::from(2) }.abi_encode(),
+                revert.abi_encode(),
+                vec![],
+            ),
+            call(TOKEN, BOB, hex!("12345678").to_vec(), Vec::new(), vec![0]),
+        ];
+
+        let trace = decoder.decode_parity_traces(&traces).unwrap();
+        assert_eq!(trace.function.as_ref().unwrap().inputs[1].name, "");
+        assert_eq!(trace.revert.as_deref(), Some("InsufficientBalance(1, 2)"));
+        assert_eq!(trace.calls.len(), 1);
+        assert!(trace.calls[0].function.is_none());
+        assert_eq!(trace.iter().count(), 2);
+
+        similar_asserts::assert_eq!(
+            trace.to_string(),
+            "\
+[100] 0x5FbDB2315678afecb367f032d93F642f64180aa3::transfer(0x70997970C51812dc3A010C7d01b50e0d17dc79C8, 2)
+  ├─ [100] 0x70997970C51812dc3A010C7d01b50e0d17dc79C8::12345678()
+  │   └─ ← [Revert] execution reverted
+  └─ ← [Revert] InsufficientBalance(1, 2)
+"
+        );
+    }
+
+    #[test]
+    fn decode_revert_reasons() {
+        let decoder = TraceDecoder::new();
+        let error = alloy_sol_types::Revert::from("not enough").abi_encode();
+        assert_eq!(decoder.decode_revert(None, &error).as_deref(), Some("revert: not enough"));
+        assert_eq!(decoder.decode_revert(None, &[]), None);
+        assert_eq!(decoder.decode_revert(None, &[0xff, 0x00]).as_deref(), Some("0xff00"));
+    }
+}
@@ -0,0 +1,609 @@
+use alloy_primitives::{hex, Address, Bytes, B256};
+use alloy_rpc_types_trace::{geth::StructLog, parity::VmTrace};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A call frame of an [`OpcodeTrace`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OpcodeFrame {
+    /// The opcode that entered the frame, e.g. `CALL` or `CREATE2`. The root frame is a `CALL`.
+    pub kind: String,
+    /// The address whose storage the frame operates on, if known.
+    pub address: Option<Address>,
+    /// The address whose code the frame executes, if known.
+    ///
+    /// Differs from the [`address`](Self::address) for `DELEGATECALL` and `CALLCODE` frames.
+    pub code_address: Option<Address>,
+    /// The call depth, starting at 1 for the root frame.
+    pub depth: usize,
+    /// The index of the parent frame.
+    pub parent: Option<usize>,
+    /// The indices of the frames of the subcalls.
+    pub calls: Vec<usize>,
+    /// The indices of the steps executed in this frame, excluding subcalls.
+    pub steps: Vec<usize>,
+    /// Gas used by the frame, including subcalls.
+    pub gas_used: u64,
+    /// Gas used by the instructions of this frame, excluding subcalls.
+    pub self_gas: u64,
+    /// Whether the frame reverted or halted with an error, which discards its storage writes.
+    pub reverted: bool,
+}
+
+/// An instruction executed in an [`OpcodeTrace`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    /// The program counter.
+    pub pc: u64,
+    /// The name of the opcode.
+    pub op: String,
+    /// The call depth, starting at 1 for the root frame.
+    pub depth: usize,
+    /// Gas remaining before the instruction.
+    pub gas: u64,
+    /// Gas used by the instruction, excluding the subcall it entered, if any.
+    pub gas_cost: u64,
+    /// The index of the frame that executed the instruction.
+    pub frame: usize,
+}
+
+/// How often an opcode was executed and how much gas it used.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OpcodeGas {
+    /// The number of executions.
+    pub count: u64,
+    /// The gas used, excluding subcalls.
+    pub gas: u64,
+}
+
+/// Whether a [`StorageAccess`] read or wrote a slot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageAccessKind {
+    /// `SLOAD`
+    Read,
+    /// `SSTORE`
+    Write,
+}
+
+/// A storage slot accessed by an `SLOAD` or `SSTORE`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StorageAccess {
+    /// The index of the step.
+    pub step: usize,
+    /// Whether the slot was read or written.
+    pub kind: StorageAccessKind,
+    /// The address whose storage was accessed, if known.
+    pub address: Option<Address>,
+    /// The slot.
+    pub slot: B256,
+    /// The value that was read or written, if known.
+    pub value: Option<B256>,
+}
+
+/// The storage slots read and written by a transaction, see [`OpcodeTrace::access_set`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StorageAccessSet {
+    /// The slots read with `SLOAD`, per address.
+    pub reads: BTreeMap<Option<Address>, BTreeSet<B256>>,
+    /// The slots written with `SSTORE`, per address.
+    pub writes: BTreeMap<Option<Address>, BTreeSet<B256>>,
+}
+
+/// An opcode-level trace folded into a tree of call frames, built from the struct logs of the
+/// default geth tracer or from a parity `vmTrace`.
+///
+/// The gas used by every instruction is attributed to the frame that executed it, excluding the
+/// gas used by the subcalls it entered, so gas can be aggregated per opcode, per contract, or as
+/// folded stacks for flame graphs. The intrinsic gas of the transaction is not included.
+///
+/// Storage and memory can be reconstructed at every step, as far as the trace records them:
+/// - geth struct logs record storage reads and writes if the stack is enabled, and memory snapshots
+///   if `enableMemory` is set.
+/// - parity `vmTrace`s record storage writes and memory changes, but not storage reads. They also
+///   don't record the addresses of subcalls, so only the address of the root frame is known.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OpcodeTrace {
+    frames: Vec<OpcodeFrame>,
+    steps: Vec<Step>,
+    accesses: Vec<StorageAccess>,
+    memory: Vec<Memory>,
+    /// The step index after the last step of every frame, including subcalls.
+    ends: Vec<usize>,
+}
+
+/// The memory recorded at a step.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Memory {
+    /// Memory was not recorded.
+    Unknown,
+    /// The memory before the step.
+    Snapshot(Bytes),
+    /// The memory changed by the step, if any.
+    Delta(Option<(usize, Bytes)>),
+}
+
+/// A step with format specific information resolved.
+#[derive(Debug)]
+struct RawStep {
+    pc: u64,
+    op: String,
+    depth: usize,
+    gas: u64,
+    /// Gas used by the step, including the subcall it entered, if any.
+    gas_used: u64,
+    /// The code address of the subcall entered by the step, if any.
+    callee: Option<Address>,
+    storage: Option<(StorageAccessKind, B256, Option<B256>)>,
+    memory: Memory,
+    failed: bool,
+}
+
+impl OpcodeTrace {
+    /// Folds the struct logs of the default geth tracer, where `address` is the recipient of the
+    /// transaction, or the created contract for contract creations.
+    pub fn from_struct_logs(address: Address, logs: &[StructLog]) -> Self {
+        let next = next_in_frame(logs.iter().map(|log| log.depth as usize));
+        let stack_top = |index: Option<usize>| {
+            index.and_then(|index| logs[index].stack.as_ref()?.last().copied())
+        };
+
+        let steps = logs.iter().enumerate().map(|(index, log)| {
+            let stack = log.stack.as_deref().unwrap_or_default();
+            let arg = |n: usize| stack.len().checked_sub(n + 1).map(|n| B256::from(stack[n]));
+            let enters_call = logs.get(index + 1).is_some_and(|next| next.depth > log.depth);
+            let failed = log.error.is_some() || log.op == "REVERT";
+
+            let callee = match log.opcode() {
+                _ if !enters_call => None,
+                // the created address is pushed when the caller resumes
+                "CREATE" | "CREATE2" => stack_top(next[index])
+                    .map(|word| Address::from_word(word.into()))
+                    .filter(|address| !address.is_zero()),
+                _ => arg(1).map(Address::from_word),
+            };
+            let storage = match log.opcode() {
+                "SLOAD" => arg(0).map(|slot| {
+                    let value =
+                        log.storage.as_ref().and_then(|storage| storage.get(&slot).copied());
+                    let value = value.or_else(|| stack_top(next[index]).map(B256::from));
+                    (StorageAccessKind::Read, slot, value)
+                }),
+                "SSTORE" => arg(0).map(|slot| (StorageAccessKind::Write, slot, arg(1))),
+                _ => None,
+            };
+            let memory = match &log.memory {
+                Some(words) => hex::decode(words.concat())
+                    .map_or(Memory::Unknown, |memory| Memory::Snapshot(memory.into())),
+                None => Memory::Unknown,
+            };
+
+            RawStep {
+                pc: log.pc,
+                op: log.op.to_string(),
+                depth: log.depth as usize,
+                gas: log.gas,
+                gas_used: match next[index] {
+                    Some(next) => log.gas.saturating_sub(logs[next].gas),
+                    // errors other than reverts consume all remaining gas
+                    None if failed && log.op != "REVERT" => log.gas,
+                    None => log.gas_cost,
+                },
+                callee,
+                storage,
+                memory,
+                failed,
+            }
+        });
+        Self::build(address, steps)
+    }
+
+    /// Folds a parity `vmTrace`, where `address` is the recipient of the transaction, or the
+    /// created contract for contract creations.
+    pub fn from_vm_trace(address: Address, trace: &VmTrace) -> Self {
+        fn flatten(trace: &VmTrace, depth: usize, steps: &mut Vec<RawStep>) {
+            let mut gas = None;
+            for instruction in &trace.ops {
+                let op = instruction.op.clone().unwrap_or_else(|| "UNKNOWN".to_string());
+                let ex = instruction.ex.as_ref();
+                // instructions that halt with an error have no result and consume all gas
+                let remaining = ex.map_or(0, |ex| ex.used);
+                let before = gas.unwrap_or(remaining + instruction.cost);
+
+                let callee = match op.as_str() {
+                    "CREATE" | "CREATE2" => ex
+                        .and_then(|ex| ex.push.last())
+                        .map(|word| Address::from_word((*word).into()))
+                        .filter(|address| !address.is_zero()),
+                    _ => None,
+                };
+                steps.push(RawStep {
+                    pc: instruction.pc as u64,
+                    depth,
+                    gas: before,
+                    gas_used: before.saturating_sub(remaining),
+                    callee,
+                    storage: ex.and_then(|ex| ex.store).map(|store| {
+                        (StorageAccessKind::Write, store.key.into(), Some(store.val.into()))
+                    }),
+                    memory: Memory::Delta(
+                        ex.and_then(|ex| ex.mem.as_ref()).map(|mem| (mem.off, mem.data.clone())),
+                    ),
+                    failed: ex.is_none() || op == "REVERT",
+                    op,
+                });
+                if let Some(sub) = &instruction.sub {
+                    flatten(sub, depth + 1, steps);
+                }
+                gas = Some(remaining);
+            }
+        }
+
+        let mut steps = Vec::new();
+        flatten(trace, 1, &mut steps);
+        Self::build(address, steps)
+    }
+
+    fn build(address: Address, raw: impl IntoIterator<Item = RawStep>) -> Self {
+        let mut trace = Self::default();
+        let mut stack: Vec<usize> = Vec::new();
+        let mut gas_used = Vec::new();
+        let mut previous: Option<RawStep> = None;
+
+        for (index, mut step) in raw.into_iter().enumerate() {
+            while stack.last().is_some_and(|&frame| trace.frames[frame].depth > step.depth) {
+                stack.pop();
+            }
+            let parent = stack.last().copied();
+            if parent.is_none_or(|parent| step.depth > trace.frames[parent].depth) {
+                let (kind, callee) = match (parent, &previous) {
+                    (Some(_), Some(previous)) => (previous.op.clone(), previous.callee),
+                    _ => ("CALL".to_string(), Some(address)),
+                };
+                let address = match kind.as_str() {
+                    "DELEGATECALL" | "CALLCODE" => {
+                        parent.and_then(|parent| trace.frames[parent].address)
+                    }
+                    _ => callee,
+                };
+                let frame = trace.frames.len();
+                if let Some(parent) = parent {
+                    trace.frames[parent].calls.push(frame);
+                }
+                trace.frames.push(OpcodeFrame {
+                    kind,
+                    address,
+                    code_address: callee,
+                    depth: step.depth,
+                    parent,
+                    calls: Vec::new(),
+                    steps: Vec::new(),
+                    gas_used: 0,
+                    self_gas: 0,
+                    reverted: false,
+                });
+                stack.push(frame);
+            }
+
+            let frame = *stack.last().expect("frame was pushed");
+            trace.frames[frame].steps.push(index);
+            trace.frames[frame].reverted = step.failed;
+            if let Some((kind, slot, value)) = step.storage {
+                let address = trace.frames[frame].address;
+                trace.accesses.push(StorageAccess { step: index, kind, address, slot, value });
+            }
+            gas_used.push(step.gas_used);
+            trace.steps.push(Step {
+                pc: step.pc,
+                op: step.op.clone(),
+                depth: step.depth,
+                gas: step.gas,
+                gas_cost: step.gas_used,
+                frame,
+            });
+            trace.memory.push(std::mem::replace(&mut step.memory, Memory::Unknown));
+            previous = Some(step);
+        }
+
+        // subcalls have higher indices than their callers
+        trace.ends = vec![0; trace.frames.len()];
+        for frame in (0..trace.frames.len()).rev() {
+            let mut end = trace.frames[frame].steps.last().map_or(0, |step| step + 1);
+            let mut children_gas = 0u64;
+            for &call in &trace.frames[frame].calls {
+                // the step before the first step of a subcall entered it
+                let caller = trace.frames[call].steps[0] - 1;
+                trace.steps[caller].gas_cost =
+                    gas_used[caller].saturating_sub(trace.frames[call].gas_used);
+                children_gas = children_gas.saturating_add(trace.frames[call].gas_used);
+                end = end.max(trace.ends[call]);
+            }
+            let self_gas = trace.frames[frame].steps.iter().map(|&s| trace.steps[s].gas_cost).sum();
+            trace.frames[frame].self_gas = self_gas;
+            trace.frames[frame].gas_used = self_gas.saturating_add(children_gas);
+            trace.ends[frame] = end;
+        }
+        trace
+    }
+
+    /// Returns the root frame, or `None` if the trace is empty.
+    pub fn root(&self) -> Option<&OpcodeFrame> {
+        self.frames.first()
+    }
+
+    /// Returns all frames in depth-first pre-order.
+    pub fn frames(&self) -> &[OpcodeFrame] {
+        &self.frames
+    }
+
+    /// Returns all steps in execution order.
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Returns the gas used per opcode.
+    pub fn gas_by_opcode(&self) -> BTreeMap<&str, OpcodeGas> {
+        gas_by_opcode(self.steps.iter())
+    }
+
+    /// Returns the gas used per opcode by the instructions of a frame, excluding subcalls.
+    pub fn frame_gas_by_opcode(&self, frame: usize) -> BTreeMap<&str, OpcodeGas> {
+        gas_by_opcode(self.frames[frame].steps.iter().map(|&step| &self.steps[step]))
+    }
+
+    /// Returns the gas used per code address, excluding the gas used by calls to other code.
+    pub fn gas_by_contract(&self) -> BTreeMap<Option<Address>, u64> {
+        let mut gas = BTreeMap::<_, u64>::new();
+        for frame in &self.frames {
+            *gas.entry(frame.code_address).or_default() += frame.self_gas;
+        }
+        gas
+    }
+
+    /// Returns all `SLOAD`s and `SSTORE`s in execution order, including those of frames that
+    /// reverted.
+    pub fn storage_accesses(&self) -> &[StorageAccess] {
+        &self.accesses
+    }
+
+    /// Returns the storage slots read and written per address.
+    pub fn access_set(&self) -> StorageAccessSet {
+        let mut set = StorageAccessSet::default();
+        for access in &self.accesses {
+            let slots = match access.kind {
+                StorageAccessKind::Read => &mut set.reads,
+                StorageAccessKind::Write => &mut set.writes,
+            };
+            slots.entry(access.address).or_default().insert(access.slot);
+        }
+        set
+    }
+
+    /// Returns the known storage values before the given step, per address.
+    ///
+    /// Only slots accessed before the step are known. Writes of frames that reverted before the
+    /// step are discarded.
+    pub fn storage_at(&self, step: usize) -> BTreeMap<Option<Address>, BTreeMap<B256, B256>> {
+        let mut storage = BTreeMap::<_, BTreeMap<_, _>>::new();
+        for access in self.accesses.iter().take_while(|access| access.step < step) {
+            let Some(value) = access.value else { continue };
+            if !self.is_discarded(access.step, step) {
+                storage.entry(access.address).or_default().insert(access.slot, value);
+            }
+        }
+        storage
+    }
+
+    /// Returns the memory of the executing frame before the given step, or `None` if the trace
+    /// doesn't record memory.
+    pub fn memory_at(&self, step: usize) -> Option<Bytes> {
+        match self.memory.get(step)? {
+            Memory::Unknown => None,
+            Memory::Snapshot(memory) => Some(memory.clone()),
+            Memory::Delta(_) => {
+                let frame = &self.frames[self.steps[step].frame];
+                let mut memory = Vec::new();
+                for &index in frame.steps.iter().take_while(|&&index| index < step) {
+                    if let Memory::Delta(Some((offset, data))) = &self.memory[index] {
+                        let end = offset + data.len();
+                        if memory.len() < end {
+                            memory.resize(end, 0);
+                        }
+                        memory[*offset..end].copy_from_slice(data);
+                    }
+                }
+                Some(memory.into())
+            }
+        }
+    }
+
+    /// Returns the gas used per stack of frames and opcode in the folded stack format of
+    /// [`inferno`] and [`flamegraph.pl`], e.g. `0x…01;0x…02;SSTORE 20000`.
+    ///
+    /// [`inferno`]: https://github.com/jonhoo/inferno
+    /// [`flamegraph.pl`]: https://github.com/brendangregg/FlameGraph
+    pub fn folded_stacks(&self) -> String {
+        let mut paths: Vec<String> = Vec::with_capacity(self.frames.len());
+        for frame in &self.frames {
+            let label = frame.code_address.map_or_else(|| "unknown".to_string(), |a| a.to_string());
+            let path = match frame.parent {
+                Some(parent) => format!("{};{label}", paths[parent]),
+                None => label,
+            };
+            paths.push(path);
+        }
+
+        let mut stacks = BTreeMap::<_, u64>::new();
+        for step in self.steps.iter().filter(|step| step.gas_cost > 0) {
+            *stacks.entry(format!("{};{}", paths[step.frame], step.op)).or_default() +=
+                step.gas_cost;
+        }
+        stacks.into_iter().map(|(stack, gas)| format!("{stack} {gas}\n")).collect()
+    }
+
+    /// Returns true if the access at `access` was discarded by a revert before `step`.
+    fn is_discarded(&self, access: usize, step: usize) -> bool {
+        let mut frame = Some(self.steps[access].frame);
+        while let Some(index) = frame {
+            if self.frames[index].reverted && self.ends[index] <= step {
+                return true;
+            }
+            frame = self.frames[index].parent;
+        }
+        false
+    }
+}
+
+fn gas_by_opcode<'a>(steps: impl Iterator<Item = &'a Step>) -> BTreeMap<&'a str, OpcodeGas> {
+    let mut gas = BTreeMap::<_, OpcodeGas>::new();
+    for step in steps {
+        let entry = gas.entry(step.op.as_str()).or_default();
+        entry.count += 1;
+        entry.gas += step.gas_cost;
+    }
+    gas
+}
+
+/// Returns the index of the next step in the same frame for every step, which is where the frame
+/// resumes after a subcall.
+fn next_in_frame(
+    depths: impl DoubleEndedIterator<Item = usize> + ExactSizeIterator,
+) -> Vec<Option<usize>> {
+    let mut next = vec![None; depths.len()];
+    let mut by_depth: Vec<Option<usize>> = Vec::new();
+    for (index, depth) in depths.enumerate().rev() {
+        if by_depth.len() <= depth {
+            by_depth.resize(depth + 1, None);
+        }
+        next[index] = by_depth[depth];
+        by_depth[depth] = Some(index);
+        // deeper frames after this step can't continue before it
+        by_depth.truncate(depth + 1);
+    }
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+    use alloy_rpc_types_trace::parity::{
+        MemoryDelta, StorageDelta, VmExecutedOperation, VmInstruction,
+    };
+    use similar_asserts::assert_eq;
+
+    fn log(op: &'static str, depth: u64, gas: u64, gas_cost: u64, stack: &[u64]) -> StructLog {
+        StructLog {
+            op: op.into(),
+            depth,
+            gas,
+            gas_cost,
+            stack: Some(stack.iter().map(|word| U256::from(*word)).collect()),
+            ..Default::default()
+        }
+    }
+
+    fn word(value: u64) -> B256 {
+        U256::from(value).into()
+    }
+
+    #[test]
+    fn fold_struct_logs() {
+        let (root, child) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let mut sload = log("SLOAD", 1, 99_997, 2100, &[1]);
+        sload.storage = Some(BTreeMap::from([(word(1), word(5))]));
+        let logs = [
+            log("PUSH1", 1, 100_000, 3, &[]),
+            sload,
+            log("CALL", 1, 97_897, 50_000, &[0, 0, 0, 0, 0, 2, 50_000]),
+            log("PUSH1", 2, 49_000, 3, &[]),
+            log("SSTORE", 2, 48_997, 20_000, &[7, 1]),
+            log("REVERT", 2, 28_997, 0, &[0, 0]),
+            log("SSTORE", 1, 75_294, 2900, &[0, 9, 2]),
+            log("STOP", 1, 72_394, 0, &[]),
+        ];
+
+        let trace = OpcodeTrace::from_struct_logs(root, &logs);
+        let frames = trace.frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!((frames[0].self_gas, frames[0].gas_used), (7603, 27_606));
+        assert_eq!(frames[1].kind, "CALL");
+        assert_eq!(frames[1].address, Some(child));
+        assert_eq!((frames[1].gas_used, frames[1].reverted), (20_003, true));
+        assert_eq!(frames[0].calls, vec![1]);
+        assert_eq!(frames[1].steps, vec![3, 4, 5]);
+        assert_eq!(trace.steps()[2].gas_cost, 2600);
+
+        assert_eq!(trace.gas_by_opcode()["SSTORE"], OpcodeGas { count: 2, gas: 22_900 });
+        assert_eq!(trace.frame_gas_by_opcode(0)["SSTORE"], OpcodeGas { count: 1, gas: 2900 });
+        assert_eq!(
+            trace.gas_by_contract(),
+            BTreeMap::from([(Some(root), 7603), (Some(child), 20_003)])
+        );
+
+        let set = trace.access_set();
+        assert_eq!(set.reads, BTreeMap::from([(Some(root), BTreeSet::from([word(1)]))]));
+        assert_eq!(set.writes[&Some(child)], BTreeSet::from([word(1)]));
+
+        // the write of the child is visible until the child reverts
+        assert_eq!(trace.storage_at(5)[&Some(child)], BTreeMap::from([(word(1), word(7))]));
+        let storage = trace.storage_at(8);
+        assert_eq!(storage.get(&Some(child)), None);
+        assert_eq!(storage[&Some(root)], BTreeMap::from([(word(1), word(5)), (word(2), word(9))]));
+        assert_eq!(trace.memory_at(0), None);
+
+        let folded = trace.folded_stacks();
+        assert!(folded.contains(&format!("{root};{child};SSTORE 20000\n")));
+        assert!(folded.contains(&format!("{root};CALL 2600\n")));
+        assert!(!folded.contains("STOP"));
+    }
+
+    #[test]
+    fn fold_vm_trace() {
+        let instruction = |op: &str, cost, used, mem: Option<MemoryDelta>, sub| VmInstruction {
+            cost,
+            ex: Some(VmExecutedOperation {
+                used,
+                push: Vec::new(),
+                mem,
+                store: (op == "SSTORE")
+                    .then(|| StorageDelta { key: U256::from(1), val: U256::from(2) }),
+            }),
+            pc: 0,
+            sub,
+            op: Some(op.to_string()),
+            idx: None,
+        };
+        let sub = VmTrace {
+            code: Bytes::new(),
+            ops: vec![
+                instruction(
+                    "MSTORE",
+                    6,
+                    20_994,
+                    Some(MemoryDelta { off: 0, data: vec![1; 32].into() }),
+                    None,
+                ),
+                instruction("SSTORE", 20_000, 994, None, None),
+            ],
+        };
+        let trace = VmTrace {
+            code: Bytes::new(),
+            ops: vec![
+                instruction("PUSH1", 3, 99_997, None, None),
+                instruction("DELEGATECALL", 100, 78_897, None, Some(sub)),
+                instruction("STOP", 0, 78_897, None, None),
+            ],
+        };
+
+        let root = Address::with_last_byte(1);
+        let trace = OpcodeTrace::from_vm_trace(root, &trace);
+        let frames = trace.frames();
+        assert_eq!(frames[1].kind, "DELEGATECALL");
+        assert_eq!((frames[1].address, frames[1].code_address), (Some(root), None));
+        assert_eq!(frames[1].gas_used, 20_006);
+        assert_eq!(trace.steps()[1].gas_cost, 1094);
+        assert_eq!(frames[0].gas_used, 21_103);
+        assert_eq!(trace.storage_at(5)[&Some(root)], BTreeMap::from([(word(1), word(2))]));
+        assert_eq!(trace.memory_at(2), Some(Bytes::new()));
+        assert_eq!(trace.memory_at(3), Some(Bytes::from(vec![1; 32])));
+    }
+}
@@ -0,0 +1,194 @@
+use alloy_json_abi::{parser, AbiItem, Error as AbiError, Event, Function, JsonAbi};
+use alloy_primitives::{
+    map::{B256HashMap, SelectorHashMap},
+    Selector, B256,
+};
+use std::{fs, path::Path};
+
+/// Errors which may occur when loading a [`SignatureDatabase`].
+#[derive(Debug, thiserror::Error)]
+pub enum SignatureDatabaseError {
+    /// Failed to read the database file.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// Failed to parse a signature.
+    #[error("invalid signature on line {line}: {source}")]
+    Parse {
+        /// The 1-based line number of the signature.
+        line: usize,
+        /// The parser error.
+        #[source]
+        source: parser::Error,
+    },
+}
+
+/// An offline database of function, event and error signatures, used to decode calls to contracts
+/// whose ABI is unknown.
+///
+/// Selectors may collide, so each selector maps to all known signatures with that selector.
+///
+/// The file format has one [human-readable ABI] item per line, e.g. as exported from a 4byte
+/// directory. Lines without an item keyword are parsed as function signatures, and empty lines and
+/// lines starting with `#` are skipped:
+///
+/// ```text
+/// # ERC-20
+/// transfer(address,uint256)
+/// function balanceOf(address owner) returns (uint256)
+/// event Transfer(address indexed from, address indexed to, uint256 value)
+/// error ERC20InsufficientBalance(address sender, uint256 balance, uint256 needed)
+/// ```
+///
+/// [human-readable ABI]: https://docs.ethers.org/v5/api/utils/abi/formats/#abi-formats--human-readable-abi
+#[derive(Clone, Debug, Default)]
+pub struct SignatureDatabase {
+    functions: SelectorHashMap<Vec<Function>>,
+    events: B256HashMap<Vec<Event>>,
+    errors: SelectorHashMap<Vec<AbiError>>,
+}
+
+impl SignatureDatabase {
+    /// Creates an empty database.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a database from its file contents.
+    pub fn parse(contents: &str) -> Result<Self, SignatureDatabaseError> {
+        let mut db = Self::default();
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let item = match line.split_once(' ') {
+                Some(("function" | "event" | "error", _)) => AbiItem::parse(line),
+                _ => Function::parse(line).map(Into::into),
+            };
+            match item
+                .map_err(|source| SignatureDatabaseError::Parse { line: index + 1, source })?
+            {
+                AbiItem::Function(function) => db.insert_function(function.into_owned()),
+                AbiItem::Event(event) => db.insert_event(event.into_owned()),
+                AbiItem::Error(error) => db.insert_error(error.into_owned()),
+                _ => {}
+            }
+        }
+        Ok(db)
+    }
+
+    /// Loads a database from a file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SignatureDatabaseError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Adds a function signature, unless it is already known.
+    pub fn insert_function(&mut self, function: Function) {
+        let functions = self.functions.entry(function.selector()).or_default();
+        if !functions.iter().any(|known| known.signature() == function.signature()) {
+            functions.push(function);
+        }
+    }
+
+    /// Adds an event signature, unless it is already known.
+    ///
+    /// Events with the same signature are distinct if different parameters are indexed.
+    pub fn insert_event(&mut self, event: Event) {
+        let indexed =
+            |event: &Event| event.inputs.iter().map(|input| input.indexed).collect::<Vec<_>>();
+        let events = self.events.entry(event.selector()).or_default();
+        let duplicate = |known: &Event| {
+            known.signature() == event.signature() && indexed(known) == indexed(&event)
+        };
+        if !events.iter().any(duplicate) {
+            events.push(event);
+        }
+    }
+
+    /// Adds an error signature, unless it is already known.
+    pub fn insert_error(&mut self, error: AbiError) {
+        let errors = self.errors.entry(error.selector()).or_default();
+        if !errors.iter().any(|known| known.signature() == error.signature()) {
+            errors.push(error);
+        }
+    }
+
+    /// Adds the functions, events and errors of the ABI.
+    pub fn extend_from_abi(&mut self, abi: &JsonAbi) {
+        abi.functions().cloned().for_each(|function| self.insert_function(function));
+        abi.events().cloned().for_each(|event| self.insert_event(event));
+        abi.errors().cloned().for_each(|error| self.insert_error(error));
+    }
+
+    /// Adds the signatures of another database.
+    pub fn extend(&mut self, other: Self) {
+        other.functions.into_values().flatten().for_each(|function| self.insert_function(function));
+        other.events.into_values().flatten().for_each(|event| self.insert_event(event));
+        other.errors.into_values().flatten().for_each(|error| self.insert_error(error));
+    }
+
+    /// Returns the functions with the given selector.
+    pub fn functions(&self, selector: &Selector) -> &[Function] {
+        self.functions.get(selector).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Returns the events with the given topic.
+    pub fn events(&self, topic: &B256) -> &[Event] {
+        self.events.get(topic).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Returns the errors with the given selector.
+    pub fn errors(&self, selector: &Selector) -> &[AbiError] {
+        self.errors.get(selector).map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{b256, hex};
+
+    #[test]
+    fn parse_error_line() {
+        let contents = "# ERC-20\n\ntransfer(address,uint256)\nevent Transfer(address,\n";
+        let err = SignatureDatabase::parse(contents).unwrap_err();
+        assert!(matches!(err, SignatureDatabaseError::Parse { line: 4, .. }), "{err}");
+        assert!(err.to_string().starts_with("invalid signature on line 4: "), "{err}");
+    }
+
+    #[test]
+    fn deduplicate_signatures() {
+        let db = SignatureDatabase::parse(
+            "transfer(address,uint256)
+            function transfer(address to, uint256 amount) returns (bool)
+            event Transfer(address indexed from, address indexed to, uint256 value)
+            event Transfer(address indexed, address indexed, uint256)
+            event Transfer(address indexed from, address indexed to, uint256 indexed id)
+            error ERC20InsufficientBalance(address sender, uint256 balance, uint256 needed)
+            error ERC20InsufficientBalance(address,uint256,uint256)",
+        )
+        .unwrap();
+
+        assert_eq!(db.functions(&hex!("a9059cbb").into()).len(), 1);
+        // ERC-20 and ERC-721 transfers share the topic, but not the indexed parameters
+        let topic = b256!("0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef");
+        assert_eq!(db.events(&topic).len(), 2);
+        assert_eq!(db.errors(&hex!("e450d38c").into()).len(), 1);
+
+        let mut extended = db.clone();
+        extended.extend(db);
+        assert_eq!(extended.functions(&hex!("a9059cbb").into()).len(), 1);
+        assert_eq!(extended.events(&topic).len(), 2);
+    }
+
+    #[test]
+    fn selector_collisions() {
+        let db = SignatureDatabase::parse("transfer(address,uint256)\nmany_msg_babbage(bytes1)")
+            .unwrap();
+        let signatures: Vec<_> =
+            db.functions(&hex!("a9059cbb").into()).iter().map(Function::signature).collect();
+        assert_eq!(signatures, ["transfer(address,uint256)", "many_msg_babbage(bytes1)"]);
+        assert!(db.functions(&Selector::ZERO).is_empty());
+    }
+}
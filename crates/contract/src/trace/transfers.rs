@@ -0,0 +1,605 @@
+use alloy_primitives::{Address, Log, I256, U256};
+use alloy_rpc_types_trace::{
+    geth::{CallFrame, CallKind, DiffMode},
+    parity::{Action, CallType, Delta, StateDiff, TransactionTrace},
+};
+use alloy_sol_types::{sol, SolEvent};
+use std::collections::{btree_map, BTreeMap};
+
+sol! {
+    interface IERC20 {
+        event Transfer(address indexed from, address indexed to, uint256 value);
+    }
+
+    interface IERC721 {
+        event Transfer(address indexed from, address indexed to, uint256 indexed tokenId);
+    }
+
+    interface IERC1155 {
+        event TransferSingle(address indexed operator, address indexed from, address indexed to, uint256 id, uint256 value);
+        event TransferBatch(address indexed operator, address indexed from, address indexed to, uint256[] ids, uint256[] values);
+    }
+}
+
+/// An asset that can be transferred between accounts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Asset {
+    /// Native ether.
+    Native,
+    /// An ERC-20 token.
+    Erc20(Address),
+    /// A non-fungible ERC-721 token.
+    Erc721 {
+        /// The token contract.
+        token: Address,
+        /// The token id.
+        id: U256,
+    },
+    /// An ERC-1155 token.
+    Erc1155 {
+        /// The token contract.
+        token: Address,
+        /// The token id.
+        id: U256,
+    },
+}
+
+/// A transfer of an [`Asset`] between two accounts.
+///
+/// Mints are transfers from the zero address, and burns are transfers to the zero address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Transfer {
+    /// The transferred asset.
+    pub asset: Asset,
+    /// The sender.
+    pub from: Address,
+    /// The recipient.
+    pub to: Address,
+    /// The transferred amount, which is always one for ERC-721 tokens.
+    pub amount: U256,
+}
+
+/// The assets moved by a transaction.
+///
+/// Native transfers are extracted from the call trace, which includes value transfers of internal
+/// calls, contract creations and selfdestructs. Calls that reverted are skipped along with all of
+/// their subcalls. Token transfers are decoded from the standard ERC-20, ERC-721 and ERC-1155
+/// events in the receipt logs.
+///
+/// Gas fees are not transfers, so the [`BalanceChanges`] of the sender and the fee recipient
+/// differ from their actual balance changes by the fees, see [`cross_check`](Self::cross_check).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransferAnalysis {
+    /// The native transfers in execution order, followed by the token transfers in log order.
+    pub transfers: Vec<Transfer>,
+}
+
+impl TransferAnalysis {
+    /// Extracts the transfers of a transaction from its geth call trace and receipt logs.
+    pub fn from_call_frame<'a>(frame: &CallFrame, logs: impl IntoIterator<Item = &'a Log>) -> Self {
+        let mut transfers = Vec::new();
+        let mut iter = frame.iter();
+        while let Some(call) = iter.next() {
+            if call.error.is_some() {
+                iter.skip_children();
+                continue;
+            }
+            let to = call.to.unwrap_or_default();
+            let value = call.value.unwrap_or_default();
+            let moves_value = match call.kind() {
+                Some(kind) => matches!(kind, CallKind::Call | CallKind::Create | CallKind::Create2),
+                None => call.typ == "SELFDESTRUCT",
+            };
+            if moves_value && !value.is_zero() {
+                transfers.push(Transfer {
+                    asset: Asset::Native,
+                    from: call.from,
+                    to,
+                    amount: value,
+                });
+            }
+        }
+        transfers.extend(logs.into_iter().flat_map(token_transfers));
+        Self { transfers }
+    }
+
+    /// Extracts the transfers of a transaction from its parity traces and receipt logs.
+    ///
+    /// The traces must be in depth-first pre-order, as returned by nodes.
+    pub fn from_parity_traces<'a, 'b>(
+        traces: impl IntoIterator<Item = &'a TransactionTrace>,
+        logs: impl IntoIterator<Item = &'b Log>,
+    ) -> Self {
+        let mut transfers = Vec::new();
+        let mut reverted: Option<&[usize]> = None;
+        for trace in traces {
+            if reverted.is_some_and(|reverted| trace.trace_address.starts_with(reverted)) {
+                continue;
+            }
+            if trace.error.is_some() {
+                reverted = Some(&trace.trace_address);
+                continue;
+            }
+            let transfer = match &trace.action {
+                Action::Call(call) if matches!(call.call_type, CallType::Call | CallType::None) => {
+                    Some((call.from, call.to, call.value))
+                }
+                Action::Create(create) => trace
+                    .result
+                    .as_ref()
+                    .and_then(|result| result.created_contract())
+                    .map(|address| (create.from, address, create.value)),
+                Action::Selfdestruct(selfdestruct) => {
+                    Some((selfdestruct.address, selfdestruct.refund_address, selfdestruct.balance))
+                }
+                _ => None,
+            };
+            if let Some((from, to, amount)) = transfer.filter(|(_, _, amount)| !amount.is_zero()) {
+                transfers.push(Transfer { asset: Asset::Native, from, to, amount });
+            }
+        }
+        transfers.extend(logs.into_iter().flat_map(token_transfers));
+        Self { transfers }
+    }
+
+    /// Returns the native transfers.
+    pub fn native_transfers(&self) -> impl Iterator<Item = &Transfer> {
+        self.transfers.iter().filter(|transfer| transfer.asset == Asset::Native)
+    }
+
+    /// Returns the token transfers.
+    pub fn token_transfers(&self) -> impl Iterator<Item = &Transfer> {
+        self.transfers.iter().filter(|transfer| transfer.asset != Asset::Native)
+    }
+
+    /// Returns the net balance change of every account and asset.
+    pub fn balance_changes(&self) -> BalanceChanges {
+        let mut changes = BalanceChanges::default();
+        for transfer in &self.transfers {
+            let amount = to_i256(transfer.amount);
+            changes.record(transfer.from, transfer.asset, -amount);
+            changes.record(transfer.to, transfer.asset, amount);
+        }
+        changes
+    }
+
+    /// Compares the native balance changes against the balance changes of a parity state diff,
+    /// and returns all accounts for which they differ.
+    ///
+    /// Unless the gas fees are [recorded](BalanceChanges::record) first, the sender and the fee
+    /// recipient of the transaction are reported as mismatches.
+    pub fn cross_check(&self, state_diff: &StateDiff) -> Vec<BalanceMismatch> {
+        self.balance_changes().cross_check(state_diff)
+    }
+
+    /// Like [`cross_check`](Self::cross_check), but against the diff of the geth prestate tracer.
+    pub fn cross_check_diff(&self, diff: &DiffMode) -> Vec<BalanceMismatch> {
+        self.cross_check(&diff.to_state_diff())
+    }
+}
+
+/// Net balance changes per account and [`Asset`], see [`TransferAnalysis::balance_changes`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BalanceChanges {
+    changes: BTreeMap<Address, BTreeMap<Asset, I256>>,
+}
+
+impl BalanceChanges {
+    /// Adds a balance change, e.g. the gas fee paid by the sender of the transaction.
+    pub fn record(&mut self, address: Address, asset: Asset, delta: I256) {
+        let assets = self.changes.entry(address).or_default();
+        match assets.entry(asset) {
+            btree_map::Entry::Occupied(mut entry) => {
+                *entry.get_mut() = entry.get().saturating_add(delta);
+                if entry.get().is_zero() {
+                    entry.remove();
+                }
+            }
+            btree_map::Entry::Vacant(entry) => {
+                if !delta.is_zero() {
+                    entry.insert(delta);
+                }
+            }
+        }
+        if assets.is_empty() {
+            self.changes.remove(&address);
+        }
+    }
+
+    /// Returns the net change of the account's balance of the asset.
+    pub fn get(&self, address: Address, asset: &Asset) -> I256 {
+        self.changes.get(&address).and_then(|assets| assets.get(asset)).copied().unwrap_or_default()
+    }
+
+    /// Returns the net change of the account's native balance.
+    pub fn native(&self, address: Address) -> I256 {
+        self.get(address, &Asset::Native)
+    }
+
+    /// Returns the non-zero balance changes of the account.
+    pub fn account(&self, address: Address) -> impl Iterator<Item = (&Asset, &I256)> {
+        self.changes.get(&address).into_iter().flatten()
+    }
+
+    /// Returns all non-zero balance changes, ordered by account.
+    pub fn iter(&self) -> impl Iterator<Item = (Address, &Asset, &I256)> {
+        self.changes.iter().flat_map(|(address, assets)| {
+            assets.iter().map(|(asset, delta)| (*address, asset, delta))
+        })
+    }
+
+    /// Returns true if no balances changed.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Compares the native balance changes against the balance changes of a parity state diff,
+    /// and returns all accounts for which they differ.
+    pub fn cross_check(&self, state_diff: &StateDiff) -> Vec<BalanceMismatch> {
+        let mut actual: BTreeMap<Address, I256> = state_diff
+            .iter()
+            .map(|(address, diff)| {
+                let delta = match &diff.balance {
+                    Delta::Unchanged => I256::ZERO,
+                    Delta::Added(balance) => to_i256(*balance),
+                    Delta::Removed(balance) => -to_i256(*balance),
+                    Delta::Changed(changed) => to_i256(changed.to) - to_i256(changed.from),
+                };
+                (*address, delta)
+            })
+            .collect();
+        for (address, assets) in &self.changes {
+            if assets.contains_key(&Asset::Native) {
+                actual.entry(*address).or_default();
+            }
+        }
+
+        actual
+            .into_iter()
+            .filter_map(|(address, actual)| {
+                let expected = self.native(address);
+                (expected != actual).then_some(BalanceMismatch { address, expected, actual })
+            })
+            .collect()
+    }
+}
+
+/// An account whose native balance change differs from its transfers, see
+/// [`BalanceChanges::cross_check`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BalanceMismatch {
+    /// The account.
+    pub address: Address,
+    /// The balance change according to the transfers.
+    pub expected: I256,
+    /// The balance change according to the state diff.
+    pub actual: I256,
+}
+
+fn to_i256(value: U256) -> I256 {
+    I256::try_from(value).unwrap_or(I256::MAX)
+}
+
+/// Decodes the token transfers of a log, if it is a standard transfer event.
+fn token_transfers(log: &Log) -> Vec<Transfer> {
+    let token = log.address;
+    let topics = log.data.topics();
+    match (topics.first(), topics.len()) {
+        (Some(&IERC20::Transfer::SIGNATURE_HASH), 3) => {
+            IERC20::Transfer::decode_log_data(&log.data)
+                .map(|event| {
+                    vec![Transfer {
+                        asset: Asset::Erc20(token),
+                        from: event.from,
+                        to: event.to,
+                        amount: event.value,
+                    }]
+                })
+                .unwrap_or_default()
+        }
+        (Some(&IERC721::Transfer::SIGNATURE_HASH), 4) => {
+            IERC721::Transfer::decode_log_data(&log.data)
+                .map(|event| {
+                    vec![Transfer {
+                        asset: Asset::Erc721 { token, id: event.tokenId },
+                        from: event.from,
+                        to: event.to,
+                        amount: U256::from(1),
+                    }]
+                })
+                .unwrap_or_default()
+        }
+        (Some(&IERC1155::TransferSingle::SIGNATURE_HASH), _) => {
+            IERC1155::TransferSingle::decode_log_data(&log.data)
+                .map(|event| {
+                    vec![Transfer {
+                        asset: Asset::Erc1155 { token, id: event.id },
+                        from: event.from,
+                        to: event.to,
+                        amount: event.value,
+                    }]
+                })
+                .unwrap_or_default()
+        }
+        (Some(&IERC1155::TransferBatch::SIGNATURE_HASH), _) => {
+            IERC1155::TransferBatch::decode_log_data(&log.data)
+                .map(|event| {
+                    event
+                        .ids
+                        .iter()
+                        .zip(&event.values)
+                        .map(|(id, amount)| Transfer {
+                            asset: Asset::Erc1155 { token, id: *id },
+                            from: event.from,
+                            to: event.to,
+                            amount: *amount,
+                        })
+                        .collect()
+                })
+                .unwrap_or_default()
+        }
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{LogData, B256};
+    use alloy_rpc_types_trace::{
+        geth::AccountState,
+        parity::{AccountDiff, CallAction},
+    };
+    use similar_asserts::assert_eq;
+
+    fn log<E: SolEvent>(token: Address, event: &E) -> Log {
+        Log { address: token, data: event.encode_log_data() }
+    }
+
+    #[test]
+    fn native_and_token_transfers() {
+        let (sender, router, pool, token) = (
+            Address::with_last_byte(1),
+            Address::with_last_byte(2),
+            Address::with_last_byte(3),
+            Address::with_last_byte(4),
+        );
+        let frame = CallFrame {
+            from: sender,
+            to: Some(router),
+            value: Some(U256::from(100)),
+            typ: "CALL".to_string(),
+            calls: vec![
+                CallFrame {
+                    from: router,
+                    to: Some(pool),
+                    value: Some(U256::from(60)),
+                    typ: "CALL".to_string(),
+                    ..Default::default()
+                },
+                // reverted calls don't move value, not even in their subcalls
+                CallFrame {
+                    from: router,
+                    to: Some(pool),
+                    value: Some(U256::from(40)),
+                    error: Some("execution reverted".to_string()),
+                    typ: "CALL".to_string(),
+                    calls: vec![CallFrame {
+                        from: pool,
+                        to: Some(sender),
+                        value: Some(U256::from(1)),
+                        typ: "CALL".to_string(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                CallFrame {
+                    from: router,
+                    to: Some(pool),
+                    value: Some(U256::from(40)),
+                    typ: "DELEGATECALL".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let logs = [
+            log(token, &IERC20::Transfer { from: pool, to: sender, value: U256::from(5) }),
+            log(
+                token,
+                &IERC721::Transfer { from: Address::ZERO, to: sender, tokenId: U256::from(7) },
+            ),
+            log(
+                token,
+                &IERC1155::TransferBatch {
+                    operator: router,
+                    from: sender,
+                    to: pool,
+                    ids: vec![U256::from(1), U256::from(2)],
+                    values: vec![U256::from(10), U256::from(20)],
+                },
+            ),
+            Log {
+                address: token,
+                data: LogData::new_unchecked(vec![B256::ZERO], Default::default()),
+            },
+        ];
+
+        let analysis = TransferAnalysis::from_call_frame(&frame, &logs);
+        assert_eq!(analysis.native_transfers().count(), 2);
+        assert_eq!(analysis.token_transfers().count(), 4);
+        assert_eq!(
+            analysis,
+            TransferAnalysis::from_parity_traces(&frame.to_parity_traces(), &logs)
+        );
+
+        let changes = analysis.balance_changes();
+        assert_eq!(changes.native(sender), I256::try_from(-100).unwrap());
+        assert_eq!(changes.native(router), I256::try_from(40).unwrap());
+        assert_eq!(changes.native(pool), I256::try_from(60).unwrap());
+        assert_eq!(changes.get(sender, &Asset::Erc20(token)), I256::try_from(5).unwrap());
+        assert_eq!(
+            changes.get(sender, &Asset::Erc721 { token, id: U256::from(7) }),
+            I256::try_from(1).unwrap()
+        );
+        assert_eq!(
+            changes.get(pool, &Asset::Erc1155 { token, id: U256::from(2) }),
+            I256::try_from(20).unwrap()
+        );
+
+        let balance = |from: u64, to: u64| AccountDiff {
+            balance: Delta::changed(U256::from(from), U256::from(to)),
+            ..Default::default()
+        };
+        let state_diff = StateDiff(BTreeMap::from([
+            (sender, balance(1_000, 890)),
+            (router, balance(0, 40)),
+            (pool, balance(0, 60)),
+        ]));
+        assert_eq!(
+            analysis.cross_check(&state_diff),
+            vec![BalanceMismatch {
+                address: sender,
+                expected: I256::try_from(-100).unwrap(),
+                actual: I256::try_from(-110).unwrap(),
+            }]
+        );
+
+        let mut changes = analysis.balance_changes();
+        changes.record(sender, Asset::Native, I256::try_from(-10).unwrap());
+        assert!(changes.cross_check(&state_diff).is_empty());
+    }
+
+    #[test]
+    fn erc1155_transfers() {
+        let (operator, from, to, token) = (
+            Address::with_last_byte(1),
+            Address::with_last_byte(2),
+            Address::with_last_byte(3),
+            Address::with_last_byte(4),
+        );
+        let single = IERC1155::TransferSingle {
+            operator,
+            from,
+            to,
+            id: U256::from(7),
+            value: U256::from(3),
+        };
+        assert_eq!(
+            token_transfers(&log(token, &single)),
+            vec![Transfer {
+                asset: Asset::Erc1155 { token, id: U256::from(7) },
+                from,
+                to,
+                amount: U256::from(3),
+            }]
+        );
+
+        let batch = IERC1155::TransferBatch {
+            operator,
+            from,
+            to: Address::ZERO,
+            ids: vec![U256::from(1), U256::from(2)],
+            values: vec![U256::from(10), U256::from(20)],
+        };
+        assert_eq!(
+            token_transfers(&log(token, &batch)),
+            vec![
+                Transfer {
+                    asset: Asset::Erc1155 { token, id: U256::from(1) },
+                    from,
+                    to: Address::ZERO,
+                    amount: U256::from(10),
+                },
+                Transfer {
+                    asset: Asset::Erc1155 { token, id: U256::from(2) },
+                    from,
+                    to: Address::ZERO,
+                    amount: U256::from(20),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parity_traces_skip_reverted_subtrees() {
+        let (sender, router, pool) =
+            (Address::with_last_byte(1), Address::with_last_byte(2), Address::with_last_byte(3));
+        let call = |from, to, value: u64, trace_address: Vec<usize>, error: Option<&str>| {
+            TransactionTrace {
+                action: Action::Call(CallAction {
+                    from,
+                    to,
+                    value: U256::from(value),
+                    call_type: CallType::Call,
+                    ..Default::default()
+                }),
+                error: error.map(Into::into),
+                trace_address,
+                ..Default::default()
+            }
+        };
+        let traces = [
+            call(sender, router, 100, vec![], None),
+            call(router, pool, 40, vec![0], Some("Reverted")),
+            call(pool, sender, 1, vec![0, 0], None),
+            call(pool, router, 2, vec![0, 0, 0], None),
+            call(router, pool, 60, vec![1], None),
+            call(pool, sender, 5, vec![1, 0], None),
+        ];
+
+        let analysis = TransferAnalysis::from_parity_traces(&traces, []);
+        let native = |from, to, amount: u64| Transfer {
+            asset: Asset::Native,
+            from,
+            to,
+            amount: U256::from(amount),
+        };
+        assert_eq!(
+            analysis.transfers,
+            vec![native(sender, router, 100), native(router, pool, 60), native(pool, sender, 5)]
+        );
+    }
+
+    #[test]
+    fn cross_check_diff() {
+        let (sender, recipient, destroyed) =
+            (Address::with_last_byte(1), Address::with_last_byte(2), Address::with_last_byte(3));
+        let analysis = TransferAnalysis {
+            transfers: vec![
+                Transfer {
+                    asset: Asset::Native,
+                    from: sender,
+                    to: recipient,
+                    amount: U256::from(100),
+                },
+                Transfer {
+                    asset: Asset::Native,
+                    from: destroyed,
+                    to: sender,
+                    amount: U256::from(30),
+                },
+            ],
+        };
+        let state = |balance: u64| AccountState {
+            balance: Some(U256::from(balance)),
+            ..Default::default()
+        };
+        let mut diff = DiffMode {
+            pre: BTreeMap::from([(sender, state(1_000)), (destroyed, state(30))]),
+            // the recipient is created, and the destroyed account is missing from the post state
+            post: BTreeMap::from([(sender, state(930)), (recipient, state(100))]),
+        };
+        assert!(analysis.cross_check_diff(&diff).is_empty());
+
+        diff.post.insert(recipient, state(90));
+        assert_eq!(
+            analysis.cross_check_diff(&diff),
+            vec![BalanceMismatch {
+                address: recipient,
+                expected: I256::try_from(100).unwrap(),
+                actual: I256::try_from(90).unwrap(),
+            }]
+        );
+    }
+}
@@ -1,13 +1,29 @@
-use alloy_primitives::{map::HashMap, Address, Bytes, ChainId, U256};
+use alloy_primitives::{map::HashMap, Address, Bytes, ChainId, B256, U256};
+
+/// The name of the [ERC-7677][erc-7677] paymaster service capability.
+///
+/// [erc-7677]: https://eips.ethereum.org/EIPS/eip-7677
+pub const PAYMASTER_SERVICE_CAPABILITY: &str = "paymasterService";
 
 /// Request that a wallet submits a batch of calls in `wallet_sendCalls`
+///
+/// Created with [`SendCallsRequest::new`], and configured with the `with_*` methods.
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SendCallsRequest {
     /// RPC version
     pub version: String,
+    /// Identifier of the batch, chosen by the wallet if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     /// Sender's address
     pub from: Address,
+    /// Id of target chain
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "alloy_serde::quantity::opt")]
+    pub chain_id: Option<ChainId>,
+    /// Whether the wallet must execute the calls atomically
+    #[serde(default)]
+    pub atomic_required: bool,
     /// A batch of calls to be submitted
     pub calls: Vec<CallParams>,
     /// Enabled permissions per chain
@@ -15,8 +31,149 @@ pub struct SendCallsRequest {
     pub capabilities: Option<HashMap<String, serde_json::Value>>,
 }
 
-/// Call parameters for `wallet_sendCalls`
+impl SendCallsRequest {
+    /// Creates a request to submit the calls from the given address.
+    pub fn new(version: impl Into<String>, from: Address, calls: Vec<CallParams>) -> Self {
+        Self {
+            version: version.into(),
+            id: None,
+            from,
+            chain_id: None,
+            atomic_required: false,
+            calls,
+            capabilities: None,
+        }
+    }
+
+    /// Sets the identifier of the batch.
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Sets the id of the target chain.
+    pub const fn with_chain_id(mut self, chain_id: ChainId) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Sets whether the wallet must execute the calls atomically.
+    pub const fn with_atomic_required(mut self, atomic_required: bool) -> Self {
+        self.atomic_required = atomic_required;
+        self
+    }
+
+    /// Sets the capabilities of the request.
+    pub fn with_capabilities(mut self, capabilities: HashMap<String, serde_json::Value>) -> Self {
+        self.capabilities = Some(capabilities);
+        self
+    }
+
+    /// Returns `true` if the request asks the wallet to use a paymaster service.
+    pub fn requests_paymaster_service(&self) -> bool {
+        self.capabilities
+            .as_ref()
+            .is_some_and(|capabilities| capabilities.contains_key(PAYMASTER_SERVICE_CAPABILITY))
+    }
+}
+
+/// Response of `wallet_sendCalls`
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendCallsResponse {
+    /// Identifier of the batch, used to query its status
+    pub id: String,
+    /// Capability-specific response data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<HashMap<String, serde_json::Value>>,
+}
+
+/// Status of a batch of calls returned by `wallet_getCallsStatus`
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallsStatus {
+    /// RPC version
+    pub version: String,
+    /// Identifier of the batch
+    pub id: String,
+    /// Id of the chain the batch was submitted to
+    #[serde(with = "alloy_serde::quantity")]
+    pub chain_id: ChainId,
+    /// Status code of the batch, see [`CallsStatus::is_pending`] and related methods
+    pub status: u16,
+    /// Whether the calls were executed atomically
+    #[serde(default)]
+    pub atomic: bool,
+    /// Receipts of the transactions that included the calls
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receipts: Option<Vec<CallReceipt>>,
+    /// Capability-specific status data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<HashMap<String, serde_json::Value>>,
+}
+
+impl CallsStatus {
+    /// Returns `true` if the batch has been received by the wallet but not yet completed
+    /// on-chain (`1xx`).
+    pub const fn is_pending(&self) -> bool {
+        self.status < 200
+    }
+
+    /// Returns `true` if the batch has been included on-chain without reverts (`2xx`).
+    pub const fn is_confirmed(&self) -> bool {
+        200 <= self.status && self.status < 300
+    }
+
+    /// Returns `true` if the batch failed, either off-chain (`4xx`), by reverting completely
+    /// (`5xx`) or by reverting partially (`6xx`).
+    pub const fn is_failure(&self) -> bool {
+        self.status >= 400
+    }
+}
+
+/// Receipt of a transaction that included calls of a batch
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallReceipt {
+    /// Logs emitted by the calls
+    pub logs: Vec<CallLog>,
+    /// `0x1` on success, `0x0` on failure
+    #[serde(with = "alloy_serde::quantity")]
+    pub status: u64,
+    /// Hash of the block the transaction was included in
+    pub block_hash: B256,
+    /// Number of the block the transaction was included in
+    #[serde(with = "alloy_serde::quantity")]
+    pub block_number: u64,
+    /// Gas used by the transaction
+    #[serde(with = "alloy_serde::quantity")]
+    pub gas_used: u64,
+    /// Hash of the transaction
+    pub transaction_hash: B256,
+}
+
+impl CallReceipt {
+    /// Returns `true` if the transaction succeeded.
+    pub const fn is_success(&self) -> bool {
+        self.status == 1
+    }
+}
+
+/// Log emitted by a call of a batch
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CallLog {
+    /// Address of the contract that emitted the log
+    pub address: Address,
+    /// Data of the log
+    pub data: Bytes,
+    /// Topics of the log
+    pub topics: Vec<B256>,
+}
+
+/// Call parameters for `wallet_sendCalls`
+///
+/// Created with [`CallParams::default`], and configured with the `with_*` methods.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CallParams {
     /// Recipient address
@@ -29,9 +186,45 @@ pub struct CallParams {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub value: Option<U256>,
     /// Id of target chain
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "alloy_serde::quantity::opt")]
     pub chain_id: Option<ChainId>,
+    /// Call-specific capabilities
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<HashMap<String, serde_json::Value>>,
+}
+
+impl CallParams {
+    /// Sets the recipient address.
+    pub const fn with_to(mut self, to: Address) -> Self {
+        self.to = Some(to);
+        self
+    }
+
+    /// Sets the call data.
+    pub fn with_data(mut self, data: Bytes) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// Sets the transferred value.
+    pub const fn with_value(mut self, value: U256) -> Self {
+        self.value = Some(value);
+        self
+    }
+
+    /// Sets the id of the target chain.
+    pub const fn with_chain_id(mut self, chain_id: ChainId) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Sets the call-specific capabilities.
+    pub fn with_capabilities(mut self, capabilities: HashMap<String, serde_json::Value>) -> Self {
+        self.capabilities = Some(capabilities);
+        self
+    }
 }
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -41,7 +234,10 @@ mod tests {
     fn test_serialization_deserialization() {
         let sample_request = SendCallsRequest {
             version: "1.0".to_string(),
+            id: None,
             from: Address::default(),
+            chain_id: None,
+            atomic_required: false,
             calls: vec![
                 CallParams {
                     to: Some(Address::default()),
@@ -49,6 +245,7 @@ mod tests {
                     )),
                     value: Some(U256::from(0x9184e72au64)),
                     chain_id: Some(ChainId::from(1u64)),
+                    capabilities: None,
                 },
                 CallParams {
                     to: Some(Address::default()),
@@ -57,6 +254,7 @@ mod tests {
                     ),
                     value: Some(U256::from(0x182183u64)),
                     chain_id: Some(ChainId::from(1u64)),
+                    capabilities: None,
                 },
             ],
             capabilities: None,
@@ -66,4 +264,94 @@ mod tests {
         let deserialized: SendCallsRequest = serde_json::from_str(&serialized).unwrap();
         assert_eq!(sample_request, deserialized);
     }
+
+    #[test]
+    fn test_calls_status() {
+        let status: CallsStatus = serde_json::from_str(
+            r#"{
+                "version": "2.0.0",
+                "chainId": "0x01",
+                "id": "0x00000000000000000000000000000000000000000000000000000000000000000e670ec64341771606e55d6b4ca35a1a6b75ee3d5145a99d05921026d1527331",
+                "status": 200,
+                "atomic": true,
+                "receipts": [
+                    {
+                        "logs": [
+                            {
+                                "address": "0xa922b54716264130634d6ff183747a8ead91a40b",
+                                "topics": ["0x5a2a90727cc9d000dd060b1132a5c977c9702bb3a52afe360c9c22f0e9451a68"],
+                                "data": "0xabcd"
+                            }
+                        ],
+                        "status": "0x1",
+                        "blockHash": "0xf19bbafd9fd0124ec110b848e8de4ab4f62bf60c189524e54213285e7f540d4a",
+                        "blockNumber": "0xabcd",
+                        "gasUsed": "0xdef",
+                        "transactionHash": "0x9b7bb827c2e5e3c1a0a44dc53e573aa0b3af3bd1f9f5ed03071b100bb039eaff"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert!(status.is_confirmed());
+        assert!(!status.is_pending());
+        assert!(!status.is_failure());
+        assert_eq!(status.chain_id, 1);
+        let receipts = status.receipts.as_ref().unwrap();
+        assert!(receipts[0].is_success());
+        assert_eq!(receipts[0].block_number, 0xabcd);
+        assert_eq!(receipts[0].logs[0].data, Bytes::from_static(&[0xab, 0xcd]));
+
+        let serialized = serde_json::to_string(&status).unwrap();
+        assert_eq!(serde_json::from_str::<CallsStatus>(&serialized).unwrap(), status);
+    }
+
+    #[test]
+    fn test_send_calls_request_capabilities() {
+        let request: SendCallsRequest = serde_json::from_str(
+            r#"{
+                "version": "2.0.0",
+                "from": "0xd46e8dd67c5d32be8058bb8eb970870f07244567",
+                "chainId": "0x01",
+                "atomicRequired": true,
+                "calls": [{ "to": "0xd46e8dd67c5d32be8058bb8eb970870f07244567", "value": "0x9184e72a" }],
+                "capabilities": { "paymasterService": { "url": "https://paymaster.example" } }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(request.chain_id, Some(1));
+        assert!(request.atomic_required);
+        assert!(request.requests_paymaster_service());
+    }
+
+    #[test]
+    fn test_send_calls_request_builder() {
+        let from = Address::repeat_byte(1);
+        let call = CallParams::default()
+            .with_to(Address::repeat_byte(2))
+            .with_value(U256::from(1))
+            .with_chain_id(1);
+        let request = SendCallsRequest::new("2.0.0", from, vec![call])
+            .with_id("0x01")
+            .with_chain_id(1)
+            .with_atomic_required(true);
+
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "version": "2.0.0",
+                "id": "0x01",
+                "from": from,
+                "chainId": "0x1",
+                "atomicRequired": true,
+                "calls": [{ "to": Address::repeat_byte(2), "value": "0x1", "chainId": "0x1" }],
+            })
+        );
+
+        // Chain ids of calls sent as numbers are accepted as well.
+        let call: CallParams = serde_json::from_str(r#"{"chainId":1}"#).unwrap();
+        assert_eq!(call.chain_id, Some(1));
+    }
 }
@@ -13,11 +13,88 @@ pub struct DelegationCapability {
     pub addresses: Vec<Address>,
 }
 
+/// The support of a wallet for executing a batch of calls atomically.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AtomicStatus {
+    /// The wallet executes calls atomically.
+    Supported,
+    /// The wallet can upgrade the account to execute calls atomically, pending user approval.
+    Ready,
+    /// The wallet does not execute calls atomically.
+    Unsupported,
+}
+
+/// The `atomic` capability of [EIP-5792][eip-5792].
+///
+/// [eip-5792]: https://eips.ethereum.org/EIPS/eip-5792
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize, Serialize)]
+pub struct AtomicCapability {
+    /// Whether the wallet executes batches atomically.
+    pub status: AtomicStatus,
+}
+
+impl AtomicCapability {
+    /// Returns `true` if atomic batches can be sent, possibly after the user upgrades the account.
+    pub const fn is_available(&self) -> bool {
+        matches!(self.status, AtomicStatus::Supported | AtomicStatus::Ready)
+    }
+}
+
+/// The `paymasterService` capability of [ERC-7677][erc-7677].
+///
+/// [erc-7677]: https://eips.ethereum.org/EIPS/eip-7677
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Deserialize, Serialize)]
+pub struct PaymasterServiceCapability {
+    /// Whether the wallet supports sponsoring calls through a paymaster service.
+    pub supported: bool,
+}
+
 /// Wallet capabilities for a specific chain.
+///
+/// Created with [`Capabilities::default`], and configured with the `with_*` methods.
 #[derive(Debug, Default, Clone, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Capabilities {
     /// The capability to delegate.
+    #[serde(default)]
     pub delegation: DelegationCapability,
+    /// The capability to execute batches atomically.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub atomic: Option<AtomicCapability>,
+    /// The capability to sponsor calls through a paymaster service.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paymaster_service: Option<PaymasterServiceCapability>,
+}
+
+impl Capabilities {
+    /// Sets the capability to delegate.
+    pub fn with_delegation(mut self, delegation: DelegationCapability) -> Self {
+        self.delegation = delegation;
+        self
+    }
+
+    /// Sets the capability to execute batches atomically.
+    pub const fn with_atomic(mut self, atomic: AtomicCapability) -> Self {
+        self.atomic = Some(atomic);
+        self
+    }
+
+    /// Sets the capability to sponsor calls through a paymaster service.
+    pub const fn with_paymaster_service(mut self, paymaster: PaymasterServiceCapability) -> Self {
+        self.paymaster_service = Some(paymaster);
+        self
+    }
+
+    /// Returns `true` if the wallet can execute batches atomically.
+    pub fn supports_atomic(&self) -> bool {
+        self.atomic.is_some_and(|atomic| atomic.is_available())
+    }
+
+    /// Returns `true` if the wallet supports paymaster services.
+    pub fn supports_paymaster_service(&self) -> bool {
+        self.paymaster_service.is_some_and(|paymaster| paymaster.supported)
+    }
 }
 
 /// A map of wallet capabilities per chain ID.
@@ -46,6 +123,7 @@ mod tests {
                 delegation: DelegationCapability {
                     addresses: vec![address!("90f79bf6eb2c4f870365e785982e1f101e93b906")],
                 },
+                ..Default::default()
             },
         )]));
         assert_eq!(serde_json::to_string(&caps).unwrap(), "{\"0x69420\":{\"delegation\":{\"addresses\":[\"0x90f79bf6eb2c4f870365e785982e1f101e93b906\"]}}}");
@@ -72,6 +150,7 @@ mod tests {
                     delegation: DelegationCapability {
                         addresses: vec![address!("90f79bf6eb2c4f870365e785982e1f101e93b906")],
                     },
+                    ..Default::default()
                 },
             )]))
         );
@@ -85,6 +164,7 @@ mod tests {
                 delegation: DelegationCapability {
                     addresses: vec![address!("90f79bf6eb2c4f870365e785982e1f101e93b906")],
                 },
+                ..Default::default()
             },
         )]));
 
@@ -105,7 +185,10 @@ mod tests {
     fn test_capabilities_with_empty_delegation() {
         let caps = WalletCapabilities(HashMap::from_iter([(
             0x12345,
-            Capabilities { delegation: DelegationCapability { addresses: vec![] } },
+            Capabilities {
+                delegation: DelegationCapability { addresses: vec![] },
+                ..Default::default()
+            },
         )]));
 
         // Verify that delegation exists but contains no addresses.
@@ -116,4 +199,29 @@ mod tests {
         let serialized = serde_json::to_string(&caps).unwrap();
         assert_eq!(serialized, "{\"0x12345\":{\"delegation\":{\"addresses\":[]}}}");
     }
+
+    #[test]
+    fn de_eip5792_capabilities() {
+        let caps: WalletCapabilities = serde_json::from_str(
+            r#"{
+                "0x2105": {
+                    "atomic": { "status": "supported" },
+                    "paymasterService": { "supported": true }
+                },
+                "0x14a34": {
+                    "atomic": { "status": "unsupported" }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let base = caps.get(0x2105).unwrap();
+        assert!(base.supports_atomic());
+        assert!(base.supports_paymaster_service());
+        assert!(base.delegation.addresses.is_empty());
+
+        let base_sepolia = caps.get(0x14a34).unwrap();
+        assert!(!base_sepolia.supports_atomic());
+        assert!(!base_sepolia.supports_paymaster_service());
+    }
 }
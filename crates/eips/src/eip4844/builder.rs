@@ -200,6 +200,27 @@ pub trait SidecarCoder {
 
     /// Decode all slices of data from the blobs.
     fn decode_all(&mut self, blobs: &[Blob]) -> Option<Vec<Vec<u8>>>;
+
+    /// Calculate the maximum number of bytes that can be stored in `num_blobs`
+    /// blobs with a single call to [`SidecarCoder::code`].
+    ///
+    /// The default implementation searches for the largest input for which
+    /// [`SidecarCoder::required_fe`] fits, coders should override it if the
+    /// capacity can be computed directly.
+    fn max_data_len(&self, num_blobs: usize) -> usize {
+        let capacity = num_blobs * FIELD_ELEMENTS_PER_BLOB as usize;
+        let zeros = vec![0u8; num_blobs * BYTES_PER_BLOB];
+        let (mut lo, mut hi) = (0, zeros.len());
+        while lo < hi {
+            let mid = (lo + hi).div_ceil(2);
+            if self.required_fe(&zeros[..mid]) <= capacity {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+        lo
+    }
 }
 
 /// Simple coder that only uses the last 31 bytes of each blob. This is the
@@ -231,6 +252,9 @@ pub trait SidecarCoder {
 pub struct SimpleCoder;
 
 impl SimpleCoder {
+    /// The maximum length of a single piece of data accepted when decoding.
+    const MAX_ALLOCATION_SIZE: usize = 2_097_152; // 2 MiB
+
     /// Decode an some bytes from an iterator of valid FEs.
     ///
     /// Returns `Ok(Some(data))` if there is some data.
@@ -248,8 +272,7 @@ impl SimpleCoder {
         }
 
         // if there are too many bytes
-        const MAX_ALLOCATION_SIZE: usize = 2_097_152; //2 MiB
-        if num_bytes > MAX_ALLOCATION_SIZE {
+        if num_bytes > Self::MAX_ALLOCATION_SIZE {
             return Err(());
         }
 
@@ -288,6 +311,11 @@ impl SidecarCoder for SimpleCoder {
     /// No-op
     fn finish(self, _builder: &mut PartialSidecar) {}
 
+    fn max_data_len(&self, num_blobs: usize) -> usize {
+        let fes = num_blobs * FIELD_ELEMENTS_PER_BLOB as usize;
+        cmp::min(fes.saturating_sub(1) * 31, Self::MAX_ALLOCATION_SIZE)
+    }
+
     fn decode_all(&mut self, blobs: &[Blob]) -> Option<Vec<Vec<u8>>> {
         if blobs.is_empty() {
             return None;
@@ -317,6 +345,176 @@ impl SidecarCoder for SimpleCoder {
     }
 }
 
+/// Coder for the blob encoding used by the [OP Stack] to post batcher frames.
+///
+/// # Behavior
+///
+/// Every piece of data is split into chunks of at most
+/// [`OpStackCoder::MAX_DATA_PER_BLOB`] bytes, and each chunk is encoded into
+/// its own blob:
+/// - Byte 1 of the blob is the encoding version, and bytes 2 to 4 are the big-endian length of the
+///   chunk.
+/// - The data is encoded in 1024 rounds of 4 field elements. In each round, the last 31 bytes of
+///   every field element hold data, and 3 more bytes of data are split into four 6-bit values
+///   stored in the first byte of each field element.
+/// - The first round holds 4 bytes less data because of the version and length.
+///
+/// Decoding rejects blobs with an unknown version, an invalid length or
+/// non-zero bytes after the data, and returns one piece of data per blob.
+///
+/// [OP Stack]: https://specs.optimism.io/protocol/derivation.html#blob-encoding
+#[derive(Clone, Copy, Debug, Default)]
+#[non_exhaustive]
+pub struct OpStackCoder;
+
+impl OpStackCoder {
+    /// The encoding version written to every blob.
+    pub const ENCODING_VERSION: u8 = 0;
+
+    /// The maximum number of bytes that can be encoded into a single blob.
+    pub const MAX_DATA_PER_BLOB: usize = (4 * 31 + 3) * Self::ROUNDS - 4;
+
+    /// The number of 4 field element rounds in a blob.
+    const ROUNDS: usize = 1024;
+
+    /// Encode a chunk of data into a blob.
+    ///
+    /// Returns `None` if the data is larger than
+    /// [`OpStackCoder::MAX_DATA_PER_BLOB`].
+    pub fn encode_blob(data: &[u8]) -> Option<Blob> {
+        if data.len() > Self::MAX_DATA_PER_BLOB {
+            return None;
+        }
+
+        let mut blob = Blob::new([0u8; BYTES_PER_BLOB]);
+        let mut offset = 0;
+        let mut read = |out: &mut [u8]| {
+            let n = cmp::min(out.len(), data.len() - offset);
+            out[..n].copy_from_slice(&data[offset..offset + n]);
+            offset += n;
+        };
+
+        for (i, round) in blob.chunks_exact_mut(4 * FIELD_ELEMENT_BYTES_USIZE).enumerate() {
+            let (mut x, mut y, mut z) = ([0u8], [0u8], [0u8]);
+
+            // the first field element starts with the version and length
+            if i == 0 {
+                round[1] = Self::ENCODING_VERSION;
+                round[2..5].copy_from_slice(&(data.len() as u32).to_be_bytes()[1..]);
+                read(&mut round[5..32]);
+            } else {
+                read(&mut round[1..32]);
+            }
+            read(&mut x);
+            round[0] = x[0] & 0b0011_1111;
+
+            read(&mut round[33..64]);
+            read(&mut y);
+            round[32] = (y[0] & 0b0000_1111) | ((x[0] & 0b1100_0000) >> 2);
+
+            read(&mut round[65..96]);
+            read(&mut z);
+            round[64] = z[0] & 0b0011_1111;
+
+            read(&mut round[97..128]);
+            round[96] = ((z[0] & 0b1100_0000) >> 2) | ((y[0] & 0b1111_0000) >> 4);
+        }
+
+        Some(blob)
+    }
+
+    /// Decode the data from a blob.
+    ///
+    /// Returns `None` if the blob is not validly encoded.
+    pub fn decode_blob(blob: &Blob) -> Option<Vec<u8>> {
+        if blob[1] != Self::ENCODING_VERSION {
+            return None;
+        }
+        let len = u32::from_be_bytes([0, blob[2], blob[3], blob[4]]) as usize;
+        if len > Self::MAX_DATA_PER_BLOB {
+            return None;
+        }
+
+        let mut out = vec![0u8; Self::MAX_DATA_PER_BLOB];
+        let mut consumed = 0;
+        let mut start = 0;
+        for (i, round) in blob.chunks_exact(4 * FIELD_ELEMENT_BYTES_USIZE).enumerate() {
+            if i > 0 && start >= len {
+                break;
+            }
+
+            // the first round holds 4 bytes less, because of the version and length
+            let (skip, round_len) = if i == 0 { (4, 123) } else { (0, 127) };
+            let mut encoded = [0u8; 4];
+            for (j, fe) in round.chunks_exact(FIELD_ELEMENT_BYTES_USIZE).enumerate() {
+                if fe[0] & 0b1100_0000 != 0 {
+                    return None;
+                }
+                encoded[j] = fe[0];
+                let (data, pos) = if i == 0 && j == 0 {
+                    (&fe[5..], 0)
+                } else {
+                    (&fe[1..], start + j * 32 - skip)
+                };
+                out[pos..pos + data.len()].copy_from_slice(data);
+            }
+
+            // re-assemble the bytes split across the first byte of each field element
+            let end = start + round_len;
+            out[end - 96] = (encoded[0] & 0b0011_1111) | ((encoded[1] & 0b0011_0000) << 2);
+            out[end - 64] = (encoded[1] & 0b0000_1111) | ((encoded[3] & 0b0000_1111) << 4);
+            out[end - 32] = (encoded[2] & 0b0011_1111) | ((encoded[3] & 0b0011_0000) << 2);
+
+            start = end;
+            consumed += round.len();
+        }
+
+        // everything after the data must be empty
+        if out[len..].iter().any(|b| *b != 0) || blob[consumed..].iter().any(|b| *b != 0) {
+            return None;
+        }
+        out.truncate(len);
+        Some(out)
+    }
+}
+
+impl SidecarCoder for OpStackCoder {
+    fn required_fe(&self, data: &[u8]) -> usize {
+        data.len().div_ceil(Self::MAX_DATA_PER_BLOB) * FIELD_ELEMENTS_PER_BLOB as usize
+    }
+
+    fn code(&mut self, builder: &mut PartialSidecar, data: &[u8]) {
+        for chunk in data.chunks(Self::MAX_DATA_PER_BLOB) {
+            let blob = Self::encode_blob(chunk).expect("chunk fits in a blob");
+            for fe in blob.chunks_exact(FIELD_ELEMENT_BYTES_USIZE) {
+                builder.ingest_valid_fe(WholeFe::new_unchecked(fe));
+            }
+        }
+    }
+
+    /// No-op
+    fn finish(self, _builder: &mut PartialSidecar) {}
+
+    fn max_data_len(&self, num_blobs: usize) -> usize {
+        num_blobs * Self::MAX_DATA_PER_BLOB
+    }
+
+    fn decode_all(&mut self, blobs: &[Blob]) -> Option<Vec<Vec<u8>>> {
+        if blobs.is_empty() {
+            return None;
+        }
+
+        let mut res = Vec::with_capacity(blobs.len());
+        for blob in blobs {
+            let data = Self::decode_blob(blob)?;
+            if !data.is_empty() {
+                res.push(data);
+            }
+        }
+        Some(res)
+    }
+}
+
 /// Build a [`BlobTransactionSidecar`] from an arbitrary amount of data.
 ///
 /// This is useful for creating a sidecar from a large amount of data,
@@ -542,6 +740,55 @@ mod tests {
         assert_eq!(SimpleCoder.decode_all(&[Blob::new([0xffu8; BYTES_PER_BLOB])]), None);
     }
 
+    #[test]
+    fn op_stack_coder() {
+        for len in [1, 27, 28, 123, 124, 1000, OpStackCoder::MAX_DATA_PER_BLOB] {
+            let data = (0..len).map(|i| (i % 251) as u8 | 0xc0).collect::<Vec<_>>();
+            let blob = OpStackCoder::encode_blob(&data).unwrap();
+            assert_eq!(blob[1], OpStackCoder::ENCODING_VERSION);
+            assert_eq!(&blob[2..5], &(len as u32).to_be_bytes()[1..]);
+            assert_eq!(&blob[5..5 + len.min(27)], &data[..len.min(27)]);
+            assert!(blob.chunks(32).all(|fe| WholeFe::new(fe).is_some()));
+            assert_eq!(OpStackCoder::decode_blob(&blob).unwrap(), data);
+        }
+        assert!(OpStackCoder::encode_blob(&[0; OpStackCoder::MAX_DATA_PER_BLOB + 1]).is_none());
+
+        let data = [vec![1u8; 10], vec![2u8; OpStackCoder::MAX_DATA_PER_BLOB + 1]];
+        let blobs = data.iter().collect::<SidecarBuilder<OpStackCoder>>().take();
+        assert_eq!(blobs.len(), 3);
+        let decoded = OpStackCoder.decode_all(&blobs).unwrap();
+        assert_eq!(decoded.len(), 3);
+        assert_eq!(decoded.concat(), data.concat());
+    }
+
+    #[test]
+    fn op_stack_coder_rejects_invalid_blobs() {
+        let mut blob = OpStackCoder::encode_blob(&[1, 2, 3]).unwrap();
+        blob[1] = 1;
+        assert_eq!(OpStackCoder::decode_blob(&blob), None);
+
+        let mut blob = OpStackCoder::encode_blob(&[1, 2, 3]).unwrap();
+        blob[BYTES_PER_BLOB - 1] = 1;
+        assert_eq!(OpStackCoder::decode_blob(&blob), None);
+
+        // trailing data within the last decoded round
+        let mut blob = OpStackCoder::encode_blob(&[1, 2, 3]).unwrap();
+        blob[40] = 1;
+        assert_eq!(OpStackCoder::decode_blob(&blob), None);
+    }
+
+    #[test]
+    fn max_data_len() {
+        for coder in [&SimpleCoder as &dyn SidecarCoder, &OpStackCoder] {
+            for blobs in 1..3 {
+                let max = coder.max_data_len(blobs);
+                let fe = FIELD_ELEMENTS_PER_BLOB as usize;
+                assert!(coder.required_fe(&vec![0; max]) <= blobs * fe);
+                assert!(coder.required_fe(&vec![0; max + 1]) > blobs * fe);
+            }
+        }
+    }
+
     #[test]
     fn it_ingests() {
         // test ingesting a lot of data.
@@ -15,6 +15,9 @@ pub mod trusted_setup_points;
 
 /// Builder and utils for the [EIP-4844 Blob Transaction](https://eips.ethereum.org/EIPS/eip-4844#blob-transaction)
 pub mod builder;
+/// Streaming adapters for coding large payloads into several blob transactions.
+#[cfg(feature = "std")]
+pub mod stream;
 pub mod utils;
 
 mod engine;
@@ -414,7 +417,7 @@ impl_ckzg_conversions!(Bytes48, c_kzg::Bytes48);
 #[cfg(feature = "kzg")]
 impl_ckzg_conversions!(reverse Bytes48, c_kzg::KzgProof);
 #[cfg(feature = "kzg")]
-impl_ckzg_conversions!(reverse crate::eip7594::Cell, c_kzg::Cell);
+impl_ckzg_conversions!(crate::eip7594::Cell, c_kzg::Cell);
 
 /// Returns blobs as c-kzg blobs.
 #[cfg(feature = "kzg")]
@@ -0,0 +1,239 @@
+//! Streaming adapters for coding payloads of arbitrary size into blobs.
+//!
+//! [`BlobWriter`] splits the bytes written to it into batches of blobs, one
+//! batch per blob transaction, and [`BlobReader`] reads the payload back from
+//! a sequence of batches.
+
+use crate::{
+    eip4844::{
+        builder::{SidecarBuilder, SidecarCoder, SimpleCoder},
+        Blob,
+    },
+    eip7594::MAX_BLOBS_PER_TX_FUSAKA,
+};
+use alloc::{collections::VecDeque, vec::Vec};
+use std::io;
+
+/// Splits a stream of bytes into batches of blobs, each of which fits into a
+/// single blob transaction.
+///
+/// Bytes written to the writer are buffered until there is enough data to
+/// fill a batch of [`blobs_per_tx`](Self::blobs_per_tx) blobs. Full batches
+/// can be taken with [`next_batch`](Self::next_batch), and the remaining data
+/// is coded into a final, possibly smaller, batch by
+/// [`finish`](Self::finish).
+///
+/// Every batch is coded independently with a fresh copy of the coder, so that
+/// each transaction can be decoded on its own.
+///
+/// # Examples
+///
+/// ```
+/// use alloy_eips::eip4844::{
+///     builder::OpStackCoder,
+///     stream::{BlobReader, BlobWriter},
+/// };
+/// use std::io::{Read, Write};
+///
+/// let payload = vec![0xab; 1_000_000];
+///
+/// let mut writer = BlobWriter::new(OpStackCoder::default());
+/// writer.write_all(&payload)?;
+/// let batches = writer.finish();
+/// assert_eq!(batches.len(), 2);
+///
+/// let mut decoded = Vec::new();
+/// BlobReader::new(OpStackCoder::default(), batches).read_to_end(&mut decoded)?;
+/// assert_eq!(decoded, payload);
+/// # Ok::<_, std::io::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct BlobWriter<T = SimpleCoder> {
+    coder: T,
+    blobs_per_tx: usize,
+    max_data_len: usize,
+    buffer: Vec<u8>,
+    ready: VecDeque<Vec<Blob>>,
+}
+
+impl<T: SidecarCoder + Clone + Default> Default for BlobWriter<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: SidecarCoder + Clone> BlobWriter<T> {
+    /// Creates a new writer with the given coder, producing batches of
+    /// [`MAX_BLOBS_PER_TX_FUSAKA`] blobs.
+    pub fn new(coder: T) -> Self {
+        let blobs_per_tx = MAX_BLOBS_PER_TX_FUSAKA as usize;
+        let max_data_len = coder.max_data_len(blobs_per_tx);
+        Self { coder, blobs_per_tx, max_data_len, buffer: Vec::new(), ready: VecDeque::new() }
+    }
+
+    /// Sets the maximum number of blobs per batch.
+    ///
+    /// # Panics
+    ///
+    /// If `blobs_per_tx` is zero, or if data has already been written.
+    pub fn with_blobs_per_tx(mut self, blobs_per_tx: usize) -> Self {
+        assert!(blobs_per_tx > 0, "blobs_per_tx must be non-zero");
+        assert!(self.buffer.is_empty() && self.ready.is_empty(), "data has already been written");
+        self.blobs_per_tx = blobs_per_tx;
+        self.max_data_len = self.coder.max_data_len(blobs_per_tx);
+        self
+    }
+
+    /// Returns the maximum number of blobs per batch.
+    pub const fn blobs_per_tx(&self) -> usize {
+        self.blobs_per_tx
+    }
+
+    /// Returns the number of bytes that have been written but not yet coded
+    /// into a batch.
+    pub const fn buffered_len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Takes the next full batch of blobs, if any.
+    pub fn next_batch(&mut self) -> Option<Vec<Blob>> {
+        self.ready.pop_front()
+    }
+
+    /// Codes the buffered data and returns all batches that have not been
+    /// taken yet.
+    pub fn finish(mut self) -> Vec<Vec<Blob>> {
+        if !self.buffer.is_empty() {
+            let data = core::mem::take(&mut self.buffer);
+            let batch = self.code(&data);
+            self.ready.push_back(batch);
+        }
+        self.ready.into()
+    }
+
+    fn code(&self, data: &[u8]) -> Vec<Blob> {
+        SidecarBuilder::from_coder_and_data(self.coder.clone(), data).take()
+    }
+}
+
+impl<T: SidecarCoder + Clone> io::Write for BlobWriter<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.max_data_len == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "coder has no capacity"));
+        }
+
+        self.buffer.extend_from_slice(buf);
+        while self.buffer.len() >= self.max_data_len {
+            let rest = self.buffer.split_off(self.max_data_len);
+            let data = core::mem::replace(&mut self.buffer, rest);
+            let batch = self.code(&data);
+            self.ready.push_back(batch);
+        }
+        Ok(buf.len())
+    }
+
+    /// No-op, partial batches are only coded by [`BlobWriter::finish`].
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Reads a payload back from a sequence of batches of blobs, as produced by
+/// [`BlobWriter`].
+///
+/// Batches are decoded lazily as the payload is read. Batches that cannot be
+/// decoded by the coder result in an [`io::ErrorKind::InvalidData`] error.
+#[derive(Clone, Debug)]
+pub struct BlobReader<I, T = SimpleCoder> {
+    coder: T,
+    batches: I,
+    buffer: Vec<u8>,
+    pos: usize,
+}
+
+impl<I, T> BlobReader<I, T>
+where
+    I: Iterator,
+    I::Item: AsRef<[Blob]>,
+    T: SidecarCoder,
+{
+    /// Creates a new reader decoding the given batches with the coder.
+    pub fn new(coder: T, batches: impl IntoIterator<IntoIter = I>) -> Self {
+        Self { coder, batches: batches.into_iter(), buffer: Vec::new(), pos: 0 }
+    }
+}
+
+impl<I, T> io::Read for BlobReader<I, T>
+where
+    I: Iterator,
+    I::Item: AsRef<[Blob]>,
+    T: SidecarCoder,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.buffer.len() {
+            let Some(batch) = self.batches.next() else {
+                return Ok(0);
+            };
+            let decoded = self.coder.decode_all(batch.as_ref()).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "invalid blob data in batch")
+            })?;
+            self.buffer = decoded.concat();
+            self.pos = 0;
+        }
+
+        let n = core::cmp::min(buf.len(), self.buffer.len() - self.pos);
+        buf[..n].copy_from_slice(&self.buffer[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eip4844::builder::OpStackCoder;
+    use io::{Read, Write};
+
+    fn payload(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7 + i / 256) as u8).collect()
+    }
+
+    fn roundtrip<T: SidecarCoder + Clone>(coder: T, len: usize, blobs_per_tx: usize) -> usize {
+        let data = payload(len);
+        let mut writer = BlobWriter::new(coder.clone()).with_blobs_per_tx(blobs_per_tx);
+        for chunk in data.chunks(10_000) {
+            writer.write_all(chunk).unwrap();
+        }
+        let batches = writer.finish();
+        assert!(batches.iter().all(|batch| batch.len() <= blobs_per_tx));
+
+        let mut decoded = Vec::new();
+        BlobReader::new(coder, &batches).read_to_end(&mut decoded).unwrap();
+        assert_eq!(decoded, data);
+        batches.len()
+    }
+
+    #[test]
+    fn simple_coder_roundtrip() {
+        assert_eq!(roundtrip(SimpleCoder, 1, 1), 1);
+        assert_eq!(roundtrip(SimpleCoder, 4095 * 31, 1), 1);
+        assert_eq!(roundtrip(SimpleCoder, 4095 * 31 + 1, 1), 2);
+        assert_eq!(roundtrip(SimpleCoder, 1_000_000, 3), 3);
+    }
+
+    #[test]
+    fn op_stack_coder_roundtrip() {
+        let max = OpStackCoder::MAX_DATA_PER_BLOB;
+        assert_eq!(roundtrip(OpStackCoder, max, 1), 1);
+        assert_eq!(roundtrip(OpStackCoder, max + 1, 1), 2);
+        assert_eq!(roundtrip(OpStackCoder, 5 * max, 2), 3);
+        assert_eq!(roundtrip(OpStackCoder, 0, 2), 0);
+    }
+
+    #[test]
+    fn reader_rejects_invalid_batches() {
+        let batches = [vec![Blob::new([0xff; crate::eip4844::BYTES_PER_BLOB])]];
+        let err = BlobReader::new(OpStackCoder, &batches).read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
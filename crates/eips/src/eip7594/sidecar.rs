@@ -992,6 +992,243 @@ impl BlobTransactionSidecarEip7594 {
         Ok(matches.into_iter())
     }
 
+    /// Returns the cells and proofs selected by `cell_mask` for every blob in the sidecar.
+    ///
+    /// The result is a partial sidecar with one entry per blob, in the same order as
+    /// [`Self::blobs`], which can be checked with [`Self::verify_cells`] and turned back into the
+    /// full sidecar with [`Self::recover`].
+    ///
+    /// This uses the default KZG settings.
+    #[cfg(feature = "kzg")]
+    pub fn select_cells(
+        &self,
+        cell_mask: BlobCellMask,
+    ) -> Result<Vec<BlobCellsAndProofsV1>, c_kzg::Error> {
+        use crate::eip4844::env_settings::EnvKzgSettings;
+
+        self.select_cells_with_settings(cell_mask, EnvKzgSettings::Default.get())
+    }
+
+    /// Returns the cells and proofs selected by `cell_mask` for every blob in the sidecar.
+    ///
+    /// See [`Self::select_cells`].
+    #[cfg(feature = "kzg")]
+    pub fn select_cells_with_settings(
+        &self,
+        cell_mask: BlobCellMask,
+        settings: &c_kzg::KzgSettings,
+    ) -> Result<Vec<BlobCellsAndProofsV1>, c_kzg::Error> {
+        if self.cell_proofs.len() != self.blobs.len() * CELLS_PER_EXT_BLOB {
+            return Err(c_kzg::Error::MismatchLength(format!(
+                "There are {} cell proofs and {} blobs. Expected {} cell proofs.",
+                self.cell_proofs.len(),
+                self.blobs.len(),
+                self.blobs.len() * CELLS_PER_EXT_BLOB
+            )));
+        }
+
+        (0..self.blobs.len())
+            .map(|blob_index| {
+                self.blob_cells_and_proofs_with_settings(blob_index, cell_mask, settings)
+                    .map(Option::unwrap_or_default)
+            })
+            .collect()
+    }
+
+    /// Verifies a subset of cells and proofs against the blob commitments in a single batch.
+    ///
+    /// `cells` holds one entry per commitment, with the cells and proofs selected by `cell_mask`
+    /// in ascending cell index order, as returned by [`Self::select_cells`]. Missing cells are
+    /// skipped, so partial responses can be verified.
+    ///
+    /// This uses the default KZG settings.
+    #[cfg(feature = "kzg")]
+    pub fn verify_cells(
+        commitments: &[Bytes48],
+        cell_mask: BlobCellMask,
+        cells: &[BlobCellsAndProofsV1],
+    ) -> Result<(), BlobTransactionValidationError> {
+        use crate::eip4844::env_settings::EnvKzgSettings;
+
+        Self::verify_cells_with_settings(
+            commitments,
+            cell_mask,
+            cells,
+            EnvKzgSettings::Default.get(),
+        )
+    }
+
+    /// Verifies a subset of cells and proofs against the blob commitments in a single batch.
+    ///
+    /// See [`Self::verify_cells`].
+    ///
+    /// Returns [BlobTransactionValidationError::InvalidProof] if any cell proof fails to verify.
+    #[cfg(feature = "kzg")]
+    pub fn verify_cells_with_settings(
+        commitments: &[Bytes48],
+        cell_mask: BlobCellMask,
+        cells: &[BlobCellsAndProofsV1],
+        settings: &c_kzg::KzgSettings,
+    ) -> Result<(), BlobTransactionValidationError> {
+        if commitments.len() != cells.len() {
+            return Err(c_kzg::Error::MismatchLength(format!(
+                "There are {} commitments and {} blobs with cells",
+                commitments.len(),
+                cells.len()
+            ))
+            .into());
+        }
+
+        let mut batch_commitments = Vec::new();
+        let mut batch_indices = Vec::new();
+        let mut batch_cells = Vec::new();
+        let mut batch_proofs = Vec::new();
+        for (commitment, blob_cells) in commitments.iter().zip(cells) {
+            for (cell_index, cell, proof) in Self::present_cells(cell_mask, blob_cells)? {
+                batch_commitments.push(*commitment);
+                batch_indices.push(cell_index);
+                batch_cells.push(*cell.as_ckzg());
+                batch_proofs.push(*proof);
+            }
+        }
+
+        if batch_cells.is_empty() {
+            return Ok(());
+        }
+
+        let res = settings.verify_cell_kzg_proof_batch(
+            Bytes48::slice_as_ckzg(&batch_commitments),
+            &batch_indices,
+            &batch_cells,
+            Bytes48::slice_as_ckzg(&batch_proofs),
+        )?;
+
+        res.then_some(()).ok_or(BlobTransactionValidationError::InvalidProof)
+    }
+
+    /// Recovers a blob and all of its cell proofs from at least half of its cells.
+    ///
+    /// `cells` holds the cells selected by `cell_mask` in ascending cell index order, missing
+    /// cells are skipped. The cells are not verified, see [`Self::verify_cells`].
+    ///
+    /// This uses the default KZG settings.
+    #[cfg(feature = "kzg")]
+    pub fn recover_blob(
+        cell_mask: BlobCellMask,
+        cells: &BlobCellsAndProofsV1,
+    ) -> Result<(Blob, Vec<Bytes48>), c_kzg::Error> {
+        use crate::eip4844::env_settings::EnvKzgSettings;
+
+        Self::recover_blob_with_settings(cell_mask, cells, EnvKzgSettings::Default.get())
+    }
+
+    /// Recovers a blob and all of its cell proofs from at least half of its cells.
+    ///
+    /// See [`Self::recover_blob`].
+    #[cfg(feature = "kzg")]
+    pub fn recover_blob_with_settings(
+        cell_mask: BlobCellMask,
+        cells: &BlobCellsAndProofsV1,
+        settings: &c_kzg::KzgSettings,
+    ) -> Result<(Blob, Vec<Bytes48>), c_kzg::Error> {
+        let mut indices = Vec::with_capacity(cell_mask.count());
+        let mut present = Vec::with_capacity(cell_mask.count());
+        for (cell_index, cell, _) in Self::present_cells(cell_mask, cells)? {
+            indices.push(cell_index);
+            present.push(*cell.as_ckzg());
+        }
+
+        if indices.len() < CELLS_PER_EXT_BLOB / 2 {
+            return Err(c_kzg::Error::MismatchLength(format!(
+                "There are {} cells. At least {} cells are required for recovery.",
+                indices.len(),
+                CELLS_PER_EXT_BLOB / 2
+            )));
+        }
+
+        let (recovered_cells, recovered_proofs) =
+            settings.recover_cells_and_kzg_proofs(&indices, &present)?;
+
+        // The first half of the extended cells holds the original blob.
+        let mut blob = Blob::ZERO;
+        for (chunk, cell) in blob
+            .chunks_exact_mut(crate::eip7594::BYTES_PER_CELL)
+            .zip(&recovered_cells[..CELLS_PER_EXT_BLOB / 2])
+        {
+            chunk.copy_from_slice(&cell.to_bytes());
+        }
+        let proofs = c_kzg::KzgProof::slice_as_alloy(recovered_proofs.as_ref()).to_vec();
+
+        Ok((blob, proofs))
+    }
+
+    /// Reconstructs the full sidecar from at least half of the cells of every blob.
+    ///
+    /// `cells` holds one entry per commitment, see [`Self::recover_blob`].
+    ///
+    /// This uses the default KZG settings.
+    #[cfg(feature = "kzg")]
+    pub fn recover(
+        commitments: Vec<Bytes48>,
+        cell_mask: BlobCellMask,
+        cells: &[BlobCellsAndProofsV1],
+    ) -> Result<Self, c_kzg::Error> {
+        use crate::eip4844::env_settings::EnvKzgSettings;
+
+        Self::recover_with_settings(commitments, cell_mask, cells, EnvKzgSettings::Default.get())
+    }
+
+    /// Reconstructs the full sidecar from at least half of the cells of every blob.
+    ///
+    /// See [`Self::recover`].
+    #[cfg(feature = "kzg")]
+    pub fn recover_with_settings(
+        commitments: Vec<Bytes48>,
+        cell_mask: BlobCellMask,
+        cells: &[BlobCellsAndProofsV1],
+        settings: &c_kzg::KzgSettings,
+    ) -> Result<Self, c_kzg::Error> {
+        if commitments.len() != cells.len() {
+            return Err(c_kzg::Error::MismatchLength(format!(
+                "There are {} commitments and {} blobs with cells",
+                commitments.len(),
+                cells.len()
+            )));
+        }
+
+        let mut blobs = Vec::with_capacity(cells.len());
+        let mut cell_proofs = Vec::with_capacity(cells.len() * CELLS_PER_EXT_BLOB);
+        for blob_cells in cells {
+            let (blob, proofs) = Self::recover_blob_with_settings(cell_mask, blob_cells, settings)?;
+            blobs.push(blob);
+            cell_proofs.extend(proofs);
+        }
+
+        Ok(Self::new(blobs, commitments, cell_proofs))
+    }
+
+    /// Returns the `(cell index, cell, proof)` triples of the cells that are present in `cells`.
+    #[cfg(feature = "kzg")]
+    fn present_cells(
+        cell_mask: BlobCellMask,
+        cells: &BlobCellsAndProofsV1,
+    ) -> Result<impl Iterator<Item = (u64, &Cell, &Bytes48)>, c_kzg::Error> {
+        if cells.blob_cells.len() != cell_mask.count() || cells.proofs.len() != cell_mask.count() {
+            return Err(c_kzg::Error::MismatchLength(format!(
+                "There are {} cells and {} proofs. Expected {} of each.",
+                cells.blob_cells.len(),
+                cells.proofs.len(),
+                cell_mask.count()
+            )));
+        }
+
+        Ok(cell_mask.selected_indices().zip(cells.blob_cells.iter().zip(&cells.proofs)).filter_map(
+            |(cell_index, (cell, proof))| {
+                Some((cell_index as u64, cell.as_ref()?, proof.as_ref()?))
+            },
+        ))
+    }
+
     /// Outputs the RLP length of [BlobTransactionSidecarEip7594] fields without a RLP header.
     #[doc(hidden)]
     pub fn rlp_encoded_fields_length(&self) -> usize {
@@ -1434,4 +1671,66 @@ mod tests {
             .collect::<Vec<_>>();
         assert_eq!(matches, vec![(0, cells_and_proofs)]);
     }
+
+    #[test]
+    #[cfg(feature = "kzg")]
+    fn select_verify_and_recover_cells() {
+        let settings = EnvKzgSettings::Default.get();
+        let sidecar = SidecarBuilder::<SimpleCoder>::from_slice(&[0xab; 200_000])
+            .build_7594_with_settings(settings)
+            .unwrap();
+        assert_eq!(sidecar.blobs.len(), 2);
+
+        // select every other cell, which is exactly enough for recovery
+        let cell_mask = BlobCellMask::from_bits(0x5555_5555_5555_5555_5555_5555_5555_5555);
+        let cells = sidecar.select_cells_with_settings(cell_mask, settings).unwrap();
+        assert_eq!(cells.len(), 2);
+        assert!(cells.iter().all(|cells| cells.blob_cells.len() == 64));
+
+        BlobTransactionSidecarEip7594::verify_cells_with_settings(
+            &sidecar.commitments,
+            cell_mask,
+            &cells,
+            settings,
+        )
+        .unwrap();
+
+        let recovered = BlobTransactionSidecarEip7594::recover_with_settings(
+            sidecar.commitments.clone(),
+            cell_mask,
+            &cells,
+            settings,
+        )
+        .unwrap();
+        assert_eq!(recovered, sidecar);
+
+        // verification skips missing cells, but recovery needs at least half of them
+        let mut partial = cells.clone();
+        partial[1].blob_cells[3] = None;
+        BlobTransactionSidecarEip7594::verify_cells_with_settings(
+            &sidecar.commitments,
+            cell_mask,
+            &partial,
+            settings,
+        )
+        .unwrap();
+        assert!(BlobTransactionSidecarEip7594::recover_blob_with_settings(
+            cell_mask,
+            &partial[1],
+            settings
+        )
+        .is_err());
+
+        // swapping the blobs invalidates the proofs
+        let swapped = vec![cells[1].clone(), cells[0].clone()];
+        assert!(matches!(
+            BlobTransactionSidecarEip7594::verify_cells_with_settings(
+                &sidecar.commitments,
+                cell_mask,
+                &swapped,
+                settings,
+            ),
+            Err(BlobTransactionValidationError::InvalidProof)
+        ));
+    }
 }
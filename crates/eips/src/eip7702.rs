@@ -1,2 +1,33 @@
 //! Re-export the EIP-7702 types.
 pub use alloy_eip7702::*;
+
+use alloy_primitives::Address;
+
+/// Parses the delegated address from the code of an account.
+///
+/// Returns `None` if the code is not an EIP-7702 delegation designator, i.e.
+/// `0xef0100 || address`.
+pub fn delegation_address(code: &[u8]) -> Option<Address> {
+    code.strip_prefix(constants::EIP7702_DELEGATION_DESIGNATOR.as_slice())
+        .filter(|address| address.len() == Address::len_bytes())
+        .map(Address::from_slice)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, hex};
+
+    #[test]
+    fn parse_delegation_address() {
+        let code = hex!("ef010063c0c19a282a1b52b07dd5a65b58948a07dae32b");
+        assert_eq!(
+            delegation_address(&code),
+            Some(address!("0x63c0c19a282a1b52b07dd5a65b58948a07dae32b"))
+        );
+        assert_eq!(delegation_address(&constants::EIP7702_CLEARED_DELEGATION), Some(Address::ZERO));
+        assert_eq!(delegation_address(&code[..22]), None);
+        assert_eq!(delegation_address(&hex!("6080604052")), None);
+        assert_eq!(delegation_address(&[]), None);
+    }
+}
@@ -2,8 +2,11 @@
 
 use crate::{
     eip4844::{self, DATA_GAS_PER_BLOB},
-    eip7594, eip7691, eip7892,
+    eip7594, eip7691,
+    eip7892::{self, BlobScheduleBlobParams},
+    merge,
 };
+use alloc::vec::Vec;
 
 /// BLOB_BASE_COST represents the minimum execution gas required to include a blob in a block,
 /// as defined by [EIP-7918 (Decoupling Blob Gas from Execution Gas)](https://eips.ethereum.org/EIPS/eip-7918).
@@ -158,6 +161,208 @@ impl BlobParams {
     }
 }
 
+/// Assumed blob gas usage of future blocks, used by [`BlobFeeForecaster`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlobGasUsage {
+    /// Blocks contain no blobs.
+    Empty,
+    /// Blocks use exactly the target blob gas, which keeps the blob fee stable.
+    Target,
+    /// Blocks use the maximum blob gas, which raises the blob fee as fast as possible.
+    Max,
+    /// Blocks use the given fraction of the maximum blob gas, e.g. the average
+    /// `blobGasUsedRatio` reported by `eth_feeHistory`.
+    ///
+    /// The ratio is clamped to `0.0..=1.0`.
+    Ratio(f64),
+}
+
+impl BlobGasUsage {
+    /// Returns the blob gas used by a block with the given [`BlobParams`] under this scenario.
+    ///
+    /// For [`BlobGasUsage::Ratio`] the result is rounded down to whole blobs.
+    pub fn blob_gas_used(&self, params: &BlobParams) -> u64 {
+        match *self {
+            Self::Empty => 0,
+            Self::Target => params.target_blob_gas_per_block(),
+            Self::Max => params.max_blob_gas_per_block(),
+            Self::Ratio(ratio) => {
+                let ratio = if ratio.is_nan() { 0.0 } else { ratio.clamp(0.0, 1.0) };
+                let blobs = (params.max_blob_count as f64 * ratio) as u64;
+                blobs * DATA_GAS_PER_BLOB
+            }
+        }
+    }
+}
+
+/// Blob gas state of a block, used as the starting point of a forecast.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlobGasState {
+    /// Timestamp of the block.
+    pub timestamp: u64,
+    /// The `excess_blob_gas` of the block.
+    pub excess_blob_gas: u64,
+    /// The `blob_gas_used` of the block.
+    pub blob_gas_used: u64,
+    /// The `base_fee_per_gas` of the block.
+    ///
+    /// This is assumed to stay constant and only matters for the blob reserve price of
+    /// [EIP-7918](https://eips.ethereum.org/EIPS/eip-7918).
+    pub base_fee_per_gas: u64,
+}
+
+/// Projected blob fee of a future block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlobFeeProjection {
+    /// Number of blocks after the starting block, starting at `1`.
+    pub blocks_ahead: u64,
+    /// Expected timestamp of the block.
+    pub timestamp: u64,
+    /// Projected `excess_blob_gas` of the block.
+    pub excess_blob_gas: u64,
+    /// Projected base fee per blob gas of the block.
+    pub base_fee_per_blob_gas: u128,
+    /// The [`BlobParams`] active for the block.
+    pub params: BlobParams,
+}
+
+/// Projects `excess_blob_gas` and the base fee per blob gas of future blocks.
+///
+/// Blocks are assumed to be produced every [`slot_time`](Self::with_slot_time) seconds, and the
+/// blob parameters of each block are taken from the [`BlobScheduleBlobParams`], so that blob
+/// parameter only forks ([EIP-7892](https://eips.ethereum.org/EIPS/eip-7892)) scheduled within
+/// the forecast are taken into account. Blocks before the first scheduled entry use the
+/// [`base params`](Self::with_params), which default to the Prague parameters of the schedule,
+/// the last parameters before the schedule starts. Set them to the Osaka parameters if Osaka is
+/// already active.
+///
+/// # Examples
+///
+/// ```
+/// use alloy_eips::{
+///     eip7840::{BlobFeeForecaster, BlobGasState, BlobGasUsage},
+///     eip7892::BlobScheduleBlobParams,
+/// };
+///
+/// let forecaster = BlobFeeForecaster::new(BlobScheduleBlobParams::mainnet());
+/// let head = BlobGasState { excess_blob_gas: 10_000_000, ..Default::default() };
+///
+/// let full = forecaster.forecast(head, BlobGasUsage::Max, 10);
+/// let empty = forecaster.forecast(head, BlobGasUsage::Empty, 10);
+/// assert!(full[9].base_fee_per_blob_gas > empty[9].base_fee_per_blob_gas);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobFeeForecaster {
+    schedule: BlobScheduleBlobParams,
+    params: BlobParams,
+    slot_time: u64,
+}
+
+impl BlobFeeForecaster {
+    /// Creates a new forecaster for the given blob schedule, with 12 second slots.
+    pub const fn new(schedule: BlobScheduleBlobParams) -> Self {
+        let params = schedule.prague;
+        Self { schedule, params, slot_time: merge::SLOT_DURATION_SECS }
+    }
+
+    /// Sets the [`BlobParams`] used for blocks before the first scheduled entry.
+    pub const fn with_params(mut self, params: BlobParams) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// Sets the number of seconds between blocks.
+    pub const fn with_slot_time(mut self, slot_time: u64) -> Self {
+        self.slot_time = slot_time;
+        self
+    }
+
+    /// Returns the blob schedule of the forecaster.
+    pub const fn schedule(&self) -> &BlobScheduleBlobParams {
+        &self.schedule
+    }
+
+    /// Returns the number of seconds between blocks.
+    pub const fn slot_time(&self) -> u64 {
+        self.slot_time
+    }
+
+    /// Returns the [`BlobParams`] active at the given timestamp.
+    pub fn params_at_timestamp(&self, timestamp: u64) -> BlobParams {
+        self.schedule
+            .active_scheduled_params_at_timestamp(timestamp)
+            .copied()
+            .unwrap_or(self.params)
+    }
+
+    /// Returns the blob fee of the block following `head`.
+    pub fn next_block_blob_fee(&self, head: BlobGasState) -> u128 {
+        self.projections(head, BlobGasUsage::Empty).next().map_or(0, |p| p.base_fee_per_blob_gas)
+    }
+
+    /// Projects the next `blocks` blocks after `head`, assuming every future block uses blob gas
+    /// according to `usage`.
+    ///
+    /// The blob gas used by `head` itself is taken from the state.
+    pub fn forecast(
+        &self,
+        head: BlobGasState,
+        usage: BlobGasUsage,
+        blocks: usize,
+    ) -> Vec<BlobFeeProjection> {
+        self.projections(head, usage).take(blocks).collect()
+    }
+
+    /// Returns the number of blocks after `head` until the blob fee drops to or below `max_fee`,
+    /// assuming every future block uses blob gas according to `usage`.
+    ///
+    /// Returns `Some(0)` if the fee of the next block is already low enough, and `None` if the fee
+    /// does not drop below `max_fee` within `max_blocks` blocks.
+    pub fn blocks_until_fee_below(
+        &self,
+        head: BlobGasState,
+        usage: BlobGasUsage,
+        max_fee: u128,
+        max_blocks: usize,
+    ) -> Option<u64> {
+        self.projections(head, usage)
+            .take(max_blocks)
+            .find(|p| p.base_fee_per_blob_gas <= max_fee)
+            .map(|p| p.blocks_ahead - 1)
+    }
+
+    fn projections(
+        &self,
+        head: BlobGasState,
+        usage: BlobGasUsage,
+    ) -> impl Iterator<Item = BlobFeeProjection> + '_ {
+        let mut state = head;
+        (1u64..).map(move |blocks_ahead| {
+            let timestamp =
+                head.timestamp.saturating_add(blocks_ahead.saturating_mul(self.slot_time));
+            let params = self.params_at_timestamp(timestamp);
+            let excess_blob_gas = params.next_block_excess_blob_gas_osaka(
+                state.excess_blob_gas,
+                state.blob_gas_used,
+                state.base_fee_per_gas,
+            );
+            state = BlobGasState {
+                timestamp,
+                excess_blob_gas,
+                blob_gas_used: usage.blob_gas_used(&params),
+                base_fee_per_gas: state.base_fee_per_gas,
+            };
+            BlobFeeProjection {
+                blocks_ahead,
+                timestamp,
+                excess_blob_gas,
+                base_fee_per_blob_gas: params.calc_blob_fee(excess_blob_gas),
+                params,
+            }
+        })
+    }
+}
+
 #[cfg(feature = "serde")]
 mod serde_impl {
     use crate::{eip4844, eip7840::BlobParams};
@@ -212,3 +417,92 @@ mod serde_impl {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_scenarios() {
+        let params = BlobParams::osaka();
+        assert_eq!(BlobGasUsage::Empty.blob_gas_used(&params), 0);
+        assert_eq!(BlobGasUsage::Target.blob_gas_used(&params), 6 * DATA_GAS_PER_BLOB);
+        assert_eq!(BlobGasUsage::Max.blob_gas_used(&params), 9 * DATA_GAS_PER_BLOB);
+        assert_eq!(BlobGasUsage::Ratio(0.5).blob_gas_used(&params), 4 * DATA_GAS_PER_BLOB);
+        assert_eq!(BlobGasUsage::Ratio(2.0).blob_gas_used(&params), 9 * DATA_GAS_PER_BLOB);
+        assert_eq!(BlobGasUsage::Ratio(f64::NAN).blob_gas_used(&params), 0);
+    }
+
+    #[test]
+    fn forecast_matches_step_by_step_calculation() {
+        let params = BlobParams::osaka();
+        let forecaster =
+            BlobFeeForecaster::new(BlobScheduleBlobParams::mainnet()).with_params(params);
+        let head = BlobGasState {
+            timestamp: 0,
+            excess_blob_gas: 20_000_000,
+            blob_gas_used: params.max_blob_gas_per_block(),
+            base_fee_per_gas: 1,
+        };
+
+        let forecast = forecaster.forecast(head, BlobGasUsage::Max, 3);
+        assert_eq!(forecast.len(), 3);
+
+        let mut excess = head.excess_blob_gas;
+        for (i, projection) in forecast.iter().enumerate() {
+            excess =
+                params.next_block_excess_blob_gas_osaka(excess, params.max_blob_gas_per_block(), 1);
+            assert_eq!(projection.blocks_ahead, i as u64 + 1);
+            assert_eq!(projection.timestamp, (i as u64 + 1) * 12);
+            assert_eq!(projection.excess_blob_gas, excess);
+            assert_eq!(projection.base_fee_per_blob_gas, params.calc_blob_fee(excess));
+        }
+        assert_eq!(forecaster.next_block_blob_fee(head), forecast[0].base_fee_per_blob_gas);
+
+        // target usage keeps the excess constant
+        let head = BlobGasState { blob_gas_used: params.target_blob_gas_per_block(), ..head };
+        let forecast = forecaster.forecast(head, BlobGasUsage::Target, 5);
+        assert!(forecast.iter().all(|p| p.excess_blob_gas == head.excess_blob_gas));
+    }
+
+    #[test]
+    fn forecast_applies_scheduled_params() {
+        let schedule = BlobScheduleBlobParams::mainnet().with_scheduled([(24, BlobParams::bpo1())]);
+        let forecaster = BlobFeeForecaster::new(schedule);
+        let head = BlobGasState { excess_blob_gas: 50_000_000, ..Default::default() };
+
+        let forecast = forecaster.forecast(head, BlobGasUsage::Target, 3);
+        assert_eq!(forecast[0].params, BlobParams::prague());
+        assert_eq!(forecast[1].params, BlobParams::bpo1());
+        assert_eq!(forecast[2].params, BlobParams::bpo1());
+        assert_eq!(
+            forecast[1].base_fee_per_blob_gas,
+            BlobParams::bpo1().calc_blob_fee(forecast[1].excess_blob_gas)
+        );
+
+        let forecaster = forecaster.with_slot_time(u64::MAX);
+        let forecast = forecaster.forecast(head, BlobGasUsage::Target, 2);
+        assert_eq!(forecast[1].timestamp, u64::MAX);
+        assert_eq!(forecast[1].params, BlobParams::bpo1());
+    }
+
+    #[test]
+    fn blocks_until_fee_below() {
+        let forecaster = BlobFeeForecaster::new(BlobScheduleBlobParams::mainnet());
+        let head = BlobGasState { excess_blob_gas: 100_000_000, ..Default::default() };
+        let next_fee = forecaster.next_block_blob_fee(head);
+
+        assert_eq!(
+            forecaster.blocks_until_fee_below(head, BlobGasUsage::Empty, next_fee, 10),
+            Some(0)
+        );
+        let blocks = forecaster
+            .blocks_until_fee_below(head, BlobGasUsage::Empty, next_fee / 2, 100)
+            .unwrap();
+        assert!(blocks > 0);
+        assert_eq!(
+            forecaster.blocks_until_fee_below(head, BlobGasUsage::Max, next_fee / 2, 100),
+            None
+        );
+    }
+}
@@ -0,0 +1,647 @@
+//! Fork-aware chain specification derived from a [`Genesis`].
+
+use crate::{ChainConfig, Genesis};
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use alloy_consensus::{
+    constants::{HOLESKY_GENESIS_HASH, MAINNET_GENESIS_HASH, SEPOLIA_GENESIS_HASH},
+    TxType,
+};
+use alloy_eips::{
+    eip1559::BaseFeeParams,
+    eip2124::{ForkFilter, ForkFilterKey, ForkId, Head},
+    eip7840::BlobParams,
+    BlobScheduleBlobParams,
+};
+use alloy_primitives::{B256, U256};
+use core::fmt;
+
+/// Ethereum hardforks known to [`ChainSpec`], in activation order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Hardfork {
+    /// Frontier, the launch of the network.
+    Frontier,
+    /// Homestead.
+    Homestead,
+    /// The DAO fork.
+    Dao,
+    /// Tangerine Whistle (EIP-150).
+    Tangerine,
+    /// Spurious Dragon (EIP-155 and EIP-158).
+    SpuriousDragon,
+    /// Byzantium.
+    Byzantium,
+    /// Constantinople.
+    Constantinople,
+    /// Petersburg.
+    Petersburg,
+    /// Istanbul.
+    Istanbul,
+    /// Muir Glacier.
+    MuirGlacier,
+    /// Berlin.
+    Berlin,
+    /// London.
+    London,
+    /// Arrow Glacier.
+    ArrowGlacier,
+    /// Gray Glacier.
+    GrayGlacier,
+    /// Paris, the merge.
+    Paris,
+    /// Shanghai.
+    Shanghai,
+    /// Cancun.
+    Cancun,
+    /// Prague.
+    Prague,
+    /// Osaka.
+    Osaka,
+    /// Blob parameter only fork BPO1.
+    Bpo1,
+    /// Blob parameter only fork BPO2.
+    Bpo2,
+    /// Blob parameter only fork BPO3.
+    Bpo3,
+    /// Blob parameter only fork BPO4.
+    Bpo4,
+    /// Blob parameter only fork BPO5.
+    Bpo5,
+    /// Amsterdam.
+    Amsterdam,
+}
+
+impl Hardfork {
+    /// All hardforks, in activation order.
+    pub const ALL: [Self; 25] = [
+        Self::Frontier,
+        Self::Homestead,
+        Self::Dao,
+        Self::Tangerine,
+        Self::SpuriousDragon,
+        Self::Byzantium,
+        Self::Constantinople,
+        Self::Petersburg,
+        Self::Istanbul,
+        Self::MuirGlacier,
+        Self::Berlin,
+        Self::London,
+        Self::ArrowGlacier,
+        Self::GrayGlacier,
+        Self::Paris,
+        Self::Shanghai,
+        Self::Cancun,
+        Self::Prague,
+        Self::Osaka,
+        Self::Bpo1,
+        Self::Bpo2,
+        Self::Bpo3,
+        Self::Bpo4,
+        Self::Bpo5,
+        Self::Amsterdam,
+    ];
+
+    /// Returns the name of the hardfork.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Frontier => "Frontier",
+            Self::Homestead => "Homestead",
+            Self::Dao => "Dao",
+            Self::Tangerine => "Tangerine",
+            Self::SpuriousDragon => "SpuriousDragon",
+            Self::Byzantium => "Byzantium",
+            Self::Constantinople => "Constantinople",
+            Self::Petersburg => "Petersburg",
+            Self::Istanbul => "Istanbul",
+            Self::MuirGlacier => "MuirGlacier",
+            Self::Berlin => "Berlin",
+            Self::London => "London",
+            Self::ArrowGlacier => "ArrowGlacier",
+            Self::GrayGlacier => "GrayGlacier",
+            Self::Paris => "Paris",
+            Self::Shanghai => "Shanghai",
+            Self::Cancun => "Cancun",
+            Self::Prague => "Prague",
+            Self::Osaka => "Osaka",
+            Self::Bpo1 => "Bpo1",
+            Self::Bpo2 => "Bpo2",
+            Self::Bpo3 => "Bpo3",
+            Self::Bpo4 => "Bpo4",
+            Self::Bpo5 => "Bpo5",
+            Self::Amsterdam => "Amsterdam",
+        }
+    }
+}
+
+impl fmt::Display for Hardfork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The condition under which a [`Hardfork`] activates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ForkCondition {
+    /// The fork activates at the given block number.
+    Block(u64),
+    /// The fork activates at the given block timestamp.
+    Timestamp(u64),
+    /// The fork is not scheduled.
+    #[default]
+    Never,
+}
+
+impl ForkCondition {
+    /// Returns `true` if the fork is active for a block with the given number and timestamp.
+    pub const fn active_at(&self, block: u64, timestamp: u64) -> bool {
+        match *self {
+            Self::Block(activation) => block >= activation,
+            Self::Timestamp(activation) => timestamp >= activation,
+            Self::Never => false,
+        }
+    }
+
+    /// Returns `true` if the fork is scheduled.
+    pub const fn is_scheduled(&self) -> bool {
+        !matches!(self, Self::Never)
+    }
+
+    const fn from_block(block: Option<u64>) -> Self {
+        match block {
+            Some(block) => Self::Block(block),
+            None => Self::Never,
+        }
+    }
+
+    const fn from_timestamp(timestamp: Option<u64>) -> Self {
+        match timestamp {
+            Some(timestamp) => Self::Timestamp(timestamp),
+            None => Self::Never,
+        }
+    }
+}
+
+/// A chain specification answering which hardfork, fee parameters and transaction types apply to
+/// a block with a given number and timestamp.
+///
+/// The specification is derived from the [`ChainConfig`] of a [`Genesis`]. The activation of
+/// [`Hardfork::Paris`] is only known if the merge happened at genesis (a terminal total difficulty
+/// of zero) or if `mergeNetsplitBlock` is set, and can be overridden with
+/// [`with_fork`](Self::with_fork).
+///
+/// # Examples
+///
+/// ```
+/// use alloy_genesis::{ChainSpec, Hardfork};
+///
+/// let spec = ChainSpec::mainnet();
+/// assert_eq!(spec.active_fork(12_965_000, 0), Hardfork::London);
+/// assert_eq!(spec.active_fork(u64::MAX, 1_746_612_311), Hardfork::Prague);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainSpec {
+    chain_id: u64,
+    genesis_hash: B256,
+    genesis_timestamp: u64,
+    forks: BTreeMap<Hardfork, ForkCondition>,
+    fork_filter_keys: Vec<ForkFilterKey>,
+    base_fee_params: BaseFeeParams,
+    blob_params: BlobScheduleBlobParams,
+}
+
+impl ChainSpec {
+    /// Creates the chain specification of the given genesis, whose block hash is `genesis_hash`.
+    ///
+    /// The EIP-1559 base fee parameters default to [`BaseFeeParams::ethereum`].
+    pub fn new(genesis: &Genesis, genesis_hash: B256) -> Self {
+        let config = &genesis.config;
+
+        let paris = if config.terminal_total_difficulty == Some(U256::ZERO) {
+            ForkCondition::Block(0)
+        } else {
+            ForkCondition::from_block(config.merge_netsplit_block)
+        };
+        let dao = if config.dao_fork_support {
+            ForkCondition::from_block(config.dao_fork_block)
+        } else {
+            ForkCondition::Never
+        };
+
+        let forks = [
+            (Hardfork::Frontier, ForkCondition::Block(0)),
+            (Hardfork::Homestead, ForkCondition::from_block(config.homestead_block)),
+            (Hardfork::Dao, dao),
+            (Hardfork::Tangerine, ForkCondition::from_block(config.eip150_block)),
+            (Hardfork::SpuriousDragon, ForkCondition::from_block(config.eip158_block)),
+            (Hardfork::Byzantium, ForkCondition::from_block(config.byzantium_block)),
+            (Hardfork::Constantinople, ForkCondition::from_block(config.constantinople_block)),
+            (Hardfork::Petersburg, ForkCondition::from_block(config.petersburg_block)),
+            (Hardfork::Istanbul, ForkCondition::from_block(config.istanbul_block)),
+            (Hardfork::MuirGlacier, ForkCondition::from_block(config.muir_glacier_block)),
+            (Hardfork::Berlin, ForkCondition::from_block(config.berlin_block)),
+            (Hardfork::London, ForkCondition::from_block(config.london_block)),
+            (Hardfork::ArrowGlacier, ForkCondition::from_block(config.arrow_glacier_block)),
+            (Hardfork::GrayGlacier, ForkCondition::from_block(config.gray_glacier_block)),
+            (Hardfork::Paris, paris),
+            (Hardfork::Shanghai, ForkCondition::from_timestamp(config.shanghai_time)),
+            (Hardfork::Cancun, ForkCondition::from_timestamp(config.cancun_time)),
+            (Hardfork::Prague, ForkCondition::from_timestamp(config.prague_time)),
+            (Hardfork::Osaka, ForkCondition::from_timestamp(config.osaka_time)),
+            (Hardfork::Bpo1, ForkCondition::from_timestamp(config.bpo1_time)),
+            (Hardfork::Bpo2, ForkCondition::from_timestamp(config.bpo2_time)),
+            (Hardfork::Bpo3, ForkCondition::from_timestamp(config.bpo3_time)),
+            (Hardfork::Bpo4, ForkCondition::from_timestamp(config.bpo4_time)),
+            (Hardfork::Bpo5, ForkCondition::from_timestamp(config.bpo5_time)),
+            (Hardfork::Amsterdam, ForkCondition::from_timestamp(config.amsterdam_time)),
+        ]
+        .into_iter()
+        .filter(|(_, condition)| condition.is_scheduled())
+        .collect();
+
+        Self {
+            chain_id: config.chain_id,
+            genesis_hash,
+            genesis_timestamp: genesis.timestamp,
+            forks,
+            fork_filter_keys: fork_filter_keys(config),
+            base_fee_params: BaseFeeParams::ethereum(),
+            blob_params: config.blob_schedule_blob_params(),
+        }
+    }
+
+    /// Creates the chain specification of the given genesis, deriving the genesis hash from
+    /// [`Genesis::header`].
+    pub fn from_genesis(genesis: &Genesis) -> Self {
+        Self::new(genesis, genesis.hash_slow())
+    }
+
+    /// Returns the chain specification of Ethereum mainnet.
+    pub fn mainnet() -> Self {
+        let config = ChainConfig {
+            chain_id: 1,
+            homestead_block: Some(1_150_000),
+            dao_fork_block: Some(1_920_000),
+            dao_fork_support: true,
+            eip150_block: Some(2_463_000),
+            eip155_block: Some(2_675_000),
+            eip158_block: Some(2_675_000),
+            byzantium_block: Some(4_370_000),
+            constantinople_block: Some(7_280_000),
+            petersburg_block: Some(7_280_000),
+            istanbul_block: Some(9_069_000),
+            muir_glacier_block: Some(9_200_000),
+            berlin_block: Some(12_244_000),
+            london_block: Some(12_965_000),
+            arrow_glacier_block: Some(13_773_000),
+            gray_glacier_block: Some(15_050_000),
+            terminal_total_difficulty: Some(U256::from(58_750_000_000_000_000_000_000u128)),
+            terminal_total_difficulty_passed: true,
+            shanghai_time: Some(1_681_338_455),
+            cancun_time: Some(1_710_338_135),
+            prague_time: Some(1_746_612_311),
+            osaka_time: Some(1_764_798_551),
+            bpo1_time: Some(1_765_290_071),
+            bpo2_time: Some(1_767_747_671),
+            blob_schedule: bpo_blob_schedule(),
+            ..Default::default()
+        };
+        let genesis = Genesis { config, ..Default::default() };
+        Self::new(&genesis, MAINNET_GENESIS_HASH)
+            .with_fork(Hardfork::Paris, ForkCondition::Block(15_537_394))
+    }
+
+    /// Returns the chain specification of the Sepolia testnet.
+    pub fn sepolia() -> Self {
+        let config = ChainConfig {
+            chain_id: 11_155_111,
+            merge_netsplit_block: Some(1_735_371),
+            terminal_total_difficulty: Some(U256::from(17_000_000_000_000_000u128)),
+            terminal_total_difficulty_passed: true,
+            shanghai_time: Some(1_677_557_088),
+            cancun_time: Some(1_706_655_072),
+            prague_time: Some(1_741_159_776),
+            osaka_time: Some(1_760_427_360),
+            bpo1_time: Some(1_761_017_184),
+            bpo2_time: Some(1_761_607_008),
+            blob_schedule: bpo_blob_schedule(),
+            ..pre_merge_at_genesis()
+        };
+        let genesis = Genesis { config, timestamp: 1_633_267_481, ..Default::default() };
+        Self::new(&genesis, SEPOLIA_GENESIS_HASH)
+    }
+
+    /// Returns the chain specification of the Holesky testnet.
+    pub fn holesky() -> Self {
+        let config = ChainConfig {
+            chain_id: 17_000,
+            merge_netsplit_block: Some(0),
+            terminal_total_difficulty: Some(U256::ZERO),
+            terminal_total_difficulty_passed: true,
+            shanghai_time: Some(1_696_000_704),
+            cancun_time: Some(1_707_305_664),
+            prague_time: Some(1_740_434_112),
+            osaka_time: Some(1_759_308_480),
+            bpo1_time: Some(1_759_800_000),
+            bpo2_time: Some(1_760_389_824),
+            blob_schedule: bpo_blob_schedule(),
+            ..pre_merge_at_genesis()
+        };
+        let genesis = Genesis { config, timestamp: 1_695_902_100, ..Default::default() };
+        Self::new(&genesis, HOLESKY_GENESIS_HASH)
+    }
+
+    /// Overrides the activation of a hardfork.
+    ///
+    /// This does not change the [`ForkId`] of the chain, which is derived from the
+    /// [`ChainConfig`].
+    pub fn with_fork(mut self, fork: Hardfork, condition: ForkCondition) -> Self {
+        if condition.is_scheduled() {
+            self.forks.insert(fork, condition);
+        } else {
+            self.forks.remove(&fork);
+        }
+        self
+    }
+
+    /// Sets the EIP-1559 base fee parameters.
+    pub const fn with_base_fee_params(mut self, base_fee_params: BaseFeeParams) -> Self {
+        self.base_fee_params = base_fee_params;
+        self
+    }
+
+    /// Returns the chain ID.
+    pub const fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    /// Returns the hash of the genesis block.
+    pub const fn genesis_hash(&self) -> B256 {
+        self.genesis_hash
+    }
+
+    /// Returns the timestamp of the genesis block.
+    pub const fn genesis_timestamp(&self) -> u64 {
+        self.genesis_timestamp
+    }
+
+    /// Returns the scheduled hardforks and their activation conditions, in activation order.
+    pub fn forks(&self) -> impl Iterator<Item = (Hardfork, ForkCondition)> + '_ {
+        self.forks.iter().map(|(fork, condition)| (*fork, *condition))
+    }
+
+    /// Returns the activation condition of the hardfork.
+    pub fn fork_activation(&self, fork: Hardfork) -> ForkCondition {
+        self.forks.get(&fork).copied().unwrap_or_default()
+    }
+
+    /// Returns `true` if the hardfork is active for a block with the given number and timestamp.
+    pub fn is_fork_active_at(&self, fork: Hardfork, block: u64, timestamp: u64) -> bool {
+        self.fork_activation(fork).active_at(block, timestamp)
+    }
+
+    /// Returns the latest hardfork active for a block with the given number and timestamp.
+    pub fn active_fork(&self, block: u64, timestamp: u64) -> Hardfork {
+        self.forks
+            .iter()
+            .rev()
+            .find(|(_, condition)| condition.active_at(block, timestamp))
+            .map_or(Hardfork::Frontier, |(fork, _)| *fork)
+    }
+
+    /// Returns the EIP-1559 base fee parameters for a block with the given number and timestamp,
+    /// or `None` before London.
+    pub fn base_fee_params_at(&self, block: u64, timestamp: u64) -> Option<BaseFeeParams> {
+        self.is_fork_active_at(Hardfork::London, block, timestamp).then_some(self.base_fee_params)
+    }
+
+    /// Returns the blob parameters for a block with the given number and timestamp, or `None`
+    /// before Cancun.
+    ///
+    /// After Osaka, the blob parameters scheduled by blob parameter only forks are taken into
+    /// account.
+    pub fn blob_params_at(&self, block: u64, timestamp: u64) -> Option<BlobParams> {
+        if self.is_fork_active_at(Hardfork::Osaka, block, timestamp) {
+            let params = self.blob_params.active_scheduled_params_at_timestamp(timestamp);
+            Some(*params.unwrap_or(&self.blob_params.osaka))
+        } else if self.is_fork_active_at(Hardfork::Prague, block, timestamp) {
+            Some(self.blob_params.prague)
+        } else if self.is_fork_active_at(Hardfork::Cancun, block, timestamp) {
+            Some(self.blob_params.cancun)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the blob schedule of the chain.
+    pub const fn blob_schedule(&self) -> &BlobScheduleBlobParams {
+        &self.blob_params
+    }
+
+    /// Returns the transaction types that are valid in a block with the given number and
+    /// timestamp.
+    pub fn supported_tx_types(&self, block: u64, timestamp: u64) -> Vec<TxType> {
+        [TxType::Legacy, TxType::Eip2930, TxType::Eip1559, TxType::Eip4844, TxType::Eip7702]
+            .into_iter()
+            .filter(|tx_type| self.is_tx_type_supported(*tx_type, block, timestamp))
+            .collect()
+    }
+
+    /// Returns `true` if transactions of the given type are valid in a block with the given
+    /// number and timestamp.
+    pub fn is_tx_type_supported(&self, tx_type: TxType, block: u64, timestamp: u64) -> bool {
+        let fork = match tx_type {
+            TxType::Legacy => return true,
+            TxType::Eip2930 => Hardfork::Berlin,
+            TxType::Eip1559 => Hardfork::London,
+            TxType::Eip4844 => Hardfork::Cancun,
+            TxType::Eip7702 => Hardfork::Prague,
+        };
+        self.is_fork_active_at(fork, block, timestamp)
+    }
+
+    /// Returns the [EIP-2124](https://eips.ethereum.org/EIPS/eip-2124) fork filter for the given
+    /// head.
+    pub fn fork_filter(&self, head: Head) -> ForkFilter {
+        ForkFilter::new(
+            head,
+            self.genesis_hash,
+            self.genesis_timestamp,
+            self.fork_filter_keys.iter().copied(),
+        )
+    }
+
+    /// Returns the [EIP-2124](https://eips.ethereum.org/EIPS/eip-2124) fork identifier for a
+    /// block with the given number and timestamp.
+    pub fn fork_id(&self, block: u64, timestamp: u64) -> ForkId {
+        self.fork_filter(Head { number: block, timestamp, ..Default::default() }).current()
+    }
+}
+
+/// Returns the fork blocks and timestamps that make up the fork identifier, following geth.
+fn fork_filter_keys(config: &ChainConfig) -> Vec<ForkFilterKey> {
+    let blocks = [
+        config.homestead_block,
+        config.dao_fork_block,
+        config.eip150_block,
+        config.eip155_block,
+        config.eip158_block,
+        config.byzantium_block,
+        config.constantinople_block,
+        config.petersburg_block,
+        config.istanbul_block,
+        config.muir_glacier_block,
+        config.berlin_block,
+        config.london_block,
+        config.arrow_glacier_block,
+        config.gray_glacier_block,
+        config.merge_netsplit_block,
+    ];
+    let timestamps = [
+        config.shanghai_time,
+        config.cancun_time,
+        config.prague_time,
+        config.osaka_time,
+        config.bpo1_time,
+        config.bpo2_time,
+        config.bpo3_time,
+        config.bpo4_time,
+        config.bpo5_time,
+        config.amsterdam_time,
+    ];
+
+    blocks
+        .into_iter()
+        .flatten()
+        .map(ForkFilterKey::Block)
+        .chain(timestamps.into_iter().flatten().map(ForkFilterKey::Time))
+        .collect()
+}
+
+/// Returns a config with all pre-merge forks active at genesis.
+fn pre_merge_at_genesis() -> ChainConfig {
+    ChainConfig {
+        homestead_block: Some(0),
+        eip150_block: Some(0),
+        eip155_block: Some(0),
+        eip158_block: Some(0),
+        byzantium_block: Some(0),
+        constantinople_block: Some(0),
+        petersburg_block: Some(0),
+        istanbul_block: Some(0),
+        berlin_block: Some(0),
+        london_block: Some(0),
+        ..Default::default()
+    }
+}
+
+/// Returns the blob schedule of the BPO1 and BPO2 forks.
+fn bpo_blob_schedule() -> BTreeMap<String, BlobParams> {
+    BTreeMap::from([("bpo1".into(), BlobParams::bpo1()), ("bpo2".into(), BlobParams::bpo2())])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_eips::eip2124::ForkHash;
+    use alloy_primitives::hex;
+
+    #[test]
+    fn mainnet_forks() {
+        let spec = ChainSpec::mainnet();
+        assert_eq!(spec.active_fork(0, 0), Hardfork::Frontier);
+        assert_eq!(spec.active_fork(1_920_000, 0), Hardfork::Dao);
+        assert_eq!(spec.active_fork(15_537_394, 0), Hardfork::Paris);
+        assert_eq!(spec.active_fork(20_000_000, 1_710_338_135), Hardfork::Cancun);
+        assert_eq!(spec.active_fork(23_000_000, 1_765_290_071), Hardfork::Bpo1);
+
+        assert!(spec.base_fee_params_at(12_964_999, 0).is_none());
+        assert_eq!(spec.base_fee_params_at(12_965_000, 0), Some(BaseFeeParams::ethereum()));
+
+        assert_eq!(spec.blob_params_at(20_000_000, 1_710_338_134), None);
+        assert_eq!(spec.blob_params_at(20_000_000, 1_710_338_135), Some(BlobParams::cancun()));
+        assert_eq!(spec.blob_params_at(22_000_000, 1_746_612_311), Some(BlobParams::prague()));
+        let osaka = BlobParams::osaka();
+        assert_eq!(spec.blob_params_at(23_000_000, 1_764_798_551), Some(osaka));
+        let bpo2 = spec.blob_params_at(23_000_000, 1_767_747_671).unwrap();
+        assert_eq!(bpo2.max_blob_count, BlobParams::bpo2().max_blob_count);
+        assert_eq!(bpo2.max_blobs_per_tx, osaka.max_blobs_per_tx);
+    }
+
+    #[test]
+    fn supported_tx_types() {
+        let spec = ChainSpec::mainnet();
+        assert_eq!(spec.supported_tx_types(0, 0), vec![TxType::Legacy]);
+        assert_eq!(
+            spec.supported_tx_types(12_965_000, 0),
+            vec![TxType::Legacy, TxType::Eip2930, TxType::Eip1559]
+        );
+        assert!(!spec.is_tx_type_supported(TxType::Eip7702, 22_000_000, 1_746_612_310));
+        assert!(spec.is_tx_type_supported(TxType::Eip7702, 22_000_000, 1_746_612_311));
+    }
+
+    #[test]
+    fn mainnet_fork_ids() {
+        let spec = ChainSpec::mainnet();
+        let cases = [
+            ((0, 0), hex!("fc64ec04"), 1_150_000),
+            ((1_150_000, 0), hex!("97c2c34c"), 1_920_000),
+            ((12_965_000, 0), hex!("b715077d"), 13_773_000),
+            ((15_050_000, 0), hex!("f0afd0e3"), 1_681_338_455),
+            ((20_000_000, 1_681_338_455), hex!("dce96c2d"), 1_710_338_135),
+            ((20_000_000, 1_710_338_135), hex!("9f3d2254"), 1_746_612_311),
+            ((22_000_000, 1_746_612_311), hex!("c376cf8b"), 1_764_798_551),
+        ];
+        for ((block, timestamp), hash, next) in cases {
+            assert_eq!(spec.fork_id(block, timestamp), ForkId { hash: ForkHash(hash), next });
+        }
+    }
+
+    #[test]
+    fn fork_ids_of_testnets() {
+        assert_eq!(
+            ChainSpec::sepolia().fork_id(0, 0),
+            ForkId { hash: ForkHash(hex!("fe3366e7")), next: 1_735_371 }
+        );
+        assert_eq!(
+            ChainSpec::holesky().fork_id(0, 0),
+            ForkId { hash: ForkHash(hex!("c61a6098")), next: 1_696_000_704 }
+        );
+    }
+
+    #[test]
+    fn from_genesis_json() {
+        let genesis: Genesis = serde_json::from_str(
+            r#"{
+                "config": {
+                    "chainId": 1337,
+                    "homesteadBlock": 0,
+                    "eip150Block": 0,
+                    "eip155Block": 0,
+                    "eip158Block": 0,
+                    "byzantiumBlock": 0,
+                    "constantinopleBlock": 0,
+                    "petersburgBlock": 0,
+                    "istanbulBlock": 0,
+                    "berlinBlock": 0,
+                    "londonBlock": 0,
+                    "terminalTotalDifficulty": 0,
+                    "shanghaiTime": 0,
+                    "cancunTime": 0,
+                    "pragueTime": 100
+                },
+                "timestamp": "0x0",
+                "alloc": {}
+            }"#,
+        )
+        .unwrap();
+        let spec = ChainSpec::from_genesis(&genesis);
+
+        assert_eq!(spec.chain_id(), 1337);
+        assert_eq!(spec.genesis_hash(), genesis.hash_slow());
+        assert_eq!(spec.active_fork(0, 0), Hardfork::Cancun);
+        assert_eq!(spec.active_fork(0, 100), Hardfork::Prague);
+        assert!(spec.is_fork_active_at(Hardfork::Paris, 0, 0));
+        assert!(!spec.fork_activation(Hardfork::Osaka).is_scheduled());
+        assert_eq!(spec.fork_id(0, 0).next, 100);
+        assert_eq!(spec.fork_id(0, 100).next, 0);
+    }
+}
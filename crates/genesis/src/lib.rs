@@ -11,10 +11,17 @@
 
 extern crate alloc;
 
+mod chainspec;
+pub use chainspec::{ChainSpec, ForkCondition, Hardfork};
+
 use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use alloy_consensus::Header;
 use alloy_eips::{
+    eip1559::INITIAL_BASE_FEE,
     eip7594,
+    eip7685::EMPTY_REQUESTS_HASH,
     eip7840::{self, BlobParams},
+    eip7928::EMPTY_BLOCK_ACCESS_LIST_HASH,
     BlobScheduleBlobParams,
 };
 use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
@@ -204,6 +211,64 @@ impl Genesis {
         self.alloc.extend(accounts);
         self
     }
+
+    /// Computes the state root of the genesis block from the [`alloc`](Self::alloc) accounts.
+    pub fn state_root(&self) -> B256 {
+        alloy_trie::root::state_root_ref_unhashed(&self.alloc)
+    }
+
+    /// Derives the header of the genesis block.
+    ///
+    /// The fork-dependent fields are set according to the forks that are active at the genesis
+    /// block:
+    /// - London: the base fee, defaulting to [`INITIAL_BASE_FEE`] if unset, and saturated to
+    ///   `u64::MAX` since the header field is a `u64`
+    /// - Shanghai: the empty withdrawals root
+    /// - Cancun: the blob gas fields, defaulting to zero if unset, and a zero parent beacon block
+    ///   root
+    /// - Prague: the empty [EIP-7685](https://eips.ethereum.org/EIPS/eip-7685) requests hash
+    /// - Amsterdam: the empty block access list hash and slot number zero
+    pub fn header(&self) -> Header {
+        let config = &self.config;
+        let number = self.number.unwrap_or_default();
+        let timestamp = self.timestamp;
+
+        let london = config.is_london_active_at_block(number);
+        let shanghai = config.is_active_at_timestamp(config.shanghai_time, timestamp);
+        let cancun = config.is_active_at_timestamp(config.cancun_time, timestamp);
+        let prague = config.is_active_at_timestamp(config.prague_time, timestamp);
+        let amsterdam = config.is_active_at_timestamp(config.amsterdam_time, timestamp);
+
+        Header {
+            parent_hash: self.parent_hash.unwrap_or_default(),
+            number,
+            beneficiary: self.coinbase,
+            state_root: self.state_root(),
+            difficulty: self.difficulty,
+            gas_limit: self.gas_limit,
+            timestamp,
+            extra_data: self.extra_data.clone(),
+            mix_hash: self.mix_hash,
+            nonce: self.nonce.into(),
+            base_fee_per_gas: london.then(|| {
+                self.base_fee_per_gas
+                    .map_or(INITIAL_BASE_FEE, |base_fee| base_fee.try_into().unwrap_or(u64::MAX))
+            }),
+            withdrawals_root: shanghai.then_some(EMPTY_ROOT_HASH),
+            blob_gas_used: cancun.then(|| self.blob_gas_used.unwrap_or_default()),
+            excess_blob_gas: cancun.then(|| self.excess_blob_gas.unwrap_or_default()),
+            parent_beacon_block_root: cancun.then_some(B256::ZERO),
+            requests_hash: prague.then_some(EMPTY_REQUESTS_HASH),
+            block_access_list_hash: amsterdam.then_some(EMPTY_BLOCK_ACCESS_LIST_HASH),
+            slot_number: amsterdam.then_some(0),
+            ..Default::default()
+        }
+    }
+
+    /// Computes the hash of the genesis block, see [`header`](Self::header).
+    pub fn hash_slow(&self) -> B256 {
+        self.header().hash_slow()
+    }
 }
 
 /// An account in the state of the genesis block.
@@ -2115,6 +2180,58 @@ mod tests {
         assert_eq!(gen1, gen2);
     }
 
+    #[test]
+    fn dump_genesis_hashes() {
+        use alloy_consensus::constants::{
+            HOLESKY_GENESIS_HASH, MAINNET_GENESIS_HASH, SEPOLIA_GENESIS_HASH,
+        };
+
+        let cases = [
+            (include_str!("../dumpgenesis/mainnet.json"), MAINNET_GENESIS_HASH),
+            (include_str!("../dumpgenesis/sepolia.json"), SEPOLIA_GENESIS_HASH),
+            (include_str!("../dumpgenesis/holesky.json"), HOLESKY_GENESIS_HASH),
+        ];
+        for (json, hash) in cases {
+            let genesis = serde_json::from_str::<Genesis>(json).unwrap();
+            assert_eq!(genesis.hash_slow(), hash);
+        }
+    }
+
+    #[test]
+    fn genesis_header_fork_fields() {
+        let mut genesis = Genesis::default().with_gas_limit(30_000_000);
+        genesis.config.london_block = Some(0);
+        genesis.config.shanghai_time = Some(0);
+
+        let header = genesis.header();
+        assert_eq!(header.state_root, EMPTY_ROOT_HASH);
+        assert_eq!(header.base_fee_per_gas, Some(INITIAL_BASE_FEE));
+        assert_eq!(header.withdrawals_root, Some(EMPTY_ROOT_HASH));
+        assert_eq!(header.blob_gas_used, None);
+        assert_eq!(header.requests_hash, None);
+
+        genesis.config.cancun_time = Some(0);
+        genesis.config.prague_time = Some(0);
+        genesis.excess_blob_gas = Some(42);
+        genesis.base_fee_per_gas = Some(7);
+        let header = genesis.header();
+        assert_eq!(header.base_fee_per_gas, Some(7));
+        assert_eq!(header.blob_gas_used, Some(0));
+        assert_eq!(header.excess_blob_gas, Some(42));
+        assert_eq!(header.parent_beacon_block_root, Some(B256::ZERO));
+        assert_eq!(header.requests_hash, Some(EMPTY_REQUESTS_HASH));
+        assert_eq!(header.block_access_list_hash, None);
+
+        genesis.base_fee_per_gas = Some(u128::from(u64::MAX) + 1);
+        assert_eq!(genesis.header().base_fee_per_gas, Some(u64::MAX));
+
+        let genesis = genesis.extend_accounts([(
+            Address::repeat_byte(1),
+            GenesisAccount::default().with_balance(U256::from(1)),
+        )]);
+        assert_ne!(genesis.header().state_root, EMPTY_ROOT_HASH);
+    }
+
     #[test]
     fn test_parent_hash_serialization() {
         // Test that parent_hash can be serialized and deserialized correctly
@@ -0,0 +1,406 @@
+//! Builder for the genesis files of private devnets.
+
+use alloy_eips::{eip2935, eip4788, eip7002, eip7251, eip7840::BlobParams};
+use alloy_genesis::{ChainConfig, Genesis, GenesisAccount, Hardfork};
+use alloy_primitives::{Address, Bytes, B256, U256};
+#[cfg(feature = "mnemonic")]
+use alloy_signer_local::{coins_bip39::English, LocalSignerError, MnemonicBuilder};
+
+/// The JSON dialect produced by [`GenesisBuilder::to_json`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GenesisFormat {
+    /// The `genesis.json` format of geth, accepted by `geth init` and `reth --chain`.
+    ///
+    /// Private keys of prefunded accounts are omitted.
+    Geth,
+    /// The `genesis.json` format accepted by `anvil --init`.
+    ///
+    /// Private keys of prefunded accounts are included, so that anvil can use the accounts as
+    /// unlocked dev accounts.
+    Anvil,
+}
+
+/// Errors when configuring the forks of a [`GenesisBuilder`].
+#[derive(Debug, thiserror::Error)]
+pub enum GenesisBuilderError {
+    /// The fork is activated by block number, and always active at genesis.
+    #[error("{0} is activated by block number")]
+    BlockFork(Hardfork),
+    /// The fork does not have blob parameters.
+    #[error("{0} does not have blob parameters")]
+    NoBlobParams(Hardfork),
+}
+
+/// A builder for the [`Genesis`] of a private devnet.
+///
+/// By default, all forks up to and including Prague are active at genesis, the merge happened at
+/// genesis, and no accounts are funded.
+///
+/// # Examples
+///
+/// ```
+/// use alloy_genesis::Hardfork;
+/// use alloy_node_bindings::genesis::{GenesisBuilder, GenesisFormat};
+/// use alloy_primitives::{Address, U256};
+///
+/// let builder = GenesisBuilder::new(1337)
+///     .with_timestamp(1_700_000_000)
+///     .fund(Address::repeat_byte(1), U256::from(10).pow(U256::from(24)))
+///     .with_system_contracts()
+///     .with_fork(Hardfork::Osaka, 1_700_000_120)?;
+///
+/// let json = builder.to_json(GenesisFormat::Geth);
+/// let genesis = builder.build();
+/// assert_eq!(genesis.alloc.len(), 5);
+/// # Ok::<_, alloy_node_bindings::genesis::GenesisBuilderError>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct GenesisBuilder {
+    genesis: Genesis,
+}
+
+impl GenesisBuilder {
+    /// Creates a new builder for the given chain ID.
+    pub fn new(chain_id: u64) -> Self {
+        let config = ChainConfig {
+            chain_id,
+            homestead_block: Some(0),
+            eip150_block: Some(0),
+            eip155_block: Some(0),
+            eip158_block: Some(0),
+            byzantium_block: Some(0),
+            constantinople_block: Some(0),
+            petersburg_block: Some(0),
+            istanbul_block: Some(0),
+            muir_glacier_block: Some(0),
+            berlin_block: Some(0),
+            london_block: Some(0),
+            arrow_glacier_block: Some(0),
+            gray_glacier_block: Some(0),
+            merge_netsplit_block: Some(0),
+            terminal_total_difficulty: Some(U256::ZERO),
+            terminal_total_difficulty_passed: true,
+            shanghai_time: Some(0),
+            cancun_time: Some(0),
+            prague_time: Some(0),
+            blob_schedule: [
+                ("cancun".to_string(), BlobParams::cancun()),
+                ("prague".to_string(), BlobParams::prague()),
+            ]
+            .into(),
+            ..Default::default()
+        };
+
+        Self { genesis: Genesis { config, gas_limit: 30_000_000, ..Default::default() } }
+    }
+
+    /// Sets the timestamp of the genesis block.
+    pub const fn with_timestamp(mut self, timestamp: u64) -> Self {
+        self.genesis.timestamp = timestamp;
+        self
+    }
+
+    /// Sets the gas limit of the genesis block.
+    pub const fn with_gas_limit(mut self, gas_limit: u64) -> Self {
+        self.genesis.gas_limit = gas_limit;
+        self
+    }
+
+    /// Sets the extra data of the genesis block.
+    pub fn with_extra_data(mut self, extra_data: Bytes) -> Self {
+        self.genesis.extra_data = extra_data;
+        self
+    }
+
+    /// Sets the base fee of the genesis block.
+    pub const fn with_base_fee(mut self, base_fee: u128) -> Self {
+        self.genesis.base_fee_per_gas = Some(base_fee);
+        self
+    }
+
+    /// Sets the address of the deposit contract, used to parse deposit requests after Prague.
+    pub const fn with_deposit_contract(mut self, address: Address) -> Self {
+        self.genesis.config.deposit_contract_address = Some(address);
+        self
+    }
+
+    /// Adds an account to the genesis state, replacing any existing account at the address.
+    pub fn with_account(mut self, address: Address, account: GenesisAccount) -> Self {
+        self.genesis.alloc.insert(address, account);
+        self
+    }
+
+    /// Funds the address with the given balance.
+    pub fn fund(self, address: Address, balance: U256) -> Self {
+        self.with_account(address, GenesisAccount::default().with_balance(balance))
+    }
+
+    /// Funds the first `count` accounts derived from the mnemonic phrase with the given balance.
+    ///
+    /// Accounts are derived with the path `m/44'/60'/0'/0/{index}`. Their private keys are
+    /// included in the [`GenesisFormat::Anvil`] output.
+    #[cfg(feature = "mnemonic")]
+    pub fn fund_mnemonic(
+        mut self,
+        phrase: &str,
+        count: u32,
+        balance: U256,
+    ) -> Result<Self, LocalSignerError> {
+        for index in 0..count {
+            let signer = MnemonicBuilder::<English>::try_from_phrase_nth(phrase, index)?;
+            let account = GenesisAccount {
+                balance,
+                private_key: Some(signer.to_bytes()),
+                ..Default::default()
+            };
+            self.genesis.alloc.insert(signer.address(), account);
+        }
+        Ok(self)
+    }
+
+    /// Predeploys the system contracts of EIP-4788 (beacon roots), EIP-2935 (block hash
+    /// history), EIP-7002 (withdrawal requests) and EIP-7251 (consolidation requests).
+    pub fn with_system_contracts(self) -> Self {
+        [
+            (eip4788::BEACON_ROOTS_ADDRESS, &eip4788::BEACON_ROOTS_CODE),
+            (eip2935::HISTORY_STORAGE_ADDRESS, &eip2935::HISTORY_STORAGE_CODE),
+            (
+                eip7002::WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS,
+                &eip7002::WITHDRAWAL_REQUEST_PREDEPLOY_CODE,
+            ),
+            (
+                eip7251::CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS,
+                &eip7251::CONSOLIDATION_REQUEST_PREDEPLOY_CODE,
+            ),
+        ]
+        .into_iter()
+        .fold(self, |builder, (address, code)| {
+            let account =
+                GenesisAccount { nonce: Some(1), code: Some(code.clone()), ..Default::default() };
+            builder.with_account(address, account)
+        })
+    }
+
+    /// Schedules a timestamp-based fork, from Shanghai onwards.
+    ///
+    /// Returns an error if the fork is activated by block number. These forks are always active
+    /// at genesis.
+    pub fn with_fork(
+        mut self,
+        fork: Hardfork,
+        timestamp: u64,
+    ) -> Result<Self, GenesisBuilderError> {
+        let config = &mut self.genesis.config;
+        let field = match fork {
+            Hardfork::Shanghai => &mut config.shanghai_time,
+            Hardfork::Cancun => &mut config.cancun_time,
+            Hardfork::Prague => &mut config.prague_time,
+            Hardfork::Osaka => &mut config.osaka_time,
+            Hardfork::Bpo1 => &mut config.bpo1_time,
+            Hardfork::Bpo2 => &mut config.bpo2_time,
+            Hardfork::Bpo3 => &mut config.bpo3_time,
+            Hardfork::Bpo4 => &mut config.bpo4_time,
+            Hardfork::Bpo5 => &mut config.bpo5_time,
+            Hardfork::Amsterdam => &mut config.amsterdam_time,
+            _ => return Err(GenesisBuilderError::BlockFork(fork)),
+        };
+        *field = Some(timestamp);
+
+        let default_params = match fork {
+            Hardfork::Osaka => Some(BlobParams::osaka()),
+            Hardfork::Bpo1 => Some(BlobParams::bpo1()),
+            Hardfork::Bpo2 => Some(BlobParams::bpo2()),
+            _ => None,
+        };
+        if let (Some(key), Some(params)) = (blob_schedule_key(fork), default_params) {
+            config.blob_schedule.entry(key.to_string()).or_insert(params);
+        }
+        Ok(self)
+    }
+
+    /// Removes a timestamp-based fork and all forks after it.
+    pub fn without_fork(mut self, fork: Hardfork) -> Self {
+        let config = &mut self.genesis.config;
+        let forks = [
+            (Hardfork::Shanghai, &mut config.shanghai_time),
+            (Hardfork::Cancun, &mut config.cancun_time),
+            (Hardfork::Prague, &mut config.prague_time),
+            (Hardfork::Osaka, &mut config.osaka_time),
+            (Hardfork::Bpo1, &mut config.bpo1_time),
+            (Hardfork::Bpo2, &mut config.bpo2_time),
+            (Hardfork::Bpo3, &mut config.bpo3_time),
+            (Hardfork::Bpo4, &mut config.bpo4_time),
+            (Hardfork::Bpo5, &mut config.bpo5_time),
+            (Hardfork::Amsterdam, &mut config.amsterdam_time),
+        ];
+        for (scheduled, field) in forks {
+            if scheduled >= fork {
+                *field = None;
+                if let Some(key) = blob_schedule_key(scheduled) {
+                    config.blob_schedule.remove(key);
+                }
+            }
+        }
+        self
+    }
+
+    /// Sets the blob parameters of a fork that changes them, from Cancun onwards.
+    ///
+    /// Osaka, BPO1 and BPO2 default to the mainnet parameters when scheduled with
+    /// [`with_fork`](Self::with_fork). BPO3 to BPO5 and Amsterdam have no default parameters and
+    /// must be configured with this method.
+    ///
+    /// Returns an error if the fork does not have blob parameters.
+    pub fn with_blob_params(
+        mut self,
+        fork: Hardfork,
+        params: BlobParams,
+    ) -> Result<Self, GenesisBuilderError> {
+        let key = blob_schedule_key(fork).ok_or(GenesisBuilderError::NoBlobParams(fork))?;
+        self.genesis.config.blob_schedule.insert(key.to_string(), params);
+        Ok(self)
+    }
+
+    /// Returns the genesis being built.
+    pub const fn genesis(&self) -> &Genesis {
+        &self.genesis
+    }
+
+    /// Returns the genesis.
+    pub fn build(self) -> Genesis {
+        self.genesis
+    }
+
+    /// Returns the hash of the genesis block.
+    pub fn genesis_hash(&self) -> B256 {
+        self.genesis.hash_slow()
+    }
+
+    /// Serializes the genesis in the given format.
+    pub fn to_json(&self, format: GenesisFormat) -> serde_json::Value {
+        let mut genesis = self.genesis.clone();
+        match format {
+            GenesisFormat::Geth => {
+                for account in genesis.alloc.values_mut() {
+                    account.private_key = None;
+                }
+            }
+            GenesisFormat::Anvil => {}
+        }
+        serde_json::to_value(genesis).expect("genesis is serializable")
+    }
+}
+
+/// Returns the key of the fork in the `blobSchedule` of the [`ChainConfig`].
+const fn blob_schedule_key(fork: Hardfork) -> Option<&'static str> {
+    Some(match fork {
+        Hardfork::Cancun => "cancun",
+        Hardfork::Prague => "prague",
+        Hardfork::Osaka => "osaka",
+        Hardfork::Bpo1 => "bpo1",
+        Hardfork::Bpo2 => "bpo2",
+        Hardfork::Bpo3 => "bpo3",
+        Hardfork::Bpo4 => "bpo4",
+        Hardfork::Bpo5 => "bpo5",
+        Hardfork::Amsterdam => "Amsterdam",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Anvil;
+    use alloy_genesis::ChainSpec;
+
+    #[test]
+    #[cfg(feature = "mnemonic")]
+    fn fund_mnemonic_accounts() {
+        use alloy_primitives::address;
+
+        const PHRASE: &str = "test test test test test test test test test test test junk";
+
+        let builder = GenesisBuilder::new(1337).fund_mnemonic(PHRASE, 2, U256::from(1)).unwrap();
+        let genesis = builder.genesis();
+        // the well-known first two anvil/hardhat accounts
+        let first = &genesis.alloc[&address!("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266")];
+        assert_eq!(first.balance, U256::from(1));
+        assert!(first.private_key.is_some());
+        assert!(genesis
+            .alloc
+            .contains_key(&address!("0x70997970C51812dc3A010C7d01b50e0d17dc79C8")));
+
+        let geth = builder.to_json(GenesisFormat::Geth).to_string();
+        assert!(!geth.contains("secretKey"));
+        let anvil = builder.to_json(GenesisFormat::Anvil).to_string();
+        assert!(anvil.contains("secretKey"));
+        let parsed: Genesis = serde_json::from_str(&geth).unwrap();
+        assert_eq!(parsed.config, genesis.config);
+    }
+
+    #[test]
+    fn system_contracts() {
+        let genesis = GenesisBuilder::new(1337).with_system_contracts().build();
+        let beacon_roots = &genesis.alloc[&eip4788::BEACON_ROOTS_ADDRESS];
+        assert_eq!(beacon_roots.nonce, Some(1));
+        assert_eq!(beacon_roots.code.as_ref(), Some(&eip4788::BEACON_ROOTS_CODE));
+        assert_eq!(genesis.alloc.len(), 4);
+    }
+
+    #[test]
+    fn schedule_forks() {
+        let genesis = GenesisBuilder::new(1337)
+            .with_fork(Hardfork::Osaka, 100)
+            .and_then(|builder| builder.with_fork(Hardfork::Bpo1, 200))
+            .and_then(|builder| builder.with_fork(Hardfork::Bpo3, 300))
+            .and_then(|builder| builder.with_blob_params(Hardfork::Bpo3, BlobParams::bpo2()))
+            .unwrap()
+            .build();
+        let spec = ChainSpec::from_genesis(&genesis);
+        assert_eq!(spec.active_fork(0, 99), Hardfork::Prague);
+        assert_eq!(spec.active_fork(0, 100), Hardfork::Osaka);
+        assert_eq!(spec.blob_params_at(0, 250).unwrap().max_blob_count, 15);
+        assert_eq!(spec.blob_params_at(0, 300).unwrap().max_blob_count, 21);
+
+        let genesis = GenesisBuilder::new(1337)
+            .with_fork(Hardfork::Osaka, 100)
+            .unwrap()
+            .without_fork(Hardfork::Cancun);
+        let config = &genesis.genesis().config;
+        assert_eq!(config.shanghai_time, Some(0));
+        assert_eq!(config.cancun_time, None);
+        assert_eq!(config.osaka_time, None);
+        assert!(config.blob_schedule.is_empty());
+    }
+
+    #[test]
+    fn invalid_forks() {
+        let err = GenesisBuilder::new(1337).with_fork(Hardfork::London, 1).unwrap_err();
+        assert_eq!(err.to_string(), "London is activated by block number");
+        let err = GenesisBuilder::new(1337)
+            .with_blob_params(Hardfork::Shanghai, BlobParams::cancun())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Shanghai does not have blob parameters");
+    }
+
+    #[test]
+    fn anvil_init() {
+        let key = B256::with_last_byte(1);
+        let account =
+            GenesisAccount { balance: U256::from(1), private_key: Some(key), ..Default::default() };
+        let builder = GenesisBuilder::new(1337)
+            .with_account(Address::repeat_byte(1), account)
+            .with_system_contracts();
+        let json = builder.to_json(GenesisFormat::Anvil);
+        assert_eq!(
+            json["alloc"][Address::repeat_byte(1).to_string()]["secretKey"],
+            key.to_string()
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("genesis.json");
+        std::fs::write(&path, json.to_string()).unwrap();
+        let anvil = Anvil::new().arg("--init").arg(&path).spawn();
+        assert_eq!(anvil.chain_id(), 1337);
+    }
+}
@@ -18,6 +18,9 @@ pub use nodes::{
     reth::{self, Reth, RethInstance},
 };
 
+pub mod genesis;
+pub use genesis::{GenesisBuilder, GenesisFormat};
+
 mod node;
 pub use node::*;
 
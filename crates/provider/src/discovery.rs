@@ -0,0 +1,292 @@
+//! [BIP-44] account discovery for mnemonic wallets.
+//!
+//! [BIP-44]: https://github.com/bitcoin/bips/blob/master/bip-0044.mediawiki#account-discovery
+
+use crate::Provider;
+use alloy_eips::BlockId;
+use alloy_network::{EthereumWallet, Network};
+use alloy_primitives::{Address, U256};
+use alloy_signer_local::{
+    coins_bip39::{English, Wordlist},
+    DerivationPathTemplate, LocalSignerError, MnemonicBuilder, PrivateKeySigner,
+};
+use alloy_transport::TransportError;
+
+/// The default number of consecutive unused accounts after which discovery stops.
+pub const DEFAULT_GAP_LIMIT: u32 = 20;
+
+/// An account found during [`AccountDiscovery`].
+#[derive(Clone, Debug)]
+pub struct DiscoveredAccount {
+    /// The index of the account in the [`DerivationPathTemplate`].
+    pub index: u32,
+    /// The signer of the account.
+    pub signer: PrivateKeySigner,
+    /// The nonce of the account at the queried block.
+    pub nonce: u64,
+    /// The balance of the account at the queried block.
+    pub balance: U256,
+}
+
+impl DiscoveredAccount {
+    /// Returns the address of the account.
+    pub const fn address(&self) -> Address {
+        self.signer.address()
+    }
+
+    /// Returns `true` if the account has sent a transaction or holds a balance.
+    pub fn is_active(&self) -> bool {
+        self.nonce != 0 || !self.balance.is_zero()
+    }
+}
+
+/// Error returned by [`AccountDiscovery`].
+#[derive(Debug, thiserror::Error)]
+pub enum AccountDiscoveryError {
+    /// Deriving a signer from the mnemonic failed.
+    #[error(transparent)]
+    Signer(#[from] LocalSignerError),
+    /// Querying the state of an account failed.
+    #[error(transparent)]
+    Transport(#[from] TransportError),
+}
+
+/// Discovers the active accounts of a mnemonic by walking the derivation indices of a
+/// [`DerivationPathTemplate`].
+///
+/// An account is considered active if it has a non-zero nonce or balance. Discovery stops once
+/// [`gap_limit`](Self::gap_limit) consecutive inactive accounts are found, as described in
+/// [BIP-44].
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn example<P: alloy_provider::Provider>(provider: P) -> Result<(), Box<dyn std::error::Error>> {
+/// use alloy_provider::discovery::AccountDiscovery;
+/// use alloy_signer_local::{DerivationPathTemplate, MnemonicBuilder};
+///
+/// let builder = MnemonicBuilder::from_phrase("test test test test test test test test test test test junk");
+/// let wallet = AccountDiscovery::new(builder)
+///     .template(DerivationPathTemplate::LedgerLive)
+///     .gap_limit(5)
+///     .discover_wallet(&provider)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [BIP-44]: https://github.com/bitcoin/bips/blob/master/bip-0044.mediawiki#account-discovery
+#[derive(Clone, Debug)]
+#[must_use = "account discovery does nothing unless `discover` is called"]
+pub struct AccountDiscovery<W: Wordlist = English> {
+    builder: MnemonicBuilder<W>,
+    template: DerivationPathTemplate,
+    gap_limit: u32,
+    start: u32,
+    block: BlockId,
+}
+
+impl<W: Wordlist> AccountDiscovery<W> {
+    /// Creates a new account discovery for the mnemonic configured in the given builder.
+    ///
+    /// Only the phrase and password of the builder are used, the derivation path is determined by
+    /// the [`DerivationPathTemplate`].
+    pub fn new(builder: MnemonicBuilder<W>) -> Self {
+        Self {
+            builder,
+            template: DerivationPathTemplate::default(),
+            gap_limit: DEFAULT_GAP_LIMIT,
+            start: 0,
+            block: BlockId::latest(),
+        }
+    }
+
+    /// Sets the [`DerivationPathTemplate`] used to derive the accounts.
+    pub const fn template(mut self, template: DerivationPathTemplate) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Sets the number of consecutive inactive accounts after which discovery stops.
+    ///
+    /// Defaults to [`DEFAULT_GAP_LIMIT`]. A limit of zero is treated as one.
+    pub const fn gap_limit(mut self, gap_limit: u32) -> Self {
+        self.gap_limit = gap_limit;
+        self
+    }
+
+    /// Sets the index at which discovery starts.
+    pub const fn start_index(mut self, start: u32) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// Sets the block at which the account state is queried.
+    ///
+    /// Defaults to the latest block.
+    pub const fn block(mut self, block: BlockId) -> Self {
+        self.block = block;
+        self
+    }
+
+    /// Walks the derivation indices and returns all active accounts, ordered by index.
+    pub async fn discover<P, N>(
+        &self,
+        provider: &P,
+    ) -> Result<Vec<DiscoveredAccount>, AccountDiscoveryError>
+    where
+        P: Provider<N>,
+        N: Network,
+    {
+        let master = self.builder.build_master_key()?;
+        let gap_limit = self.gap_limit.max(1);
+
+        let mut accounts = Vec::new();
+        let mut gap = 0;
+        let mut index = self.start;
+        while gap < gap_limit {
+            let signer = master.signer_at(self.template, index)?;
+            let account = self.fetch_account(provider, index, signer).await?;
+            trace!(index, address = %account.address(), active = account.is_active(), "checked account");
+
+            if account.is_active() {
+                accounts.push(account);
+                gap = 0;
+            } else {
+                gap += 1;
+            }
+
+            index = match index.checked_add(1) {
+                Some(index) => index,
+                None => break,
+            };
+        }
+
+        Ok(accounts)
+    }
+
+    /// Discovers the active accounts and returns an [`EthereumWallet`] with all of them
+    /// registered.
+    ///
+    /// The account with the lowest index is used as the default signer. If no account is active,
+    /// the wallet only contains the account at the start index, which is where a new user would
+    /// receive their first funds.
+    pub async fn discover_wallet<P, N>(
+        &self,
+        provider: &P,
+    ) -> Result<EthereumWallet, AccountDiscoveryError>
+    where
+        P: Provider<N>,
+        N: Network,
+    {
+        let mut accounts = self.discover(provider).await?.into_iter();
+
+        let Some(first) = accounts.next() else {
+            let signer = self.builder.build_master_key()?.signer_at(self.template, self.start)?;
+            return Ok(EthereumWallet::new(signer));
+        };
+
+        let mut wallet = EthereumWallet::new(first.signer);
+        for account in accounts {
+            wallet.register_signer(account.signer);
+        }
+        Ok(wallet)
+    }
+
+    async fn fetch_account<P, N>(
+        &self,
+        provider: &P,
+        index: u32,
+        signer: PrivateKeySigner,
+    ) -> Result<DiscoveredAccount, TransportError>
+    where
+        P: Provider<N>,
+        N: Network,
+    {
+        let address = signer.address();
+        let nonce = provider.get_transaction_count(address).block_id(self.block).await?;
+        let balance = provider.get_balance(address).block_id(self.block).await?;
+        Ok(DiscoveredAccount { index, signer, nonce, balance })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mock::Asserter, ProviderBuilder};
+    use alloy_primitives::U64;
+
+    const PHRASE: &str = "test test test test test test test test test test test junk";
+
+    fn push_account(asserter: &Asserter, nonce: u64, balance: u64) {
+        asserter.push_success(&U64::from(nonce));
+        asserter.push_success(&U256::from(balance));
+    }
+
+    #[tokio::test]
+    async fn discovers_accounts_until_gap() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+        // index 0 has sent transactions, index 1 is unused, index 2 only holds a balance, then the
+        // gap of two unused accounts ends discovery.
+        push_account(&asserter, 3, 0);
+        push_account(&asserter, 0, 0);
+        push_account(&asserter, 0, 100);
+        push_account(&asserter, 0, 0);
+        push_account(&asserter, 0, 0);
+
+        let discovery = AccountDiscovery::new(MnemonicBuilder::from_phrase(PHRASE)).gap_limit(2);
+        let accounts = discovery.discover(&provider).await.unwrap();
+        assert!(asserter.read_q().is_empty());
+
+        assert_eq!(accounts.iter().map(|a| a.index).collect::<Vec<_>>(), [0, 2]);
+        assert_eq!(accounts[0].nonce, 3);
+        assert_eq!(accounts[1].balance, U256::from(100));
+        assert_eq!(accounts[1].address(), MnemonicBuilder::from_phrase_nth(PHRASE, 2).address(),);
+    }
+
+    #[tokio::test]
+    async fn discovers_wallet() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+        push_account(&asserter, 1, 0);
+        push_account(&asserter, 0, 1);
+        push_account(&asserter, 0, 0);
+
+        let template = DerivationPathTemplate::LedgerLive;
+        let wallet = AccountDiscovery::new(MnemonicBuilder::from_phrase(PHRASE))
+            .template(template)
+            .gap_limit(1)
+            .start_index(1)
+            .discover_wallet(&provider)
+            .await
+            .unwrap();
+        assert!(asserter.read_q().is_empty());
+
+        let master = MnemonicBuilder::from_phrase(PHRASE).build_master_key().unwrap();
+        let address = |index| master.signer_at(template, index).unwrap().address();
+        assert_eq!(wallet.default_signer().address(), address(1));
+        assert!(wallet.signer_by_address(address(2)).is_some());
+        assert!(wallet.signer_by_address(address(3)).is_none());
+    }
+
+    #[tokio::test]
+    async fn discovers_empty_wallet() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+        push_account(&asserter, 0, 0);
+
+        let wallet = AccountDiscovery::new(MnemonicBuilder::from_phrase(PHRASE))
+            .gap_limit(0)
+            .discover_wallet(&provider)
+            .await
+            .unwrap();
+        assert_eq!(
+            wallet.default_signer().address(),
+            MnemonicBuilder::from_phrase_first(PHRASE).address()
+        );
+    }
+}
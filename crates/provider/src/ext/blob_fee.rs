@@ -0,0 +1,277 @@
+//! This module extends the Ethereum JSON-RPC provider with blob fee forecasting and a policy for
+//! sending blob transactions once the blob fee is low enough.
+use crate::{utils::poll_until, PendingTransactionBuilder, Provider};
+use alloy_consensus::BlockHeader;
+use alloy_eips::{
+    eip7840::{BlobFeeForecaster, BlobFeeProjection, BlobGasState, BlobGasUsage},
+    BlockNumberOrTag,
+};
+use alloy_network::{Network, TransactionBuilder4844};
+use alloy_network_primitives::BlockResponse;
+use alloy_transport::TransportError;
+use std::{ops::ControlFlow, time::Duration};
+
+/// Errors that may occur when forecasting blob fees or waiting for a low blob fee.
+#[derive(Debug, thiserror::Error)]
+pub enum BlobFeeError {
+    /// Underlying transport error.
+    #[error(transparent)]
+    Transport(#[from] TransportError),
+    /// The requested block does not exist.
+    #[error("block {0} not found")]
+    BlockNotFound(BlockNumberOrTag),
+    /// The block has no blob gas fields, i.e. it predates the Cancun hardfork.
+    #[error("block {0} has no blob gas fields")]
+    MissingBlobGas(BlockNumberOrTag),
+    /// The blob fee did not drop to the maximum before the timeout elapsed.
+    #[error("blob fee {blob_fee} did not drop to {max_blob_fee} before the timeout")]
+    Timeout {
+        /// The maximum blob fee of the policy.
+        max_blob_fee: u128,
+        /// The last observed blob fee.
+        blob_fee: u128,
+    },
+}
+
+/// Policy for sending blob transactions only once the blob fee is low enough, e.g. for batch
+/// posters that can delay their submissions.
+///
+/// See [`BlobFeeApi::wait_for_blob_fee`] and [`BlobFeeApi::send_transaction_with_blob_fee_policy`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlobFeePolicy {
+    max_blob_fee: u128,
+    poll_interval: Option<Duration>,
+    timeout: Option<Duration>,
+}
+
+impl BlobFeePolicy {
+    /// Creates a new policy that waits until the blob base fee of the next block is at most
+    /// `max_blob_fee`.
+    pub const fn new(max_blob_fee: u128) -> Self {
+        Self { max_blob_fee, poll_interval: None, timeout: None }
+    }
+
+    /// Returns the maximum blob fee of the policy.
+    pub const fn max_blob_fee(&self) -> u128 {
+        self.max_blob_fee
+    }
+
+    /// Sets the interval at which the blob base fee is polled.
+    ///
+    /// Defaults to the poll interval of the client.
+    pub const fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = Some(poll_interval);
+        self
+    }
+
+    /// Sets the duration after which waiting for a low blob fee is aborted.
+    ///
+    /// Defaults to no timeout.
+    pub const fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+/// Blob fee forecasting and scheduling helpers.
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+pub trait BlobFeeApi<N: Network>: Send + Sync {
+    /// Returns the blob gas state of the given block.
+    async fn get_blob_gas_state(
+        &self,
+        block: BlockNumberOrTag,
+    ) -> Result<BlobGasState, BlobFeeError>;
+
+    /// Returns the average blob gas usage of the last `block_count` blocks, based on the
+    /// `blobGasUsedRatio` reported by `eth_feeHistory`.
+    async fn get_blob_gas_usage(&self, block_count: u64) -> Result<BlobGasUsage, BlobFeeError>;
+
+    /// Projects the blob fees of the next `blocks` blocks after the latest block, assuming every
+    /// future block uses blob gas according to `usage`.
+    ///
+    /// Use [`get_blob_gas_usage`](Self::get_blob_gas_usage) to assume recent usage continues.
+    async fn forecast_blob_fees(
+        &self,
+        forecaster: &BlobFeeForecaster,
+        usage: BlobGasUsage,
+        blocks: usize,
+    ) -> Result<Vec<BlobFeeProjection>, BlobFeeError>;
+
+    /// Polls `eth_blobBaseFee` until the blob base fee is at most the maximum of the policy, and
+    /// returns the blob base fee.
+    async fn wait_for_blob_fee(&self, policy: BlobFeePolicy) -> Result<u128, BlobFeeError>;
+
+    /// Waits for the blob fee to drop according to the policy, then sends the transaction.
+    ///
+    /// If the transaction has no `max_fee_per_blob_gas`, it is set to the maximum of the policy,
+    /// so the transaction never pays more than the policy allows.
+    async fn send_transaction_with_blob_fee_policy(
+        &self,
+        tx: N::TransactionRequest,
+        policy: BlobFeePolicy,
+    ) -> Result<PendingTransactionBuilder<N>, BlobFeeError>
+    where
+        N::TransactionRequest: TransactionBuilder4844;
+}
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl<N, P> BlobFeeApi<N> for P
+where
+    N: Network,
+    P: Provider<N>,
+{
+    async fn get_blob_gas_state(
+        &self,
+        block: BlockNumberOrTag,
+    ) -> Result<BlobGasState, BlobFeeError> {
+        let block =
+            self.get_block_by_number(block).await?.ok_or(BlobFeeError::BlockNotFound(block))?;
+        let header = block.header();
+        let (Some(excess_blob_gas), Some(blob_gas_used)) =
+            (header.excess_blob_gas(), header.blob_gas_used())
+        else {
+            return Err(BlobFeeError::MissingBlobGas(header.number().into()));
+        };
+
+        Ok(BlobGasState {
+            timestamp: header.timestamp(),
+            excess_blob_gas,
+            blob_gas_used,
+            base_fee_per_gas: header.base_fee_per_gas().unwrap_or_default(),
+        })
+    }
+
+    async fn get_blob_gas_usage(&self, block_count: u64) -> Result<BlobGasUsage, BlobFeeError> {
+        let fee_history = self.get_fee_history(block_count, BlockNumberOrTag::Latest, &[]).await?;
+        let ratios = &fee_history.blob_gas_used_ratio;
+        if ratios.is_empty() {
+            return Ok(BlobGasUsage::Empty);
+        }
+        Ok(BlobGasUsage::Ratio(ratios.iter().sum::<f64>() / ratios.len() as f64))
+    }
+
+    async fn forecast_blob_fees(
+        &self,
+        forecaster: &BlobFeeForecaster,
+        usage: BlobGasUsage,
+        blocks: usize,
+    ) -> Result<Vec<BlobFeeProjection>, BlobFeeError> {
+        let head = self.get_blob_gas_state(BlockNumberOrTag::Latest).await?;
+        Ok(forecaster.forecast(head, usage, blocks))
+    }
+
+    async fn wait_for_blob_fee(&self, policy: BlobFeePolicy) -> Result<u128, BlobFeeError> {
+        let poll_interval = policy.poll_interval.unwrap_or_else(|| self.client().poll_interval());
+
+        let blob_fee = poll_until(poll_interval, policy.timeout, || async {
+            let blob_fee = self.get_blob_base_fee().await?;
+            if blob_fee <= policy.max_blob_fee {
+                return Ok::<_, BlobFeeError>(ControlFlow::Break(blob_fee));
+            }
+            trace!(blob_fee, max_blob_fee = policy.max_blob_fee, "blob fee too high");
+            Ok(ControlFlow::Continue(blob_fee))
+        })
+        .await?;
+        blob_fee.map_err(|blob_fee| BlobFeeError::Timeout {
+            max_blob_fee: policy.max_blob_fee,
+            blob_fee,
+        })
+    }
+
+    async fn send_transaction_with_blob_fee_policy(
+        &self,
+        mut tx: N::TransactionRequest,
+        policy: BlobFeePolicy,
+    ) -> Result<PendingTransactionBuilder<N>, BlobFeeError>
+    where
+        N::TransactionRequest: TransactionBuilder4844,
+    {
+        self.wait_for_blob_fee(policy).await?;
+        if tx.max_fee_per_blob_gas().is_none() {
+            tx.set_max_fee_per_blob_gas(policy.max_blob_fee);
+        }
+        Ok(self.send_transaction(tx).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mock::Asserter, ProviderBuilder};
+    use alloy_eips::eip7892::BlobScheduleBlobParams;
+    use alloy_primitives::U128;
+    use alloy_rpc_types_eth::{Block, FeeHistory, Header};
+
+    fn block(excess_blob_gas: Option<u64>) -> Block {
+        Block {
+            header: Header::new(alloy_consensus::Header {
+                number: 10,
+                timestamp: 120,
+                base_fee_per_gas: Some(7),
+                excess_blob_gas,
+                blob_gas_used: excess_blob_gas.map(|_| 0),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn forecast_from_latest_block() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let forecaster = BlobFeeForecaster::new(BlobScheduleBlobParams::mainnet());
+
+        asserter.push_success(&block(Some(30_000_000)));
+        let forecast =
+            provider.forecast_blob_fees(&forecaster, BlobGasUsage::Max, 4).await.unwrap();
+        let head = BlobGasState {
+            timestamp: 120,
+            excess_blob_gas: 30_000_000,
+            blob_gas_used: 0,
+            base_fee_per_gas: 7,
+        };
+        assert_eq!(forecast, forecaster.forecast(head, BlobGasUsage::Max, 4));
+        assert_eq!(forecast[0].timestamp, 132);
+
+        asserter.push_success(&block(None));
+        let err = provider.forecast_blob_fees(&forecaster, BlobGasUsage::Max, 4).await.unwrap_err();
+        assert!(matches!(err, BlobFeeError::MissingBlobGas(BlockNumberOrTag::Number(10))));
+        assert!(asserter.read_q().is_empty());
+    }
+
+    #[tokio::test]
+    async fn usage_from_fee_history() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+        asserter.push_success(&FeeHistory {
+            blob_gas_used_ratio: vec![0.25, 0.5, 0.75],
+            ..Default::default()
+        });
+        let usage = provider.get_blob_gas_usage(3).await.unwrap();
+        assert_eq!(usage, BlobGasUsage::Ratio(0.5));
+    }
+
+    #[tokio::test]
+    async fn wait_for_blob_fee() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let policy = BlobFeePolicy::new(10).with_poll_interval(Duration::from_millis(1));
+
+        asserter.push_success(&U128::from(30));
+        asserter.push_success(&U128::from(20));
+        asserter.push_success(&U128::from(9));
+        assert_eq!(provider.wait_for_blob_fee(policy).await.unwrap(), 9);
+        assert!(asserter.read_q().is_empty());
+
+        asserter.push_success(&U128::from(30));
+        let err = provider
+            .wait_for_blob_fee(policy.with_timeout(Some(Duration::ZERO)))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BlobFeeError::Timeout { max_blob_fee: 10, blob_fee: 30 }));
+    }
+}
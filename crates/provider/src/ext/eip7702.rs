@@ -0,0 +1,321 @@
+//! This module extends the Ethereum JSON-RPC provider with helpers for managing EIP-7702
+//! delegations.
+
+use crate::{PendingTransactionBuilder, Provider};
+use alloy_eips::eip7702::{delegation_address, Authorization, SignedAuthorization};
+use alloy_network::{Network, TransactionBuilder, TransactionBuilder7702};
+use alloy_primitives::{Address, Signature, U256};
+use alloy_signer::Signer;
+use alloy_transport::{TransportError, TransportResult};
+
+/// Describes who pays for an EIP-7702 delegation transaction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Sponsor {
+    /// The authority sends the transaction itself.
+    ///
+    /// The sender nonce is incremented before the authorization list is processed, so the
+    /// authorization must be signed with the nonce following the transaction nonce.
+    #[default]
+    SelfSponsored,
+    /// The transaction is sent by the given account, which pays for the gas.
+    ///
+    /// The provider must be able to sign transactions for this account.
+    Sponsored(Address),
+}
+
+impl Sponsor {
+    /// Returns `true` if the authority sends the transaction itself.
+    pub const fn is_self_sponsored(&self) -> bool {
+        matches!(self, Self::SelfSponsored)
+    }
+}
+
+/// Error returned by [`DelegationApi`] methods.
+#[derive(Debug, thiserror::Error)]
+pub enum DelegationError {
+    /// Signing the authorization failed.
+    #[error(transparent)]
+    Signer(#[from] alloy_signer::Error),
+    /// A request to the node failed.
+    #[error(transparent)]
+    Transport(#[from] TransportError),
+}
+
+/// Helpers for managing [EIP-7702] delegations of externally owned accounts.
+///
+/// [EIP-7702]: https://eips.ethereum.org/EIPS/eip-7702
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+pub trait DelegationApi<N: Network>: Send + Sync {
+    /// Returns the address the account currently delegates to, or `None` if the account has no
+    /// delegation designator as its code.
+    async fn get_delegation(&self, account: Address) -> TransportResult<Option<Address>>;
+
+    /// Signs an authorization that delegates the signer's account to `delegate`.
+    ///
+    /// The chain ID and the authority's nonce are fetched from the node, and the nonce is adjusted
+    /// for the given [`Sponsor`].
+    async fn sign_authorization<S>(
+        &self,
+        signer: &S,
+        delegate: Address,
+        sponsor: Sponsor,
+    ) -> Result<SignedAuthorization, DelegationError>
+    where
+        S: Signer<Signature> + Send + Sync + ?Sized;
+
+    /// Signs an authorization delegating the signer's account to `delegate` and sends it with the
+    /// given transaction request.
+    ///
+    /// The sender of the request is set according to the [`Sponsor`]. If the request has no
+    /// recipient, the transaction is sent to the authority itself.
+    async fn delegate<S>(
+        &self,
+        signer: &S,
+        delegate: Address,
+        sponsor: Sponsor,
+        request: N::TransactionRequest,
+    ) -> Result<PendingTransactionBuilder<N>, DelegationError>
+    where
+        S: Signer<Signature> + Send + Sync + ?Sized;
+
+    /// Clears the delegation of the signer's account by delegating to the zero address.
+    async fn revoke_delegation<S>(
+        &self,
+        signer: &S,
+        sponsor: Sponsor,
+    ) -> Result<PendingTransactionBuilder<N>, DelegationError>
+    where
+        S: Signer<Signature> + Send + Sync + ?Sized,
+    {
+        self.delegate(signer, Address::ZERO, sponsor, Default::default()).await
+    }
+}
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl<N, P> DelegationApi<N> for P
+where
+    N: Network,
+    N::TransactionRequest: TransactionBuilder7702,
+    P: Provider<N>,
+{
+    async fn get_delegation(&self, account: Address) -> TransportResult<Option<Address>> {
+        let code = self.get_code_at(account).await?;
+        Ok(delegation_address(&code))
+    }
+
+    async fn sign_authorization<S>(
+        &self,
+        signer: &S,
+        delegate: Address,
+        sponsor: Sponsor,
+    ) -> Result<SignedAuthorization, DelegationError>
+    where
+        S: Signer<Signature> + Send + Sync + ?Sized,
+    {
+        let nonce = self.get_transaction_count(signer.address()).pending().await?;
+        let nonce = if sponsor.is_self_sponsored() { nonce + 1 } else { nonce };
+        sign_authorization(self, signer, delegate, nonce).await
+    }
+
+    async fn delegate<S>(
+        &self,
+        signer: &S,
+        delegate: Address,
+        sponsor: Sponsor,
+        mut request: N::TransactionRequest,
+    ) -> Result<PendingTransactionBuilder<N>, DelegationError>
+    where
+        S: Signer<Signature> + Send + Sync + ?Sized,
+    {
+        let authority = signer.address();
+        if request.to().is_none() {
+            request.set_to(authority);
+        }
+
+        let nonce = match sponsor {
+            Sponsor::SelfSponsored => {
+                request.set_from(authority);
+                // The transaction nonce is consumed before the authorization is applied.
+                let tx_nonce = match request.nonce() {
+                    Some(nonce) => nonce,
+                    None => self.get_transaction_count(authority).pending().await?,
+                };
+                request.set_nonce(tx_nonce);
+                tx_nonce + 1
+            }
+            Sponsor::Sponsored(sponsor) => {
+                request.set_from(sponsor);
+                self.get_transaction_count(authority).pending().await?
+            }
+        };
+
+        let authorization = sign_authorization(self, signer, delegate, nonce).await?;
+        let mut authorization_list = request.authorization_list().cloned().unwrap_or_default();
+        authorization_list.push(authorization);
+        request.set_authorization_list(authorization_list);
+
+        Ok(self.send_transaction(request).await?)
+    }
+}
+
+async fn sign_authorization<N, P, S>(
+    provider: &P,
+    signer: &S,
+    delegate: Address,
+    nonce: u64,
+) -> Result<SignedAuthorization, DelegationError>
+where
+    N: Network,
+    P: Provider<N>,
+    S: Signer<Signature> + Send + Sync + ?Sized,
+{
+    let chain_id = match signer.chain_id() {
+        Some(chain_id) => chain_id,
+        None => provider.get_chain_id().await?,
+    };
+    let authorization = Authorization { chain_id: U256::from(chain_id), address: delegate, nonce };
+    let signature = signer.sign_hash(&authorization.signature_hash()).await?;
+    Ok(authorization.into_signed(signature))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mock::Asserter, ProviderBuilder, RootProvider};
+    use alloy_eips::eip7702::constants::EIP7702_DELEGATION_DESIGNATOR;
+    use alloy_json_rpc::RequestPacket;
+    use alloy_network::Ethereum;
+    use alloy_primitives::{Bytes, TxHash, U64};
+    use alloy_rpc_client::RpcClient;
+    use alloy_rpc_types_eth::TransactionRequest;
+    use alloy_signer_local::PrivateKeySigner;
+    use alloy_transport::{mock::MockTransport, TransportFut};
+    use std::sync::{Arc, Mutex};
+    use tower::Service;
+
+    type Requests = Arc<Mutex<Vec<(String, serde_json::Value)>>>;
+
+    /// Returns a provider answering from the asserter, and the methods and params of the requests
+    /// it sent.
+    fn recording_provider(asserter: &Asserter) -> (RootProvider<Ethereum>, Requests) {
+        let requests = Requests::default();
+        let recorded = requests.clone();
+        let mock = MockTransport::new(asserter.clone());
+        let transport = tower::service_fn(move |req: RequestPacket| -> TransportFut<'static> {
+            for req in req.requests() {
+                let params = serde_json::from_str(req.params().map_or("null", |p| p.get()));
+                recorded.lock().unwrap().push((req.method().to_string(), params.unwrap()));
+            }
+            mock.clone().call(req)
+        });
+        (RootProvider::new(RpcClient::new(transport, true)), requests)
+    }
+
+    /// Returns the last transaction request sent with `eth_sendTransaction`.
+    fn sent_transaction(requests: &Requests) -> TransactionRequest {
+        let requests = requests.lock().unwrap();
+        let (method, params) = requests.last().unwrap();
+        assert_eq!(method, "eth_sendTransaction");
+        serde_json::from_value(params[0].clone()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn get_delegation() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+        let delegate = Address::random();
+        let code = [EIP7702_DELEGATION_DESIGNATOR.as_slice(), delegate.as_slice()].concat();
+        asserter.push_success(&Bytes::from(code));
+        asserter.push_success(&Bytes::new());
+
+        assert_eq!(provider.get_delegation(Address::random()).await.unwrap(), Some(delegate));
+        assert_eq!(provider.get_delegation(Address::random()).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn sign_authorization_nonce() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let signer = PrivateKeySigner::random();
+        let delegate = Address::random();
+
+        asserter.push_success(&U64::from(7));
+        asserter.push_success(&U64::from(1));
+        let auth = provider.sign_authorization(&signer, delegate, Sponsor::SelfSponsored).await;
+        let auth = auth.unwrap();
+        assert_eq!(auth.nonce, 8);
+        assert_eq!(auth.chain_id, U256::from(1));
+        assert_eq!(auth.address, delegate);
+        assert_eq!(auth.recover_authority().unwrap(), signer.address());
+
+        let signer = signer.with_chain_id(Some(10));
+        asserter.push_success(&U64::from(7));
+        let auth = provider
+            .sign_authorization(&signer, delegate, Sponsor::Sponsored(Address::random()))
+            .await
+            .unwrap();
+        assert_eq!(auth.nonce, 7);
+        assert_eq!(auth.chain_id, U256::from(10));
+        assert!(asserter.read_q().is_empty());
+    }
+
+    #[tokio::test]
+    async fn delegate_self_sponsored() {
+        let asserter = Asserter::new();
+        let (provider, requests) = recording_provider(&asserter);
+        let signer = PrivateKeySigner::random();
+        let delegate = Address::random();
+
+        asserter.push_success(&U64::from(5));
+        asserter.push_success(&U64::from(1));
+        asserter.push_success(&TxHash::with_last_byte(1));
+        let pending = provider
+            .delegate(&signer, delegate, Sponsor::SelfSponsored, Default::default())
+            .await
+            .unwrap();
+        assert_eq!(*pending.tx_hash(), TxHash::with_last_byte(1));
+        assert!(asserter.read_q().is_empty());
+
+        let tx = sent_transaction(&requests);
+        assert_eq!(tx.from, Some(signer.address()));
+        assert_eq!(tx.to, Some(signer.address().into()));
+        assert_eq!(tx.nonce, Some(5));
+        let authorization_list = tx.authorization_list.unwrap();
+        assert_eq!(authorization_list.len(), 1);
+        let authorization = &authorization_list[0];
+        assert_eq!(authorization.address, delegate);
+        assert_eq!(authorization.nonce, 6);
+        assert_eq!(authorization.chain_id, U256::from(1));
+        assert_eq!(authorization.recover_authority().unwrap(), signer.address());
+    }
+
+    #[tokio::test]
+    async fn revoke_sponsored_delegation() {
+        let asserter = Asserter::new();
+        let (provider, requests) = recording_provider(&asserter);
+        let signer = PrivateKeySigner::random().with_chain_id(Some(10));
+        let sponsor = Address::random();
+
+        asserter.push_success(&U64::from(3));
+        asserter.push_success(&TxHash::with_last_byte(2));
+        let pending =
+            provider.revoke_delegation(&signer, Sponsor::Sponsored(sponsor)).await.unwrap();
+        assert_eq!(*pending.tx_hash(), TxHash::with_last_byte(2));
+        assert!(asserter.read_q().is_empty());
+
+        let tx = sent_transaction(&requests);
+        assert_eq!(tx.from, Some(sponsor));
+        assert_eq!(tx.to, Some(signer.address().into()));
+        assert_eq!(tx.nonce, None);
+        let authorization_list = tx.authorization_list.unwrap();
+        assert_eq!(authorization_list.len(), 1);
+        let authorization = &authorization_list[0];
+        assert_eq!(authorization.address, Address::ZERO);
+        assert_eq!(authorization.nonce, 3);
+        assert_eq!(authorization.chain_id, U256::from(10));
+        assert_eq!(authorization.recover_authority().unwrap(), signer.address());
+    }
+}
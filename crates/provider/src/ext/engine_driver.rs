@@ -0,0 +1,471 @@
+//! A minimal consensus layer that drives an execution client through the engine API, e.g. to run
+//! devnets without a beacon node.
+use crate::{ext::EngineApi, Provider};
+use alloy_consensus::BlockHeader;
+use alloy_eips::{eip7685::RequestsOrHash, BlockNumHash, BlockNumberOrTag};
+use alloy_genesis::{ChainSpec, Hardfork};
+use alloy_network::{Ethereum, Network};
+use alloy_network_primitives::{BlockResponse, HeaderResponse};
+use alloy_primitives::{Address, B256};
+use alloy_rpc_types_engine::{
+    EngineApiMessageVersion, ExecutionPayload, ExecutionPayloadInputV2, ForkchoiceState,
+    ForkchoiceUpdateVersion, ForkchoiceUpdated, PayloadAttributes, PayloadId, PayloadStatus,
+    PayloadStatusEnum,
+};
+use alloy_transport::TransportError;
+use std::{marker::PhantomData, time::Duration};
+
+#[cfg(all(target_family = "wasm", target_os = "unknown"))]
+use wasmtimer::{std::Instant, tokio::sleep};
+
+#[cfg(not(all(target_family = "wasm", target_os = "unknown")))]
+use {std::time::Instant, tokio::time::sleep};
+
+/// Errors that may occur when driving an execution client through the engine API.
+#[derive(Debug, thiserror::Error)]
+pub enum EngineDriverError {
+    /// Underlying transport error.
+    #[error(transparent)]
+    Transport(#[from] TransportError),
+    /// The next block is not past the merge, so it can't be built through the engine API.
+    #[error("the engine API is unavailable before Paris, the next block is in {0}")]
+    PreMerge(Hardfork),
+    /// The execution client did not accept a payload or fork choice update as valid.
+    #[error("execution client returned status {0}")]
+    InvalidStatus(PayloadStatusEnum),
+    /// The execution client did not start building a payload.
+    #[error("execution client did not return a payload id")]
+    MissingPayloadId,
+    /// The latest block of the execution client does not exist.
+    #[error("latest block not found")]
+    BlockNotFound,
+}
+
+/// Drives an execution client through the engine API, acting as a minimal consensus layer.
+///
+/// Every call to [`advance`](Self::advance) builds one block on top of the current head: it
+/// starts a payload build with `engine_forkchoiceUpdated`, fetches the payload with
+/// `engine_getPayload`, imports it with `engine_newPayload` and makes it the new head, safe and
+/// finalized block. The method versions are selected from the fork that is active for the new
+/// block according to the [`ChainSpec`].
+///
+/// The provider must be authenticated for the engine API, e.g. with a JWT secret.
+///
+/// ```ignore
+/// let mut driver = EngineDriver::new(provider, ChainSpec::from_genesis(&genesis))
+///     .with_slot_time(Duration::from_secs(2));
+/// driver.run(Some(10)).await?;
+/// ```
+#[derive(Debug)]
+pub struct EngineDriver<P, N = Ethereum> {
+    provider: P,
+    chain_spec: ChainSpec,
+    head: BlockNumHash,
+    head_timestamp: u64,
+    fee_recipient: Address,
+    slot_time: Duration,
+    build_time: Duration,
+    _network: PhantomData<N>,
+}
+
+impl<P, N> EngineDriver<P, N>
+where
+    N: Network,
+    P: Provider<N>,
+{
+    /// Creates a new driver that builds on top of the genesis block of the chain spec.
+    ///
+    /// Use [`with_head`](Self::with_head) or [`sync_to_latest`](Self::sync_to_latest) if the
+    /// execution client already has blocks.
+    pub const fn new(provider: P, chain_spec: ChainSpec) -> Self {
+        let head = BlockNumHash::new(0, chain_spec.genesis_hash());
+        let head_timestamp = chain_spec.genesis_timestamp();
+        Self {
+            provider,
+            chain_spec,
+            head,
+            head_timestamp,
+            fee_recipient: Address::ZERO,
+            slot_time: Duration::from_secs(12),
+            build_time: Duration::from_millis(500),
+            _network: PhantomData,
+        }
+    }
+
+    /// Sets the block to build on top of.
+    pub const fn with_head(mut self, head: BlockNumHash, timestamp: u64) -> Self {
+        self.head = head;
+        self.head_timestamp = timestamp;
+        self
+    }
+
+    /// Sets the fee recipient of the built blocks.
+    ///
+    /// Defaults to the zero address.
+    pub const fn with_fee_recipient(mut self, fee_recipient: Address) -> Self {
+        self.fee_recipient = fee_recipient;
+        self
+    }
+
+    /// Sets the time between two blocks.
+    ///
+    /// Block timestamps advance by the whole seconds of the slot time, but at least by one second.
+    /// Defaults to 12 seconds.
+    pub const fn with_slot_time(mut self, slot_time: Duration) -> Self {
+        self.slot_time = slot_time;
+        self
+    }
+
+    /// Sets the time the execution client is given to build a payload before it is fetched.
+    ///
+    /// Defaults to 500 milliseconds.
+    pub const fn with_build_time(mut self, build_time: Duration) -> Self {
+        self.build_time = build_time;
+        self
+    }
+
+    /// Returns the provider.
+    pub const fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Returns the chain spec.
+    pub const fn chain_spec(&self) -> &ChainSpec {
+        &self.chain_spec
+    }
+
+    /// Returns the current head.
+    pub const fn head(&self) -> BlockNumHash {
+        self.head
+    }
+
+    /// Returns the timestamp of the current head.
+    pub const fn head_timestamp(&self) -> u64 {
+        self.head_timestamp
+    }
+
+    /// Sets the head to the latest block of the execution client.
+    pub async fn sync_to_latest(&mut self) -> Result<BlockNumHash, EngineDriverError> {
+        let block = self
+            .provider
+            .get_block_by_number(BlockNumberOrTag::Latest)
+            .await?
+            .ok_or(EngineDriverError::BlockNotFound)?;
+        let header = block.header();
+        self.head = BlockNumHash::new(header.number(), header.hash());
+        self.head_timestamp = header.timestamp();
+        Ok(self.head)
+    }
+
+    /// Returns the engine API message version for a block with the given number and timestamp.
+    pub fn message_version(
+        &self,
+        number: u64,
+        timestamp: u64,
+    ) -> Result<EngineApiMessageVersion, EngineDriverError> {
+        let version = match self.chain_spec.active_fork(number, timestamp) {
+            Hardfork::Paris => EngineApiMessageVersion::V1,
+            Hardfork::Shanghai => EngineApiMessageVersion::V2,
+            Hardfork::Cancun => EngineApiMessageVersion::V3,
+            Hardfork::Prague => EngineApiMessageVersion::V4,
+            Hardfork::Osaka
+            | Hardfork::Bpo1
+            | Hardfork::Bpo2
+            | Hardfork::Bpo3
+            | Hardfork::Bpo4
+            | Hardfork::Bpo5 => EngineApiMessageVersion::V5,
+            Hardfork::Amsterdam => EngineApiMessageVersion::V6,
+            fork => return Err(EngineDriverError::PreMerge(fork)),
+        };
+        Ok(version)
+    }
+
+    /// Returns the payload attributes for the block on top of the current head.
+    ///
+    /// The parent hash is used as `prev_randao` and as parent beacon block root, and no
+    /// withdrawals are included.
+    pub fn next_payload_attributes(&self) -> Result<PayloadAttributes, EngineDriverError> {
+        let timestamp = self.head_timestamp + self.slot_time.as_secs().max(1);
+        let version = self.message_version(self.head.number + 1, timestamp)?;
+        let slot_number = (version >= EngineApiMessageVersion::V6).then(|| {
+            (timestamp - self.chain_spec.genesis_timestamp()) / self.slot_time.as_secs().max(1)
+        });
+
+        Ok(PayloadAttributes {
+            timestamp,
+            prev_randao: self.head.hash,
+            suggested_fee_recipient: self.fee_recipient,
+            withdrawals: (version >= EngineApiMessageVersion::V2).then(Vec::new),
+            parent_beacon_block_root: (version >= EngineApiMessageVersion::V3)
+                .then_some(self.head.hash),
+            slot_number,
+        })
+    }
+
+    /// Builds a block on top of the current head, imports it and makes it the new head.
+    ///
+    /// Returns the payload of the new block.
+    pub async fn advance(&mut self) -> Result<ExecutionPayload, EngineDriverError> {
+        let attributes = self.next_payload_attributes()?;
+        let version = self.message_version(self.head.number + 1, attributes.timestamp)?;
+        let parent_beacon_block_root = attributes.parent_beacon_block_root.unwrap_or_default();
+
+        let updated = self
+            .fork_choice_updated(
+                version,
+                ForkchoiceState::same_hash(self.head.hash),
+                Some(attributes),
+            )
+            .await?;
+        let payload_id = updated.payload_id.ok_or(EngineDriverError::MissingPayloadId)?;
+
+        sleep(self.build_time).await;
+        let (payload, status) =
+            self.get_and_import_payload(version, payload_id, parent_beacon_block_root).await?;
+        ensure_valid(status)?;
+
+        let hash = payload.block_hash();
+        self.fork_choice_updated(version, ForkchoiceState::same_hash(hash), None).await?;
+        trace!(number = payload.block_number(), %hash, "advanced head");
+
+        self.head = BlockNumHash::new(payload.block_number(), hash);
+        self.head_timestamp = payload.timestamp();
+        Ok(payload)
+    }
+
+    /// Advances the head once per slot, until `blocks` blocks are built, or indefinitely if
+    /// `blocks` is `None`.
+    pub async fn run(&mut self, blocks: Option<u64>) -> Result<(), EngineDriverError> {
+        let mut built = 0;
+        while blocks.is_none_or(|blocks| built < blocks) {
+            let start = Instant::now();
+            self.advance().await?;
+            built += 1;
+            if blocks.is_none_or(|blocks| built < blocks) {
+                sleep(self.slot_time.saturating_sub(start.elapsed())).await;
+            }
+        }
+        Ok(())
+    }
+
+    async fn fork_choice_updated(
+        &self,
+        version: EngineApiMessageVersion,
+        state: ForkchoiceState,
+        attributes: Option<PayloadAttributes>,
+    ) -> Result<ForkchoiceUpdated, EngineDriverError> {
+        let updated = match version.forkchoice_updated_version() {
+            ForkchoiceUpdateVersion::V1 => {
+                self.provider.fork_choice_updated_v1(state, attributes).await?
+            }
+            ForkchoiceUpdateVersion::V2 => {
+                self.provider.fork_choice_updated_v2(state, attributes).await?
+            }
+            ForkchoiceUpdateVersion::V3 => {
+                self.provider.fork_choice_updated_v3(state, attributes).await?
+            }
+            ForkchoiceUpdateVersion::V4 => {
+                self.provider.fork_choice_updated_v4(state, attributes).await?
+            }
+        };
+        ensure_valid(updated.payload_status.clone())?;
+        Ok(updated)
+    }
+
+    async fn get_and_import_payload(
+        &self,
+        version: EngineApiMessageVersion,
+        payload_id: PayloadId,
+        parent_beacon_block_root: B256,
+    ) -> Result<(ExecutionPayload, PayloadStatus), EngineDriverError> {
+        let provider = &self.provider;
+        let imported = match version {
+            EngineApiMessageVersion::V1 => {
+                let payload = provider.get_payload_v1(payload_id).await?;
+                let status = provider.new_payload_v1(payload.clone()).await?;
+                (ExecutionPayload::V1(payload), status)
+            }
+            EngineApiMessageVersion::V2 => {
+                let payload = provider.get_payload_v2(payload_id).await?.execution_payload;
+                let payload = payload.into_payload();
+                let input = ExecutionPayloadInputV2 {
+                    execution_payload: payload.as_v1().clone(),
+                    withdrawals: payload.withdrawals().cloned(),
+                };
+                (payload, provider.new_payload_v2(input).await?)
+            }
+            EngineApiMessageVersion::V3 => {
+                let envelope = provider.get_payload_v3(payload_id).await?;
+                let status = provider
+                    .new_payload_v3(
+                        envelope.execution_payload.clone(),
+                        envelope.blobs_bundle.versioned_hashes(),
+                        parent_beacon_block_root,
+                    )
+                    .await?;
+                (ExecutionPayload::V3(envelope.execution_payload), status)
+            }
+            EngineApiMessageVersion::V4 => {
+                let envelope = provider.get_payload_v4(payload_id).await?;
+                let inner = envelope.envelope_inner;
+                let status = provider
+                    .new_payload_v4(
+                        inner.execution_payload.clone(),
+                        inner.blobs_bundle.versioned_hashes(),
+                        parent_beacon_block_root,
+                        envelope.execution_requests.take(),
+                    )
+                    .await?;
+                (ExecutionPayload::V3(inner.execution_payload), status)
+            }
+            EngineApiMessageVersion::V5 => {
+                let envelope = provider.get_payload_v5(payload_id).await?;
+                let status = provider
+                    .new_payload_v4(
+                        envelope.execution_payload.clone(),
+                        envelope.blobs_bundle.versioned_hashes(),
+                        parent_beacon_block_root,
+                        envelope.execution_requests.take(),
+                    )
+                    .await?;
+                (ExecutionPayload::V3(envelope.execution_payload), status)
+            }
+            EngineApiMessageVersion::V6 => {
+                let envelope = provider.get_payload_v6(payload_id).await?;
+                let status = provider
+                    .new_payload_v5(
+                        envelope.execution_payload.clone(),
+                        envelope.blobs_bundle.versioned_hashes(),
+                        parent_beacon_block_root,
+                        RequestsOrHash::Requests(envelope.execution_requests),
+                    )
+                    .await?;
+                (ExecutionPayload::V4(envelope.execution_payload), status)
+            }
+        };
+        Ok(imported)
+    }
+}
+
+fn ensure_valid(status: PayloadStatus) -> Result<(), EngineDriverError> {
+    match status.status {
+        PayloadStatusEnum::Valid => Ok(()),
+        status => Err(EngineDriverError::InvalidStatus(status)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mock::Asserter, ProviderBuilder};
+    use alloy_genesis::{ChainConfig, ForkCondition, Genesis};
+    use alloy_primitives::{b256, Bloom, Bytes, U256};
+    use alloy_rpc_types_engine::{
+        BlobsBundleV1, ExecutionPayloadEnvelopeV3, ExecutionPayloadV1, ExecutionPayloadV2,
+        ExecutionPayloadV3,
+    };
+
+    fn cancun_spec() -> ChainSpec {
+        let genesis = Genesis {
+            config: ChainConfig {
+                chain_id: 1337,
+                london_block: Some(0),
+                terminal_total_difficulty: Some(U256::ZERO),
+                shanghai_time: Some(0),
+                cancun_time: Some(0),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        ChainSpec::from_genesis(&genesis)
+    }
+
+    fn payload(parent_hash: B256, block_hash: B256) -> ExecutionPayloadV3 {
+        ExecutionPayloadV3 {
+            payload_inner: ExecutionPayloadV2 {
+                payload_inner: ExecutionPayloadV1 {
+                    parent_hash,
+                    fee_recipient: Address::ZERO,
+                    state_root: B256::ZERO,
+                    receipts_root: B256::ZERO,
+                    logs_bloom: Bloom::ZERO,
+                    prev_randao: parent_hash,
+                    block_number: 1,
+                    gas_limit: 30_000_000,
+                    gas_used: 0,
+                    timestamp: 2,
+                    extra_data: Bytes::new(),
+                    base_fee_per_gas: U256::from(7),
+                    block_hash,
+                    transactions: Vec::new(),
+                },
+                withdrawals: Vec::new(),
+            },
+            blob_gas_used: 0,
+            excess_blob_gas: 0,
+        }
+    }
+
+    #[test]
+    fn message_version_follows_forks() {
+        let spec = cancun_spec().with_fork(Hardfork::Prague, ForkCondition::Timestamp(100));
+        let driver = EngineDriver::<_, Ethereum>::new(
+            ProviderBuilder::new().connect_mocked_client(Asserter::new()),
+            spec,
+        )
+        .with_slot_time(Duration::from_secs(2));
+
+        assert_eq!(driver.message_version(1, 99).unwrap(), EngineApiMessageVersion::V3);
+        assert_eq!(driver.message_version(1, 100).unwrap(), EngineApiMessageVersion::V4);
+
+        let attributes = driver.next_payload_attributes().unwrap();
+        assert_eq!(attributes.timestamp, 2);
+        assert_eq!(attributes.withdrawals, Some(Vec::new()));
+        assert_eq!(attributes.parent_beacon_block_root, Some(driver.head().hash));
+        assert_eq!(attributes.slot_number, None);
+
+        let pre_merge = EngineDriver::<_, Ethereum>::new(
+            ProviderBuilder::new().connect_mocked_client(Asserter::new()),
+            ChainSpec::from_genesis(&Genesis::default()),
+        );
+        assert!(matches!(
+            pre_merge.message_version(1, 0),
+            Err(EngineDriverError::PreMerge(Hardfork::Frontier))
+        ));
+    }
+
+    #[tokio::test]
+    async fn advance_head() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let mut driver = EngineDriver::new(provider, cancun_spec())
+            .with_slot_time(Duration::from_secs(2))
+            .with_build_time(Duration::ZERO);
+        let parent = driver.head().hash;
+        let block_hash =
+            b256!("0x0101010101010101010101010101010101010101010101010101010101010101");
+
+        let payload_id = PayloadId::new([1; 8]);
+        let valid = PayloadStatus::from_status(PayloadStatusEnum::Valid);
+        asserter.push_success(&ForkchoiceUpdated::new(valid.clone()).with_payload_id(payload_id));
+        asserter.push_success(&ExecutionPayloadEnvelopeV3 {
+            execution_payload: payload(parent, block_hash),
+            block_value: U256::ZERO,
+            blobs_bundle: BlobsBundleV1::default(),
+            should_override_builder: false,
+        });
+        asserter.push_success(&valid);
+        asserter.push_success(&ForkchoiceUpdated::new(valid));
+
+        let built = driver.advance().await.unwrap();
+        assert_eq!(built.block_hash(), block_hash);
+        assert_eq!(driver.head(), BlockNumHash::new(1, block_hash));
+        assert_eq!(driver.head_timestamp(), 2);
+        assert!(asserter.read_q().is_empty());
+
+        let invalid = PayloadStatus::from_status(PayloadStatusEnum::Syncing);
+        asserter.push_success(&ForkchoiceUpdated::new(invalid));
+        let err = driver.run(Some(1)).await.unwrap_err();
+        assert!(matches!(err, EngineDriverError::InvalidStatus(PayloadStatusEnum::Syncing)));
+    }
+}
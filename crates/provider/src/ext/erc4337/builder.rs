@@ -0,0 +1,413 @@
+use super::{Erc4337Api, PendingUserOperation, UserOperationError};
+use crate::Provider;
+use alloy_network::{Network, TransactionBuilder};
+use alloy_primitives::{aliases::U192, Address, Bytes, Signature, U256};
+use alloy_rpc_types_eth::erc4337::{
+    PackedUserOperation, SendUserOperation, UserOperation, ENTRY_POINT_V06_ADDRESS,
+    ENTRY_POINT_V07_ADDRESS,
+};
+use alloy_signer::Signer;
+use alloy_sol_types::{sol, SolCall};
+use alloy_transport::TransportResult;
+use std::{fmt, marker::PhantomData, sync::Arc};
+
+sol! {
+    /// The nonce query of the ERC-4337 `EntryPoint` contract.
+    interface IEntryPoint {
+        function getNonce(address sender, uint192 key) external view returns (uint256 nonce);
+    }
+}
+
+/// The version of the ERC-4337 `EntryPoint` contract a user operation targets.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum EntryPointVersion {
+    /// `EntryPoint` v0.6, which uses [`UserOperation`].
+    V06,
+    /// `EntryPoint` v0.7, which uses [`PackedUserOperation`].
+    #[default]
+    V07,
+}
+
+impl EntryPointVersion {
+    /// Returns the canonical address of the `EntryPoint` contract.
+    pub const fn address(&self) -> Address {
+        match self {
+            Self::V06 => ENTRY_POINT_V06_ADDRESS,
+            Self::V07 => ENTRY_POINT_V07_ADDRESS,
+        }
+    }
+}
+
+/// A hook that fills in the paymaster fields of a user operation.
+///
+/// The hook is invoked twice while building a user operation, following [ERC-7677]:
+/// [`stub_data`](Self::stub_data) is called before gas estimation and should set placeholder
+/// paymaster fields of realistic size, and [`paymaster_data`](Self::paymaster_data) is called
+/// after gas estimation and should set the final fields.
+///
+/// [ERC-7677]: https://eips.ethereum.org/EIPS/eip-7677
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+pub trait PaymasterHook: Send + Sync {
+    /// Sets the paymaster fields used for gas estimation.
+    ///
+    /// By default this delegates to [`paymaster_data`](Self::paymaster_data).
+    async fn stub_data(
+        &self,
+        user_op: &mut SendUserOperation,
+        entry_point: Address,
+        chain_id: u64,
+    ) -> TransportResult<()> {
+        self.paymaster_data(user_op, entry_point, chain_id).await
+    }
+
+    /// Sets the final paymaster fields of the user operation.
+    async fn paymaster_data(
+        &self,
+        user_op: &mut SendUserOperation,
+        entry_point: Address,
+        chain_id: u64,
+    ) -> TransportResult<()>;
+}
+
+/// A builder for ERC-4337 user operations.
+///
+/// Any field that is not set explicitly is filled in by [`build`](Self::build):
+/// - the chain ID is fetched from the provider,
+/// - the nonce is fetched from the `EntryPoint` contract for the configured nonce key,
+/// - the fees are estimated with [`Provider::estimate_eip1559_fees`],
+/// - the gas limits are estimated with `eth_estimateUserOperationGas`.
+///
+/// The provider is expected to be connected to a bundler that also serves the `eth` namespace.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn example<P: alloy_provider::Provider>(bundler: P, signer: alloy_signer_local::PrivateKeySigner) -> Result<(), Box<dyn std::error::Error>> {
+/// use alloy_primitives::{address, bytes};
+/// use alloy_provider::ext::{EntryPointVersion, UserOperationBuilder};
+///
+/// let sender = address!("0x1111111111111111111111111111111111111111");
+/// let receipt = UserOperationBuilder::new(&bundler, sender)
+///     .entry_point_version(EntryPointVersion::V07)
+///     .call_data(bytes!("b61d27f6"))
+///     .dummy_signature(vec![0xff; 65].into())
+///     .send(&signer)
+///     .await?
+///     .get_receipt()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[must_use = "builders do nothing unless `build`, `sign` or `send` is called"]
+pub struct UserOperationBuilder<'a, P, N> {
+    provider: &'a P,
+    sender: Address,
+    entry_point: Address,
+    version: EntryPointVersion,
+    chain_id: Option<u64>,
+    nonce: Option<U256>,
+    nonce_key: U192,
+    factory: Option<Address>,
+    factory_data: Bytes,
+    call_data: Bytes,
+    call_gas_limit: Option<U256>,
+    verification_gas_limit: Option<U256>,
+    pre_verification_gas: Option<U256>,
+    max_fee_per_gas: Option<U256>,
+    max_priority_fee_per_gas: Option<U256>,
+    paymaster: Option<Arc<dyn PaymasterHook>>,
+    dummy_signature: Bytes,
+    _network: PhantomData<N>,
+}
+
+impl<P, N> fmt::Debug for UserOperationBuilder<'_, P, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UserOperationBuilder")
+            .field("sender", &self.sender)
+            .field("entry_point", &self.entry_point)
+            .field("version", &self.version)
+            .field("nonce", &self.nonce)
+            .field("nonce_key", &self.nonce_key)
+            .field("call_data", &self.call_data)
+            .field("paymaster", &self.paymaster.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, P, N> UserOperationBuilder<'a, P, N>
+where
+    P: Provider<N>,
+    N: Network,
+{
+    /// Creates a new builder for a user operation of the given smart account, targeting the
+    /// canonical `EntryPoint` v0.7.
+    pub const fn new(provider: &'a P, sender: Address) -> Self {
+        Self {
+            provider,
+            sender,
+            entry_point: ENTRY_POINT_V07_ADDRESS,
+            version: EntryPointVersion::V07,
+            chain_id: None,
+            nonce: None,
+            nonce_key: U192::ZERO,
+            factory: None,
+            factory_data: Bytes::new(),
+            call_data: Bytes::new(),
+            call_gas_limit: None,
+            verification_gas_limit: None,
+            pre_verification_gas: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            paymaster: None,
+            dummy_signature: Bytes::new(),
+            _network: PhantomData,
+        }
+    }
+
+    /// Targets the canonical `EntryPoint` contract of the given version.
+    pub const fn entry_point_version(mut self, version: EntryPointVersion) -> Self {
+        self.version = version;
+        self.entry_point = version.address();
+        self
+    }
+
+    /// Targets a custom deployment of an `EntryPoint` contract of the given version.
+    pub const fn entry_point(mut self, entry_point: Address, version: EntryPointVersion) -> Self {
+        self.version = version;
+        self.entry_point = entry_point;
+        self
+    }
+
+    /// Sets the chain ID used for the user operation hash.
+    pub const fn chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Sets the nonce of the user operation.
+    pub const fn nonce(mut self, nonce: U256) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    /// Sets the key of the nonce sequence that is queried if no nonce is set.
+    pub const fn nonce_key(mut self, key: U192) -> Self {
+        self.nonce_key = key;
+        self
+    }
+
+    /// Sets the factory and the factory data used to deploy the account.
+    pub fn factory(mut self, factory: Address, factory_data: Bytes) -> Self {
+        self.factory = Some(factory);
+        self.factory_data = factory_data;
+        self
+    }
+
+    /// Sets the data passed to the account for execution.
+    pub fn call_data(mut self, call_data: Bytes) -> Self {
+        self.call_data = call_data;
+        self
+    }
+
+    /// Sets the gas limit of the execution phase.
+    pub const fn call_gas_limit(mut self, gas: U256) -> Self {
+        self.call_gas_limit = Some(gas);
+        self
+    }
+
+    /// Sets the gas limit of the verification phase.
+    pub const fn verification_gas_limit(mut self, gas: U256) -> Self {
+        self.verification_gas_limit = Some(gas);
+        self
+    }
+
+    /// Sets the gas that compensates the bundler for the pre-verification work.
+    pub const fn pre_verification_gas(mut self, gas: U256) -> Self {
+        self.pre_verification_gas = Some(gas);
+        self
+    }
+
+    /// Sets the maximum fee per gas.
+    pub const fn max_fee_per_gas(mut self, fee: U256) -> Self {
+        self.max_fee_per_gas = Some(fee);
+        self
+    }
+
+    /// Sets the maximum priority fee per gas.
+    pub const fn max_priority_fee_per_gas(mut self, fee: U256) -> Self {
+        self.max_priority_fee_per_gas = Some(fee);
+        self
+    }
+
+    /// Sets the [`PaymasterHook`] that sponsors the user operation.
+    pub fn paymaster<H: PaymasterHook + 'static>(mut self, hook: H) -> Self {
+        self.paymaster = Some(Arc::new(hook));
+        self
+    }
+
+    /// Sets the signature used while estimating gas.
+    ///
+    /// Accounts usually require a signature of the right length that does not revert
+    /// validation, which depends on the account implementation.
+    pub fn dummy_signature(mut self, signature: Bytes) -> Self {
+        self.dummy_signature = signature;
+        self
+    }
+
+    /// Fills in all missing fields and returns the unsigned user operation, carrying the dummy
+    /// signature.
+    pub async fn build(&self) -> Result<SendUserOperation, UserOperationError> {
+        self.fill().await.map(|(user_op, _)| user_op)
+    }
+
+    /// Builds the user operation and signs its hash with the given signer.
+    ///
+    /// The signature is an [EIP-191] signature over the user operation hash, which is the scheme
+    /// expected by the reference `SimpleAccount` and most ECDSA-owned accounts. Accounts with a
+    /// different scheme should sign [`SendUserOperation::hash`] themselves.
+    ///
+    /// [EIP-191]: https://eips.ethereum.org/EIPS/eip-191
+    pub async fn sign<S>(&self, signer: &S) -> Result<SendUserOperation, UserOperationError>
+    where
+        S: Signer<Signature> + Send + Sync + ?Sized,
+    {
+        let (mut user_op, chain_id) = self.fill().await?;
+        let hash = user_op.hash(self.entry_point, chain_id);
+        let signature = signer.sign_message(hash.as_slice()).await?;
+        user_op.set_signature(signature.as_bytes().into());
+        Ok(user_op)
+    }
+
+    /// Builds, signs and sends the user operation to the bundler.
+    pub async fn send<S>(&self, signer: &S) -> Result<PendingUserOperation<N>, UserOperationError>
+    where
+        S: Signer<Signature> + Send + Sync + ?Sized,
+    {
+        let user_op = self.sign(signer).await?;
+        let response = self.provider.send_user_operation(user_op, self.entry_point).await?;
+        Ok(PendingUserOperation::new(self.provider.root().clone(), response.user_op_hash))
+    }
+
+    async fn fill(&self) -> Result<(SendUserOperation, u64), UserOperationError> {
+        let chain_id = self.resolve_chain_id().await?;
+        let nonce = match self.nonce {
+            Some(nonce) => nonce,
+            None => self.fetch_nonce().await?,
+        };
+        let (max_fee_per_gas, max_priority_fee_per_gas) =
+            match (self.max_fee_per_gas, self.max_priority_fee_per_gas) {
+                (Some(max_fee), Some(priority_fee)) => (max_fee, priority_fee),
+                (max_fee, priority_fee) => {
+                    let estimate = self.provider.estimate_eip1559_fees().await?;
+                    (
+                        max_fee.unwrap_or(U256::from(estimate.max_fee_per_gas)),
+                        priority_fee.unwrap_or(U256::from(estimate.max_priority_fee_per_gas)),
+                    )
+                }
+            };
+
+        let mut user_op = self.user_operation(nonce, max_fee_per_gas, max_priority_fee_per_gas);
+        if let Some(paymaster) = &self.paymaster {
+            paymaster.stub_data(&mut user_op, self.entry_point, chain_id).await?;
+        }
+
+        if self.call_gas_limit.is_none()
+            || self.verification_gas_limit.is_none()
+            || self.pre_verification_gas.is_none()
+        {
+            let estimate = self
+                .provider
+                .estimate_user_operation_gas(user_op.clone(), self.entry_point)
+                .await?;
+            let call_gas_limit = self.call_gas_limit.unwrap_or(estimate.call_gas_limit);
+            let verification_gas_limit =
+                self.verification_gas_limit.unwrap_or(estimate.verification_gas);
+            let pre_verification_gas =
+                self.pre_verification_gas.unwrap_or(estimate.pre_verification_gas);
+            match &mut user_op {
+                SendUserOperation::EntryPointV06(op) => {
+                    op.call_gas_limit = call_gas_limit;
+                    op.verification_gas_limit = verification_gas_limit;
+                    op.pre_verification_gas = pre_verification_gas;
+                }
+                SendUserOperation::EntryPointV07(op) => {
+                    op.call_gas_limit = call_gas_limit;
+                    op.verification_gas_limit = verification_gas_limit;
+                    op.pre_verification_gas = pre_verification_gas;
+                    if op.paymaster.is_some() {
+                        op.paymaster_verification_gas_limit =
+                            Some(estimate.paymaster_verification_gas);
+                    }
+                }
+            }
+        }
+
+        if let Some(paymaster) = &self.paymaster {
+            paymaster.paymaster_data(&mut user_op, self.entry_point, chain_id).await?;
+        }
+
+        Ok((user_op, chain_id))
+    }
+
+    async fn resolve_chain_id(&self) -> TransportResult<u64> {
+        match self.chain_id {
+            Some(chain_id) => Ok(chain_id),
+            None => self.provider.get_chain_id().await,
+        }
+    }
+
+    async fn fetch_nonce(&self) -> Result<U256, UserOperationError> {
+        let call = IEntryPoint::getNonceCall { sender: self.sender, key: self.nonce_key };
+        let request = N::TransactionRequest::default()
+            .with_to(self.entry_point)
+            .with_input(call.abi_encode());
+        let output = self.provider.call(request).await?;
+        Ok(IEntryPoint::getNonceCall::abi_decode_returns(&output)?)
+    }
+
+    fn user_operation(
+        &self,
+        nonce: U256,
+        max_fee_per_gas: U256,
+        max_priority_fee_per_gas: U256,
+    ) -> SendUserOperation {
+        match self.version {
+            EntryPointVersion::V06 => UserOperation {
+                sender: self.sender,
+                nonce,
+                init_code: self
+                    .factory
+                    .map(|factory| [factory.as_slice(), &self.factory_data].concat().into())
+                    .unwrap_or_default(),
+                call_data: self.call_data.clone(),
+                call_gas_limit: self.call_gas_limit.unwrap_or_default(),
+                verification_gas_limit: self.verification_gas_limit.unwrap_or_default(),
+                pre_verification_gas: self.pre_verification_gas.unwrap_or_default(),
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                paymaster_and_data: Bytes::new(),
+                signature: self.dummy_signature.clone(),
+            }
+            .into(),
+            EntryPointVersion::V07 => PackedUserOperation {
+                sender: self.sender,
+                nonce,
+                factory: self.factory,
+                factory_data: self.factory.map(|_| self.factory_data.clone()),
+                call_data: self.call_data.clone(),
+                call_gas_limit: self.call_gas_limit.unwrap_or_default(),
+                verification_gas_limit: self.verification_gas_limit.unwrap_or_default(),
+                pre_verification_gas: self.pre_verification_gas.unwrap_or_default(),
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                paymaster: None,
+                paymaster_verification_gas_limit: None,
+                paymaster_post_op_gas_limit: None,
+                paymaster_data: None,
+                signature: self.dummy_signature.clone(),
+            }
+            .into(),
+        }
+    }
+}
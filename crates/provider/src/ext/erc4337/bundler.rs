@@ -0,0 +1,319 @@
+use super::{builder::IEntryPoint, EntryPointVersion};
+use crate::RootProvider;
+use alloy_consensus::{Receipt, ReceiptEnvelope, ReceiptWithBloom};
+use alloy_eips::BlockNumberOrTag;
+use alloy_json_rpc::{ErrorPayload, RequestPacket, Response, ResponsePacket, ResponsePayload};
+use alloy_network::Network;
+use alloy_primitives::{
+    aliases::U192, keccak256, map::HashMap, Address, Bytes, B256, U128, U256, U64,
+};
+use alloy_rpc_client::RpcClient;
+use alloy_rpc_types_eth::{
+    erc4337::{
+        PackedUserOperation, SendUserOperation, SendUserOperationResponse, UserOperation,
+        UserOperationGasEstimation, UserOperationReceipt,
+    },
+    FeeHistory, TransactionReceipt, TransactionRequest,
+};
+use alloy_sol_types::SolCall;
+use alloy_transport::{TransportError, TransportFut};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::value::RawValue;
+use std::{
+    borrow::Cow,
+    sync::{Arc, Mutex, PoisonError},
+    task::{Context, Poll},
+};
+
+/// An in-memory stand-in for an ERC-4337 bundler, to test user operation flows without a
+/// bundler or a chain.
+///
+/// The bundler is a transport that serves `eth_chainId`, `eth_feeHistory`,
+/// `eth_maxPriorityFeePerGas`, `eth_supportedEntryPoints`, `eth_estimateUserOperationGas`,
+/// `eth_sendUserOperation`, `eth_getUserOperationReceipt`, and `eth_call` to
+/// `EntryPoint.getNonce` of the canonical `EntryPoint` contracts. Accepted user
+/// operations stay pending until [`bundle`](Self::bundle) is called, which includes them in a new
+/// block. Signatures are not validated, but nonces must be sequential per entry point and nonce
+/// key.
+///
+/// ```ignore
+/// let bundler = LocalBundler::new(1);
+/// let provider = bundler.provider::<Ethereum>();
+/// let pending = UserOperationBuilder::new(&provider, sender).send(&signer).await?;
+/// bundler.bundle();
+/// let receipt = pending.get_receipt().await?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct LocalBundler {
+    state: Arc<Mutex<BundlerState>>,
+}
+
+#[derive(Debug)]
+struct BundlerState {
+    chain_id: u64,
+    base_fee_per_gas: u128,
+    max_priority_fee_per_gas: u128,
+    gas_estimation: UserOperationGasEstimation,
+    block_number: u64,
+    nonces: HashMap<(Address, Address, U192), u64>,
+    pending: Vec<(B256, Address, SendUserOperation)>,
+    receipts: HashMap<Bytes, UserOperationReceipt>,
+}
+
+impl LocalBundler {
+    /// Creates a bundler for the given chain.
+    pub fn new(chain_id: u64) -> Self {
+        let gas_estimation = UserOperationGasEstimation {
+            pre_verification_gas: U256::from(50_000),
+            verification_gas: U256::from(100_000),
+            paymaster_verification_gas: U256::ZERO,
+            call_gas_limit: U256::from(100_000),
+        };
+        let state = BundlerState {
+            chain_id,
+            base_fee_per_gas: 1_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            gas_estimation,
+            block_number: 0,
+            nonces: HashMap::default(),
+            pending: Vec::new(),
+            receipts: HashMap::default(),
+        };
+        Self { state: Arc::new(Mutex::new(state)) }
+    }
+
+    /// Sets the base fee and priority fee reported by `eth_feeHistory` and
+    /// `eth_maxPriorityFeePerGas`, which default to 1 gwei each.
+    pub fn with_fees(self, base_fee_per_gas: u128, max_priority_fee_per_gas: u128) -> Self {
+        let mut state = self.lock();
+        state.base_fee_per_gas = base_fee_per_gas;
+        state.max_priority_fee_per_gas = max_priority_fee_per_gas;
+        drop(state);
+        self
+    }
+
+    /// Sets the result of `eth_estimateUserOperationGas`.
+    pub fn with_gas_estimation(self, gas_estimation: UserOperationGasEstimation) -> Self {
+        self.lock().gas_estimation = gas_estimation;
+        self
+    }
+
+    /// Returns a provider connected to the bundler.
+    pub fn provider<N: Network>(&self) -> RootProvider<N> {
+        RootProvider::new(RpcClient::new(self.clone(), true))
+    }
+
+    /// Returns the hashes of the accepted user operations that have not been bundled yet.
+    pub fn pending(&self) -> Vec<B256> {
+        self.lock().pending.iter().map(|(hash, ..)| *hash).collect()
+    }
+
+    /// Includes all pending user operations in a new block, and returns their receipts.
+    ///
+    /// All user operations succeed, and use the sum of their gas limits.
+    pub fn bundle(&self) -> Vec<UserOperationReceipt> {
+        let mut state = self.lock();
+        state.block_number += 1;
+        let block_number = state.block_number;
+        let pending = std::mem::take(&mut state.pending);
+
+        let block_hash = keccak256(block_number.to_be_bytes());
+        let mut cumulative_gas_used = 0u64;
+        let mut receipts = Vec::with_capacity(pending.len());
+        for (index, (hash, entry_point, user_op)) in pending.into_iter().enumerate() {
+            let (gas_used, max_fee_per_gas, paymaster) = match &user_op {
+                SendUserOperation::EntryPointV06(op) => (
+                    op.call_gas_limit + op.verification_gas_limit + op.pre_verification_gas,
+                    op.max_fee_per_gas,
+                    op.paymaster_and_data.get(..20).map(Address::from_slice).unwrap_or_default(),
+                ),
+                SendUserOperation::EntryPointV07(op) => (
+                    op.call_gas_limit + op.verification_gas_limit + op.pre_verification_gas,
+                    op.max_fee_per_gas,
+                    op.paymaster.unwrap_or_default(),
+                ),
+            };
+            let gas_used_u64 = gas_used.saturating_to::<u64>();
+            cumulative_gas_used = cumulative_gas_used.saturating_add(gas_used_u64);
+            let receipt = UserOperationReceipt {
+                user_op_hash: hash.into(),
+                entry_point,
+                sender: user_op.sender(),
+                nonce: user_op.nonce(),
+                paymaster,
+                actual_gas_cost: gas_used * max_fee_per_gas,
+                actual_gas_used: gas_used,
+                success: true,
+                reason: Bytes::new(),
+                logs: Vec::new(),
+                receipt: TransactionReceipt {
+                    inner: ReceiptEnvelope::Eip1559(ReceiptWithBloom::new(
+                        Receipt { status: true.into(), cumulative_gas_used, logs: Vec::new() },
+                        Default::default(),
+                    )),
+                    transaction_hash: B256::left_padding_from(&block_number.to_be_bytes()),
+                    transaction_index: Some(index as u64),
+                    block_hash: Some(block_hash),
+                    block_number: Some(block_number),
+                    gas_used: gas_used_u64,
+                    effective_gas_price: max_fee_per_gas.saturating_to(),
+                    blob_gas_used: None,
+                    blob_gas_price: None,
+                    from: Address::ZERO,
+                    to: Some(entry_point),
+                    contract_address: None,
+                },
+            };
+            state.receipts.insert(receipt.user_op_hash.clone(), receipt.clone());
+            receipts.push(receipt);
+        }
+        receipts
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BundlerState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn handle(&self, method: &str, params: &RawValue) -> Result<Box<RawValue>, ErrorPayload> {
+        let mut state = self.lock();
+        match method {
+            "eth_chainId" => to_raw(U64::from(state.chain_id)),
+            "eth_feeHistory" => {
+                let (block_count, _, percentiles): (U64, BlockNumberOrTag, Vec<f64>) =
+                    from_raw(params)?;
+                let block_count = block_count.to::<u64>().min(state.block_number + 1);
+                let blocks = block_count as usize;
+                to_raw(FeeHistory {
+                    base_fee_per_gas: vec![state.base_fee_per_gas; blocks + 1],
+                    gas_used_ratio: vec![0.5; blocks],
+                    oldest_block: state.block_number + 1 - block_count,
+                    reward: Some(vec![
+                        vec![state.max_priority_fee_per_gas; percentiles.len()];
+                        blocks
+                    ]),
+                    ..Default::default()
+                })
+            }
+            "eth_maxPriorityFeePerGas" => to_raw(U128::from(state.max_priority_fee_per_gas)),
+            "eth_supportedEntryPoints" => {
+                to_raw([EntryPointVersion::V07.address(), EntryPointVersion::V06.address()])
+            }
+            "eth_call" => {
+                let mut params: Vec<serde_json::Value> = from_raw(params)?;
+                let request: TransactionRequest = match params.is_empty() {
+                    true => return Err(invalid_params("missing transaction")),
+                    false => serde_json::from_value(params.swap_remove(0))
+                        .map_err(|err| invalid_params(err.to_string()))?,
+                };
+                let entry_point = request.to.and_then(|to| to.to().copied()).filter(|to| {
+                    *to == EntryPointVersion::V06.address()
+                        || *to == EntryPointVersion::V07.address()
+                });
+                let (entry_point, call) = entry_point
+                    .zip(request.input.input())
+                    .and_then(|(entry_point, input)| {
+                        Some((entry_point, IEntryPoint::getNonceCall::abi_decode(input).ok()?))
+                    })
+                    .ok_or_else(|| invalid_params("only `EntryPoint.getNonce` can be called"))?;
+                let sequence =
+                    state.nonces.get(&(entry_point, call.sender, call.key)).copied().unwrap_or(0);
+                let nonce: U256 = (call.key.to::<U256>() << 64) | U256::from(sequence);
+                to_raw(Bytes::from(nonce.to_be_bytes::<32>()))
+            }
+            "eth_estimateUserOperationGas" => {
+                let (entry_point, _) = user_operation(params)?;
+                let mut estimation = state.gas_estimation.clone();
+                if entry_point == EntryPointVersion::V06.address() {
+                    estimation.paymaster_verification_gas = U256::ZERO;
+                }
+                to_raw(estimation)
+            }
+            "eth_sendUserOperation" => {
+                let (entry_point, user_op) = user_operation(params)?;
+                let nonce = user_op.nonce();
+                let key: U192 = (nonce >> 64usize).to();
+                let sequence = nonce.wrapping_to::<u64>();
+                let expected =
+                    state.nonces.entry((entry_point, user_op.sender(), key)).or_default();
+                if sequence != *expected {
+                    return Err(invalid_params(format!(
+                        "AA25 invalid account nonce: expected {expected}, got {sequence}"
+                    )));
+                }
+                *expected += 1;
+
+                let hash = user_op.hash(entry_point, state.chain_id);
+                state.pending.push((hash, entry_point, user_op));
+                to_raw(SendUserOperationResponse { user_op_hash: hash.into() })
+            }
+            "eth_getUserOperationReceipt" => {
+                let (hash,): (Bytes,) = from_raw(params)?;
+                to_raw(state.receipts.get(&hash))
+            }
+            _ => Err(ErrorPayload::method_not_found()),
+        }
+    }
+}
+
+/// Deserializes the user operation and entry point parameters, in the format of the entry point.
+fn user_operation(params: &RawValue) -> Result<(Address, SendUserOperation), ErrorPayload> {
+    let (user_op, entry_point): (serde_json::Value, Address) = from_raw(params)?;
+    let user_op = if entry_point == EntryPointVersion::V07.address() {
+        serde_json::from_value::<PackedUserOperation>(user_op).map(Into::into)
+    } else if entry_point == EntryPointVersion::V06.address() {
+        serde_json::from_value::<UserOperation>(user_op).map(Into::into)
+    } else {
+        return Err(invalid_params(format!("unsupported entry point {entry_point}")));
+    };
+    Ok((entry_point, user_op.map_err(|err| invalid_params(err.to_string()))?))
+}
+
+fn from_raw<T: DeserializeOwned>(params: &RawValue) -> Result<T, ErrorPayload> {
+    serde_json::from_str(params.get()).map_err(|err| invalid_params(err.to_string()))
+}
+
+fn to_raw(value: impl Serialize) -> Result<Box<RawValue>, ErrorPayload> {
+    serde_json::value::to_raw_value(&value)
+        .map_err(|err| ErrorPayload::internal_error_message(err.to_string().into()))
+}
+
+fn invalid_params(message: impl Into<Cow<'static, str>>) -> ErrorPayload {
+    ErrorPayload { message: message.into(), ..ErrorPayload::invalid_params() }
+}
+
+/// The parts of a JSON-RPC request the bundler reads.
+#[derive(Deserialize)]
+struct Call<'a> {
+    #[serde(borrow)]
+    params: Option<&'a RawValue>,
+}
+
+impl tower::Service<RequestPacket> for LocalBundler {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: RequestPacket) -> Self::Future {
+        let respond = |req: &alloy_json_rpc::SerializedRequest| {
+            let empty = RawValue::NULL;
+            let params = serde_json::from_str::<Call<'_>>(req.serialized().get())
+                .ok()
+                .and_then(|call| call.params)
+                .unwrap_or(empty);
+            let payload = match self.handle(req.method(), params) {
+                Ok(result) => ResponsePayload::Success(result),
+                Err(err) => ResponsePayload::Failure(err),
+            };
+            Response { id: req.id().clone(), payload }
+        };
+        let response = match &req {
+            RequestPacket::Single(req) => ResponsePacket::Single(respond(req)),
+            RequestPacket::Batch(reqs) => ResponsePacket::Batch(reqs.iter().map(respond).collect()),
+        };
+        Box::pin(async move { Ok(response) })
+    }
+}
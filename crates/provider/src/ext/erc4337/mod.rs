@@ -0,0 +1,295 @@
+use crate::Provider;
+use alloy_network::Network;
+use alloy_primitives::{Address, Bytes};
+use alloy_rpc_types_eth::erc4337::{
+    SendUserOperation, SendUserOperationResponse, UserOperationGasEstimation, UserOperationReceipt,
+};
+use alloy_transport::{TransportError, TransportResult};
+
+mod builder;
+pub use builder::{EntryPointVersion, PaymasterHook, UserOperationBuilder};
+
+mod bundler;
+pub use bundler::LocalBundler;
+
+mod pending;
+pub use pending::PendingUserOperation;
+
+/// Errors that may occur when building, sending or watching a user operation.
+#[derive(Debug, thiserror::Error)]
+pub enum UserOperationError {
+    /// Underlying transport error.
+    #[error(transparent)]
+    Transport(#[from] TransportError),
+    /// Signing the user operation failed.
+    #[error(transparent)]
+    Signer(#[from] alloy_signer::Error),
+    /// The `EntryPoint` contract returned malformed data.
+    #[error(transparent)]
+    Abi(#[from] alloy_sol_types::Error),
+    /// The user operation was not included before the timeout elapsed.
+    #[error("user operation {0} was not included before the timeout")]
+    Timeout(Bytes),
+}
+
+/// ERC-4337 Account Abstraction API
+///
+/// This module provides support for the `eth_sendUserOperation` RPC method
+/// as defined in ERC-4337.
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+pub trait Erc4337Api<N>: Send + Sync {
+    /// Sends a user operation to the bundler, as defined in ERC-4337.
+    ///
+    /// Entry point changes based on the user operation type.
+    async fn send_user_operation(
+        &self,
+        user_op: SendUserOperation,
+        entry_point: Address,
+    ) -> TransportResult<SendUserOperationResponse>;
+
+    /// Returns the list of supported entry points.
+    async fn supported_entry_points(&self) -> TransportResult<Vec<Address>>;
+
+    /// Returns the receipt for any user operation.
+    ///
+    /// Hash is the same returned by any user operation.
+    async fn get_user_operation_receipt(
+        &self,
+        user_op_hash: Bytes,
+    ) -> TransportResult<UserOperationReceipt>;
+
+    /// Estimates the gas for a user operation.
+    ///
+    /// Entry point changes based on the user operation type.
+    async fn estimate_user_operation_gas(
+        &self,
+        user_op: SendUserOperation,
+        entry_point: Address,
+    ) -> TransportResult<UserOperationGasEstimation>;
+}
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl<N, P> Erc4337Api<N> for P
+where
+    N: Network,
+    P: Provider<N>,
+{
+    async fn send_user_operation(
+        &self,
+        user_op: SendUserOperation,
+        entry_point: Address,
+    ) -> TransportResult<SendUserOperationResponse> {
+        match user_op {
+            SendUserOperation::EntryPointV06(user_op) => {
+                self.client().request("eth_sendUserOperation", (user_op, entry_point)).await
+            }
+            SendUserOperation::EntryPointV07(packed_user_op) => {
+                self.client().request("eth_sendUserOperation", (packed_user_op, entry_point)).await
+            }
+        }
+    }
+
+    async fn supported_entry_points(&self) -> TransportResult<Vec<Address>> {
+        self.client().request("eth_supportedEntryPoints", ()).await
+    }
+
+    async fn get_user_operation_receipt(
+        &self,
+        user_op_hash: Bytes,
+    ) -> TransportResult<UserOperationReceipt> {
+        self.client().request("eth_getUserOperationReceipt", (user_op_hash,)).await
+    }
+
+    async fn estimate_user_operation_gas(
+        &self,
+        user_op: SendUserOperation,
+        entry_point: Address,
+    ) -> TransportResult<UserOperationGasEstimation> {
+        match user_op {
+            SendUserOperation::EntryPointV06(user_op) => {
+                self.client().request("eth_estimateUserOperationGas", (user_op, entry_point)).await
+            }
+            SendUserOperation::EntryPointV07(packed_user_op) => {
+                self.client()
+                    .request("eth_estimateUserOperationGas", (packed_user_op, entry_point))
+                    .await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mock::Asserter, ProviderBuilder};
+    use alloy_primitives::{bytes, Signature, U256};
+    use alloy_rpc_types_eth::erc4337::ENTRY_POINT_V07_ADDRESS;
+    use alloy_signer_local::PrivateKeySigner;
+    use std::time::Duration;
+
+    #[derive(Debug)]
+    struct TestPaymaster;
+
+    #[async_trait::async_trait]
+    impl PaymasterHook for TestPaymaster {
+        async fn paymaster_data(
+            &self,
+            user_op: &mut SendUserOperation,
+            _entry_point: Address,
+            _chain_id: u64,
+        ) -> TransportResult<()> {
+            if let SendUserOperation::EntryPointV07(op) = user_op {
+                op.paymaster = Some(Address::repeat_byte(0xaa));
+                op.paymaster_post_op_gas_limit = Some(U256::from(1));
+                op.paymaster_data = Some(bytes!("beef"));
+            }
+            Ok(())
+        }
+    }
+
+    fn gas_estimation() -> UserOperationGasEstimation {
+        UserOperationGasEstimation {
+            pre_verification_gas: U256::from(50_000),
+            verification_gas: U256::from(100_000),
+            paymaster_verification_gas: U256::from(30_000),
+            call_gas_limit: U256::from(200_000),
+        }
+    }
+
+    #[tokio::test]
+    async fn build_sign_and_send_user_operation() {
+        let asserter = Asserter::new();
+        let bundler = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let signer = PrivateKeySigner::random();
+        let sender = Address::random();
+
+        // eth_call to `EntryPoint.getNonce`
+        asserter.push_success(&Bytes::from(U256::from(5).to_be_bytes::<32>()));
+        asserter.push_success(&gas_estimation());
+        asserter.push_success(&SendUserOperationResponse { user_op_hash: bytes!("0123") });
+
+        let builder = UserOperationBuilder::new(&bundler, sender)
+            .chain_id(1)
+            .call_data(bytes!("b61d27f6"))
+            .call_gas_limit(U256::from(300_000))
+            .max_fee_per_gas(U256::from(10))
+            .max_priority_fee_per_gas(U256::from(1))
+            .paymaster(TestPaymaster)
+            .dummy_signature(bytes!("ff"));
+        let pending = builder.send(&signer).await.unwrap();
+        assert_eq!(pending.user_op_hash(), &bytes!("0123"));
+        assert!(asserter.read_q().is_empty());
+
+        asserter.push_success(&Bytes::from(U256::from(5).to_be_bytes::<32>()));
+        asserter.push_success(&gas_estimation());
+        let user_op = builder.sign(&signer).await.unwrap();
+        let SendUserOperation::EntryPointV07(op) = &user_op else { panic!("expected v0.7") };
+        assert_eq!(op.nonce, U256::from(5));
+        assert_eq!(op.call_gas_limit, U256::from(300_000));
+        assert_eq!(op.verification_gas_limit, U256::from(100_000));
+        assert_eq!(op.pre_verification_gas, U256::from(50_000));
+        assert_eq!(op.paymaster_verification_gas_limit, Some(U256::from(30_000)));
+        assert_eq!(op.paymaster_data, Some(bytes!("beef")));
+
+        let hash = user_op.hash(ENTRY_POINT_V07_ADDRESS, 1);
+        let signature = Signature::try_from(user_op.signature().as_ref()).unwrap();
+        assert_eq!(signature.recover_address_from_msg(hash.as_slice()).unwrap(), signer.address());
+    }
+
+    #[tokio::test]
+    async fn pending_user_operation_timeout() {
+        let asserter = Asserter::new();
+        let bundler = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+        asserter.push_success(&Option::<UserOperationReceipt>::None);
+        let err = PendingUserOperation::new(bundler.root().clone(), bytes!("0123"))
+            .with_poll_interval(Duration::from_millis(10))
+            .with_timeout(Some(Duration::ZERO))
+            .get_receipt()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, UserOperationError::Timeout(hash) if hash == bytes!("0123")));
+    }
+
+    #[tokio::test]
+    async fn local_bundler_example() {
+        let bundler = LocalBundler::new(1);
+        let provider = bundler.provider::<alloy_network::Ethereum>();
+        let signer = PrivateKeySigner::random();
+        let sender = Address::random();
+
+        let pending = UserOperationBuilder::new(&provider, sender).send(&signer).await.unwrap();
+        bundler.bundle();
+        let receipt = pending.get_receipt().await.unwrap();
+        assert_eq!(receipt.sender, sender);
+        assert!(receipt.success);
+
+        // both user operations of the second bundle are in block 2, after the first bundle
+        for _ in 0..2 {
+            let _ = UserOperationBuilder::new(&provider, sender).send(&signer).await.unwrap();
+        }
+        let receipts = bundler.bundle();
+        assert_eq!(receipts[0].receipt.block_hash, receipts[1].receipt.block_hash);
+        assert_ne!(receipts[0].receipt.block_hash, receipt.receipt.block_hash);
+        let cumulative_gas_used = |receipt: &UserOperationReceipt| {
+            receipt.receipt.inner.as_receipt_with_bloom().unwrap().receipt.cumulative_gas_used
+        };
+        assert_eq!(
+            cumulative_gas_used(&receipts[1]),
+            receipts[0].receipt.gas_used + receipts[1].receipt.gas_used
+        );
+    }
+
+    #[tokio::test]
+    async fn local_bundler_round_trip() {
+        let bundler = LocalBundler::new(1);
+        let provider = bundler.provider::<alloy_network::Ethereum>();
+        let signer = PrivateKeySigner::random();
+        let sender = Address::random();
+
+        let builder = || {
+            UserOperationBuilder::new(&provider, sender)
+                .call_data(bytes!("b61d27f6"))
+                .max_fee_per_gas(U256::from(10))
+                .max_priority_fee_per_gas(U256::from(1))
+        };
+        let user_op = builder().sign(&signer).await.unwrap();
+        let pending = builder().send(&signer).await.unwrap();
+        let hash = user_op.hash(ENTRY_POINT_V07_ADDRESS, 1);
+        assert_eq!(pending.user_op_hash(), &Bytes::from(hash));
+        assert_eq!(bundler.pending(), vec![hash]);
+        let receipt: Option<UserOperationReceipt> = provider
+            .client()
+            .request("eth_getUserOperationReceipt", (Bytes::from(hash),))
+            .await
+            .unwrap();
+        assert_eq!(receipt, None);
+
+        // the nonce is taken from the bundler, and the v0.6 entry point has its own sequence
+        let next = builder().sign(&signer).await.unwrap();
+        assert_eq!(next.nonce(), U256::from(1));
+        let v06 = builder().entry_point_version(EntryPointVersion::V06);
+        let v06_pending = v06.send(&signer).await.unwrap();
+        assert_eq!(bundler.pending().len(), 2);
+
+        let receipts = bundler.bundle();
+        assert_eq!(receipts.len(), 2);
+        assert!(bundler.pending().is_empty());
+
+        let receipt = pending.with_poll_interval(Duration::from_millis(10)).get_receipt().await;
+        let receipt = receipt.unwrap();
+        assert_eq!(receipt, receipts[0]);
+        assert_eq!(receipt.sender, sender);
+        assert_eq!(receipt.nonce, U256::ZERO);
+        assert_eq!(receipt.entry_point, ENTRY_POINT_V07_ADDRESS);
+        assert_eq!(receipt.receipt.block_number, Some(1));
+        let receipt = v06_pending.get_receipt().await.unwrap();
+        assert_eq!(receipt.nonce, U256::ZERO);
+        assert_eq!(receipt.entry_point, EntryPointVersion::V06.address());
+
+        let err = builder().nonce(U256::from(5)).send(&signer).await.unwrap_err();
+        assert!(err.to_string().contains("AA25 invalid account nonce"), "{err}");
+    }
+}
@@ -0,0 +1,73 @@
+use super::UserOperationError;
+use crate::{utils::poll_until, Provider, RootProvider};
+use alloy_network::Network;
+use alloy_primitives::Bytes;
+use alloy_rpc_types_eth::erc4337::UserOperationReceipt;
+use std::{ops::ControlFlow, time::Duration};
+
+/// A user operation that has been accepted by a bundler.
+///
+/// The receipt is available once the bundle transaction including the user operation has been
+/// mined, see [`get_receipt`](Self::get_receipt).
+#[derive(Debug)]
+#[must_use = "this type does nothing unless you call `get_receipt`"]
+pub struct PendingUserOperation<N: Network> {
+    provider: RootProvider<N>,
+    user_op_hash: Bytes,
+    poll_interval: Option<Duration>,
+    timeout: Option<Duration>,
+}
+
+impl<N: Network> PendingUserOperation<N> {
+    /// Creates a new pending user operation for the given hash.
+    pub const fn new(provider: RootProvider<N>, user_op_hash: Bytes) -> Self {
+        Self { provider, user_op_hash, poll_interval: None, timeout: None }
+    }
+
+    /// Returns the hash of the user operation.
+    pub const fn user_op_hash(&self) -> &Bytes {
+        &self.user_op_hash
+    }
+
+    /// Returns the provider the user operation is polled with.
+    pub const fn provider(&self) -> &RootProvider<N> {
+        &self.provider
+    }
+
+    /// Sets the interval at which the bundler is polled for the receipt.
+    ///
+    /// Defaults to the poll interval of the client.
+    pub const fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = Some(poll_interval);
+        self
+    }
+
+    /// Sets the duration after which waiting for the receipt is aborted.
+    ///
+    /// Defaults to no timeout.
+    pub const fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Polls `eth_getUserOperationReceipt` until the user operation has been included.
+    pub async fn get_receipt(self) -> Result<UserOperationReceipt, UserOperationError> {
+        let poll_interval =
+            self.poll_interval.unwrap_or_else(|| self.provider.client().poll_interval());
+
+        let receipt = poll_until(poll_interval, self.timeout, || async {
+            let receipt: Option<UserOperationReceipt> = self
+                .provider
+                .client()
+                .request("eth_getUserOperationReceipt", (self.user_op_hash.clone(),))
+                .await?;
+            if let Some(receipt) = receipt {
+                return Ok::<_, UserOperationError>(ControlFlow::Break(receipt));
+            }
+            trace!(user_op_hash = %self.user_op_hash, "user operation pending");
+            Ok(ControlFlow::Continue(()))
+        })
+        .await?;
+        receipt.map_err(|()| UserOperationError::Timeout(self.user_op_hash))
+    }
+}
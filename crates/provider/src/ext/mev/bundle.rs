@@ -0,0 +1,635 @@
+//! Building, simulating and submitting bundles of signed transactions.
+
+use crate::{ext::MevApi, utils::poll_until, Provider};
+use alloy_eips::{eip2718::Encodable2718, BlockNumberOrTag};
+use alloy_network::{
+    Ethereum, Network, NetworkTransactionBuilder, NetworkWallet, ReceiptResponse,
+    TransactionBuilder, TransactionBuilderError,
+};
+use alloy_primitives::{keccak256, Address, Bytes, TxHash, U256};
+use alloy_rpc_types_mev::{
+    EthCallBundle, EthCallBundleResponse, EthCallBundleTransactionResult, EthSendBundle,
+};
+use alloy_signer::Signer;
+use alloy_transport::{TransportError, TransportErrorKind, TransportResult};
+use futures::future::join_all;
+use std::{collections::HashMap, future::IntoFuture, ops::ControlFlow};
+
+/// Errors that may occur when building a bundle, see [`BundleBuilder::build`].
+#[derive(Debug, thiserror::Error)]
+pub enum BundleError<N: Network> {
+    /// Underlying transport error.
+    #[error(transparent)]
+    Transport(#[from] TransportError),
+    /// A transaction request has no sender, so its nonce can't be filled.
+    #[error("transaction {0} of the bundle has no `from` address")]
+    MissingFrom(usize),
+    /// A transaction request could not be signed.
+    #[error(transparent)]
+    Build(#[from] TransactionBuilderError<N>),
+}
+
+#[derive(Debug)]
+enum BundleTransaction<N: Network> {
+    Request { request: N::TransactionRequest, can_revert: bool },
+    Signed { encoded: Bytes, can_revert: bool },
+}
+
+/// A builder for bundles of transactions, signed with sequential nonces.
+///
+/// Transactions are added as transaction requests, e.g. from
+/// `CallBuilder::into_transaction_request`, or as already signed transactions. Missing nonces are
+/// filled sequentially per sender, starting at the pending nonce of the sender, and missing chain
+/// ids, fees and gas limits are filled from the provider.
+///
+/// Gas limits are estimated against the latest block, without the preceding transactions of the
+/// bundle, so they should be set for transactions that depend on them.
+///
+/// ```no_run
+/// # async fn example<P: alloy_provider::Provider>(
+/// #     provider: P,
+/// #     builder: P,
+/// #     wallet: alloy_network::EthereumWallet,
+/// #     auth: alloy_signer_local::PrivateKeySigner,
+/// #     tx: alloy_rpc_types_eth::TransactionRequest,
+/// # ) -> Result<(), Box<dyn std::error::Error>> {
+/// use alloy_provider::ext::BundleBuilder;
+///
+/// let bundle =
+///     BundleBuilder::new().transaction(tx).max_blocks(3).build(&provider, &wallet).await?;
+/// let simulation = bundle.simulate(&builder, auth.clone()).await?;
+/// if simulation.is_success() {
+///     let status = bundle.submit(&provider, &[builder], auth).await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct BundleBuilder<N: Network = Ethereum> {
+    transactions: Vec<BundleTransaction<N>>,
+    target_block: Option<u64>,
+    max_blocks: u64,
+    replacement_uuid: Option<String>,
+}
+
+impl<N: Network> Default for BundleBuilder<N> {
+    fn default() -> Self {
+        Self { transactions: Vec::new(), target_block: None, max_blocks: 1, replacement_uuid: None }
+    }
+}
+
+impl<N: Network> BundleBuilder<N> {
+    /// Creates an empty bundle that targets the next block.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a transaction that must not revert.
+    pub fn transaction(mut self, request: impl Into<N::TransactionRequest>) -> Self {
+        self.transactions
+            .push(BundleTransaction::Request { request: request.into(), can_revert: false });
+        self
+    }
+
+    /// Adds a transaction that may revert without invalidating the bundle.
+    pub fn reverting_transaction(mut self, request: impl Into<N::TransactionRequest>) -> Self {
+        self.transactions
+            .push(BundleTransaction::Request { request: request.into(), can_revert: true });
+        self
+    }
+
+    /// Adds an already signed, EIP-2718 encoded transaction that must not revert, e.g. the
+    /// transaction of another user to backrun.
+    pub fn signed_transaction(mut self, encoded: impl Into<Bytes>) -> Self {
+        self.transactions
+            .push(BundleTransaction::Signed { encoded: encoded.into(), can_revert: false });
+        self
+    }
+
+    /// Sets the first block the bundle targets, the next block by default.
+    pub const fn target_block(mut self, block: u64) -> Self {
+        self.target_block = Some(block);
+        self
+    }
+
+    /// Sets the number of blocks the bundle is submitted for before it expires, `1` by default.
+    pub const fn max_blocks(mut self, max_blocks: u64) -> Self {
+        self.max_blocks = max_blocks;
+        self
+    }
+
+    /// Sets the replacement UUID of the bundle, which allows to replace or cancel it.
+    pub fn replacement_uuid(mut self, uuid: impl Into<String>) -> Self {
+        self.replacement_uuid = Some(uuid.into());
+        self
+    }
+
+    /// Fills and signs the transactions of the bundle.
+    pub async fn build<P, W>(self, provider: &P, wallet: &W) -> Result<SignedBundle, BundleError<N>>
+    where
+        P: Provider<N>,
+        W: NetworkWallet<N>,
+    {
+        let target_block = match self.target_block {
+            Some(block) => block,
+            None => provider.get_block_number().await? + 1,
+        };
+
+        let mut chain_id = None;
+        let mut fees = None;
+        let mut nonces = HashMap::<Address, u64>::new();
+        let mut bundle = SignedBundle {
+            transactions: Vec::with_capacity(self.transactions.len()),
+            tx_hashes: Vec::with_capacity(self.transactions.len()),
+            reverting_tx_hashes: Vec::new(),
+            target_block,
+            max_blocks: self.max_blocks.max(1),
+            replacement_uuid: self.replacement_uuid,
+        };
+
+        for (index, transaction) in self.transactions.into_iter().enumerate() {
+            let (encoded, can_revert) = match transaction {
+                BundleTransaction::Signed { encoded, can_revert } => (encoded, can_revert),
+                BundleTransaction::Request { mut request, can_revert } => {
+                    let from = request.from().ok_or(BundleError::MissingFrom(index))?;
+                    if request.nonce().is_none() {
+                        let nonce = match nonces.get(&from) {
+                            Some(nonce) => *nonce,
+                            None => provider.get_transaction_count(from).pending().await?,
+                        };
+                        request.set_nonce(nonce);
+                    }
+                    if let Some(nonce) = request.nonce() {
+                        nonces.insert(from, nonce + 1);
+                    }
+
+                    if request.chain_id().is_none() {
+                        let id = match chain_id {
+                            Some(id) => id,
+                            None => *chain_id.insert(provider.get_chain_id().await?),
+                        };
+                        request.set_chain_id(id);
+                    }
+                    if request.gas_price().is_none() && request.max_fee_per_gas().is_none() {
+                        let estimate = match fees {
+                            Some(estimate) => estimate,
+                            None => *fees.insert(provider.estimate_eip1559_fees().await?),
+                        };
+                        request.set_max_fee_per_gas(estimate.max_fee_per_gas);
+                        request.set_max_priority_fee_per_gas(estimate.max_priority_fee_per_gas);
+                    }
+                    if request.gas_limit().is_none() {
+                        request.set_gas_limit(provider.estimate_gas(request.clone()).await?);
+                    }
+
+                    (request.build(wallet).await?.encoded_2718().into(), can_revert)
+                }
+            };
+
+            let hash = keccak256(&encoded);
+            if can_revert {
+                bundle.reverting_tx_hashes.push(hash);
+            }
+            bundle.tx_hashes.push(hash);
+            bundle.transactions.push(encoded);
+        }
+
+        Ok(bundle)
+    }
+}
+
+/// A bundle of signed transactions, see [`BundleBuilder`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignedBundle {
+    /// The EIP-2718 encoded transactions.
+    pub transactions: Vec<Bytes>,
+    /// The hashes of the transactions.
+    pub tx_hashes: Vec<TxHash>,
+    /// The hashes of the transactions that may revert.
+    pub reverting_tx_hashes: Vec<TxHash>,
+    /// The first block the bundle targets.
+    pub target_block: u64,
+    /// The number of blocks the bundle is submitted for before it expires.
+    pub max_blocks: u64,
+    /// The replacement UUID of the bundle.
+    pub replacement_uuid: Option<String>,
+}
+
+impl SignedBundle {
+    /// Returns the last block the bundle targets.
+    pub const fn last_block(&self) -> u64 {
+        self.target_block.saturating_add(self.max_blocks.saturating_sub(1))
+    }
+
+    /// Returns the `eth_sendBundle` request that targets the given block.
+    pub fn send_bundle_request(&self, block: u64) -> EthSendBundle {
+        EthSendBundle {
+            txs: self.transactions.clone(),
+            block_number: block,
+            reverting_tx_hashes: self.reverting_tx_hashes.clone(),
+            replacement_uuid: self.replacement_uuid.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the `eth_callBundle` request that simulates the bundle in the target block, on top
+    /// of the latest state.
+    pub fn call_bundle_request(&self) -> EthCallBundle {
+        EthCallBundle {
+            block_number: self.target_block,
+            state_block_number: BlockNumberOrTag::Latest,
+            ..EthCallBundle::from_raw_txs(self.transactions.clone())
+        }
+    }
+
+    /// Simulates the bundle with `eth_callBundle`, authenticated with the given signer.
+    pub async fn simulate<P, N, S>(
+        &self,
+        builder: &P,
+        signer: S,
+    ) -> TransportResult<BundleSimulation>
+    where
+        P: Provider<N>,
+        N: Network,
+        S: Signer + Send + Sync + 'static,
+    {
+        let response = builder
+            .call_bundle(self.call_bundle_request())
+            .with_auth(signer)
+            .await?
+            .ok_or_else(|| TransportErrorKind::custom_str("empty eth_callBundle response"))?;
+        Ok(BundleSimulation::new(response, &self.reverting_tx_hashes))
+    }
+
+    /// Submits the bundle to all builders concurrently, authenticated with the given signer, and
+    /// retargets it at every new block until it is included or expires.
+    ///
+    /// The provider is used to follow the chain and to check whether the bundle was included, i.e.
+    /// all of its transactions landed in the same block at or after the target block. Submissions
+    /// rejected by individual builders are logged and otherwise ignored.
+    pub async fn submit<P, B, N, S>(
+        &self,
+        provider: &P,
+        builders: &[B],
+        signer: S,
+    ) -> TransportResult<BundleStatus>
+    where
+        P: Provider<N>,
+        B: Provider<N>,
+        N: Network,
+        S: Signer + Clone + Send + Sync + 'static,
+    {
+        if self.tx_hashes.is_empty() {
+            return Ok(BundleStatus::Expired { last_block: self.last_block() });
+        }
+        let poll_interval = provider.client().poll_interval();
+
+        let mut block =
+            provider.get_block_number().await?.max(self.target_block.saturating_sub(1)) + 1;
+        while block <= self.last_block() {
+            let request = self.send_bundle_request(block);
+            let results = join_all(builders.iter().map(|builder| {
+                builder.send_bundle(request.clone()).with_auth(signer.clone()).into_future()
+            }))
+            .await;
+            for (index, result) in results.into_iter().enumerate() {
+                if let Err(err) = result {
+                    debug!(%err, builder = index, block, "bundle submission failed");
+                }
+            }
+
+            // wait for the targeted block
+            let latest = poll_until(poll_interval, None, || async {
+                let latest = provider.get_block_number().await?;
+                Ok::<_, TransportError>(match latest >= block {
+                    true => ControlFlow::Break(latest),
+                    false => ControlFlow::Continue(()),
+                })
+            })
+            .await?
+            .unwrap_or(block);
+
+            if let Some(block_number) = self.included_block(provider).await? {
+                return Ok(BundleStatus::Included { block_number });
+            }
+            block = latest + 1;
+        }
+
+        Ok(BundleStatus::Expired { last_block: self.last_block() })
+    }
+}
+
+impl SignedBundle {
+    /// Returns the block the bundle was included in, if all of its transactions landed in the same
+    /// block at or after the target block.
+    ///
+    /// Checking only a single transaction is not enough, since e.g. the first transaction of a
+    /// backrun bundle is the transaction of another user, which may be mined without the bundle.
+    async fn included_block<P, N>(&self, provider: &P) -> TransportResult<Option<u64>>
+    where
+        P: Provider<N>,
+        N: Network,
+    {
+        let mut included = None;
+        for hash in &self.tx_hashes {
+            let receipt = provider.get_transaction_receipt(*hash).await?;
+            let Some(block_number) = receipt.and_then(|receipt| receipt.block_number()) else {
+                return Ok(None);
+            };
+            if block_number < self.target_block || included.is_some_and(|b| b != block_number) {
+                return Ok(None);
+            }
+            included = Some(block_number);
+        }
+        Ok(included)
+    }
+}
+
+/// The outcome of [`SignedBundle::submit`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BundleStatus {
+    /// The bundle was included in the given block.
+    Included {
+        /// The number of the block.
+        block_number: u64,
+    },
+    /// The bundle was not included up to the last block it targets.
+    Expired {
+        /// The last block the bundle targeted.
+        last_block: u64,
+    },
+}
+
+/// The result of [`SignedBundle::simulate`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BundleSimulation {
+    /// The raw `eth_callBundle` response.
+    pub response: EthCallBundleResponse,
+    /// The results of the individual transactions, in bundle order.
+    pub transactions: Vec<SimulatedTransaction>,
+}
+
+impl BundleSimulation {
+    fn new(response: EthCallBundleResponse, reverting_tx_hashes: &[TxHash]) -> Self {
+        let transactions = response
+            .results
+            .iter()
+            .map(|result| SimulatedTransaction::new(result, reverting_tx_hashes))
+            .collect();
+        Self { response, transactions }
+    }
+
+    /// Returns the profit of the bundle for the block builder, i.e. the increase of the coinbase
+    /// balance.
+    pub const fn profit(&self) -> U256 {
+        self.response.coinbase_diff
+    }
+
+    /// Returns the total gas used by the bundle.
+    pub const fn gas_used(&self) -> u64 {
+        self.response.total_gas_used
+    }
+
+    /// Returns the transactions that reverted although they are not allowed to.
+    pub fn unexpected_reverts(&self) -> impl Iterator<Item = &SimulatedTransaction> {
+        self.transactions.iter().filter(|tx| tx.reverted() && !tx.can_revert)
+    }
+
+    /// Returns `true` if no transaction reverted that is not allowed to.
+    pub fn is_success(&self) -> bool {
+        self.unexpected_reverts().next().is_none()
+    }
+}
+
+/// The simulated result of a transaction of a bundle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimulatedTransaction {
+    /// The hash of the transaction.
+    pub tx_hash: TxHash,
+    /// The sender of the transaction.
+    pub from: Address,
+    /// The profit of the transaction for the block builder, i.e. the increase of the coinbase
+    /// balance.
+    pub profit: U256,
+    /// The gas used by the transaction.
+    pub gas_used: u64,
+    /// The revert data, if the transaction reverted.
+    pub revert: Option<Bytes>,
+    /// Whether the transaction may revert.
+    pub can_revert: bool,
+}
+
+impl SimulatedTransaction {
+    fn new(result: &EthCallBundleTransactionResult, reverting_tx_hashes: &[TxHash]) -> Self {
+        Self {
+            tx_hash: result.tx_hash,
+            from: result.from_address,
+            profit: result.coinbase_diff,
+            gas_used: result.gas_used,
+            revert: result.revert.clone(),
+            can_revert: reverting_tx_hashes.contains(&result.tx_hash),
+        }
+    }
+
+    /// Returns `true` if the transaction reverted.
+    pub const fn reverted(&self) -> bool {
+        self.revert.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProviderBuilder;
+    use alloy_consensus::{ReceiptEnvelope, ReceiptWithBloom, Transaction, TxEnvelope};
+    use alloy_eips::eip2718::Decodable2718;
+    use alloy_network::EthereumWallet;
+    use alloy_primitives::{address, B256, U64};
+    use alloy_rpc_types_eth::{TransactionReceipt, TransactionRequest};
+    use alloy_signer_local::PrivateKeySigner;
+    use alloy_transport::mock::Asserter;
+
+    fn request(from: Address) -> TransactionRequest {
+        TransactionRequest::default()
+            .from(from)
+            .to(address!("0x0000000000000000000000000000000000000001"))
+            .gas_limit(21_000)
+            .max_fee_per_gas(2_000_000_000)
+            .max_priority_fee_per_gas(1_000_000_000)
+            .with_chain_id(1)
+    }
+
+    #[tokio::test]
+    async fn build_fills_sequential_nonces() {
+        let alice = PrivateKeySigner::random();
+        let bob = PrivateKeySigner::random();
+        let mut wallet = EthereumWallet::new(alice.clone());
+        wallet.register_signer(bob.clone());
+
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        asserter.push_success(&U64::from(41));
+        asserter.push_success(&U64::from(7));
+        asserter.push_success(&U64::from(5));
+
+        let bundle = BundleBuilder::new()
+            .transaction(request(alice.address()))
+            .reverting_transaction(request(bob.address()))
+            .transaction(request(alice.address()))
+            .max_blocks(3)
+            .build(&provider, &wallet)
+            .await
+            .unwrap();
+        assert!(asserter.read_q().is_empty());
+        assert_eq!((bundle.target_block, bundle.last_block()), (42, 44));
+
+        let nonces = bundle
+            .transactions
+            .iter()
+            .map(|tx| TxEnvelope::decode_2718(&mut tx.as_ref()).unwrap().nonce())
+            .collect::<Vec<_>>();
+        assert_eq!(nonces, [7, 5, 8]);
+        assert_eq!(bundle.reverting_tx_hashes, [bundle.tx_hashes[1]]);
+        assert_eq!(bundle.send_bundle_request(43).reverting_tx_hashes, [bundle.tx_hashes[1]]);
+    }
+
+    #[tokio::test]
+    async fn simulate_reports_reverts() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let bundle = SignedBundle {
+            transactions: vec![Bytes::from_static(&[1]), Bytes::from_static(&[2])],
+            tx_hashes: vec![TxHash::with_last_byte(1), TxHash::with_last_byte(2)],
+            reverting_tx_hashes: vec![TxHash::with_last_byte(2)],
+            target_block: 10,
+            max_blocks: 1,
+            replacement_uuid: None,
+        };
+
+        let result =
+            |hash: u8, profit: u64, revert: Option<Bytes>| EthCallBundleTransactionResult {
+                coinbase_diff: U256::from(profit),
+                tx_hash: TxHash::with_last_byte(hash),
+                gas_used: 21_000,
+                revert,
+                ..Default::default()
+            };
+        let response = EthCallBundleResponse {
+            coinbase_diff: U256::from(3),
+            total_gas_used: 42_000,
+            results: vec![result(1, 3, None), result(2, 0, Some(Bytes::from_static(&[0xff])))],
+            ..Default::default()
+        };
+        asserter.push_success(&response);
+        asserter.push_success(&EthCallBundleResponse {
+            results: vec![result(1, 0, Some(Bytes::new())), result(2, 0, None)],
+            ..response
+        });
+
+        let signer = PrivateKeySigner::random();
+        let simulation = bundle.simulate(&provider, signer.clone()).await.unwrap();
+        assert!(simulation.is_success());
+        assert_eq!(simulation.profit(), U256::from(3));
+        assert_eq!(simulation.transactions[0].profit, U256::from(3));
+        assert!(simulation.transactions[1].reverted() && simulation.transactions[1].can_revert);
+
+        let simulation = bundle.simulate(&provider, signer).await.unwrap();
+        assert!(!simulation.is_success());
+        assert_eq!(simulation.unexpected_reverts().next().unwrap().tx_hash, bundle.tx_hashes[0]);
+    }
+
+    #[tokio::test]
+    async fn submit_retargets_until_expiry() {
+        let chain = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(chain.clone());
+        let builder = Asserter::new();
+        let builders = [ProviderBuilder::new().connect_mocked_client(builder.clone())];
+        let bundle = SignedBundle {
+            transactions: vec![Bytes::from_static(&[1])],
+            tx_hashes: vec![TxHash::with_last_byte(1)],
+            reverting_tx_hashes: Vec::new(),
+            target_block: 10,
+            max_blocks: 2,
+            replacement_uuid: None,
+        };
+
+        // block 10 is targeted while the chain is at 9, then block 11
+        chain.push_success(&U64::from(9));
+        chain.push_success(&U64::from(10));
+        chain.push_success(&None::<()>);
+        chain.push_success(&U64::from(11));
+        chain.push_success(&None::<()>);
+        builder.push_success(&serde_json::json!({ "bundleHash": TxHash::ZERO }));
+        builder.push_failure_msg("bundle rejected");
+
+        let status = bundle.submit(&provider, &builders, PrivateKeySigner::random()).await.unwrap();
+        assert_eq!(status, BundleStatus::Expired { last_block: 11 });
+        assert!(chain.read_q().is_empty() && builder.read_q().is_empty());
+    }
+
+    #[tokio::test]
+    async fn submit_requires_all_transactions() {
+        let chain = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(chain.clone());
+        let builder = Asserter::new();
+        let builders = [ProviderBuilder::new().connect_mocked_client(builder.clone())];
+        let victim = TxHash::with_last_byte(1);
+        let backrun = TxHash::with_last_byte(2);
+        let bundle = SignedBundle {
+            transactions: vec![Bytes::from_static(&[1]), Bytes::from_static(&[2])],
+            tx_hashes: vec![victim, backrun],
+            reverting_tx_hashes: Vec::new(),
+            target_block: 10,
+            max_blocks: 1,
+            replacement_uuid: None,
+        };
+        let receipt = |hash: TxHash, block_number: u64| TransactionReceipt::<ReceiptEnvelope> {
+            inner: ReceiptEnvelope::Legacy(ReceiptWithBloom::default()),
+            transaction_hash: hash,
+            transaction_index: Some(0),
+            block_hash: Some(B256::with_last_byte(block_number as u8)),
+            block_number: Some(block_number),
+            gas_used: 21_000,
+            effective_gas_price: 1,
+            blob_gas_used: None,
+            blob_gas_price: None,
+            from: Address::ZERO,
+            to: None,
+            contract_address: None,
+        };
+
+        // the victim transaction is mined without the bundle
+        chain.push_success(&U64::from(9));
+        chain.push_success(&U64::from(10));
+        chain.push_success(&receipt(victim, 10));
+        chain.push_success(&None::<()>);
+        builder.push_success(&serde_json::json!({ "bundleHash": TxHash::ZERO }));
+        let status = bundle.submit(&provider, &builders, PrivateKeySigner::random()).await.unwrap();
+        assert_eq!(status, BundleStatus::Expired { last_block: 10 });
+        assert!(chain.read_q().is_empty() && builder.read_q().is_empty());
+
+        // both transactions are mined in the target block
+        chain.push_success(&U64::from(9));
+        chain.push_success(&U64::from(10));
+        chain.push_success(&receipt(victim, 10));
+        chain.push_success(&receipt(backrun, 10));
+        builder.push_success(&serde_json::json!({ "bundleHash": TxHash::ZERO }));
+        let status = bundle.submit(&provider, &builders, PrivateKeySigner::random()).await.unwrap();
+        assert_eq!(status, BundleStatus::Included { block_number: 10 });
+        assert!(chain.read_q().is_empty() && builder.read_q().is_empty());
+    }
+
+    #[test]
+    fn block_range_saturates() {
+        let bundle = SignedBundle {
+            transactions: Vec::new(),
+            tx_hashes: Vec::new(),
+            reverting_tx_hashes: Vec::new(),
+            target_block: u64::MAX - 1,
+            max_blocks: 5,
+            replacement_uuid: None,
+        };
+        assert_eq!(bundle.last_block(), u64::MAX);
+        assert_eq!(SignedBundle { target_block: 0, max_blocks: 0, ..bundle }.last_block(), 0);
+    }
+}
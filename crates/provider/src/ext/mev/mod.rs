@@ -1,7 +1,22 @@
+mod bundle;
+mod share;
+#[cfg(all(feature = "reqwest", not(all(target_os = "wasi", target_env = "p1"))))]
+mod share_client;
 mod with_auth;
 
-pub use self::with_auth::{
-    sign_flashbots_payload, verify_flashbots_signature, FlashbotsSignatureError, MevBuilder,
+#[cfg(all(feature = "reqwest", not(all(target_os = "wasi", target_env = "p1"))))]
+pub use self::share_client::{
+    EventHistoryPages, MevShareClient, MevShareEventStream, FLASHBOTS_MEV_SHARE_URL,
+};
+pub use self::{
+    bundle::{
+        BundleBuilder, BundleError, BundleSimulation, BundleStatus, SignedBundle,
+        SimulatedTransaction,
+    },
+    share::{BackrunBuilder, DecodeHint},
+    with_auth::{
+        sign_flashbots_payload, verify_flashbots_signature, FlashbotsSignatureError, MevBuilder,
+    },
 };
 use crate::Provider;
 use alloy_network::Network;
@@ -0,0 +1,257 @@
+//! Decoding of MEV-Share hints, and backruns of the hinted transactions.
+//!
+//! See <https://docs.flashbots.net/flashbots-mev-share/searchers/understanding-bundles> for the
+//! `mev_sendBundle` bundle format.
+
+use alloy_primitives::{Bytes, TxHash};
+use alloy_rpc_types_mev::{
+    mevshare::{Event, EventTransaction, EventTransactionLog, Hint},
+    BundleItem, Inclusion, MevSendBundle, Privacy, ProtocolVersion, Refund, RefundConfig, Validity,
+};
+use alloy_sol_types::{SolCall, SolEvent};
+
+/// Decodes the logs and calls that MEV-Share hints at into `alloy-sol-types` types.
+///
+/// Hints only contain the fields the sender chose to share, so decoding succeeds only where the
+/// shared fields are sufficient, e.g. logs without data can't be decoded into events with
+/// non-indexed fields.
+pub trait DecodeHint {
+    /// Returns the hinted transactions.
+    fn hinted_transactions(&self) -> &[EventTransaction];
+
+    /// Returns the hinted logs.
+    fn hinted_logs(&self) -> &[EventTransactionLog];
+
+    /// Returns the hinted logs that decode as the event `E`.
+    fn decode_logs<E: SolEvent>(&self) -> Vec<E> {
+        self.hinted_logs()
+            .iter()
+            .filter_map(|log| E::decode_raw_log(log.topics.iter().copied(), &log.data).ok())
+            .collect()
+    }
+
+    /// Returns the hinted transactions that call the function `C`, by their calldata or function
+    /// selector.
+    fn calls<C: SolCall>(&self) -> impl Iterator<Item = &EventTransaction> {
+        self.hinted_transactions().iter().filter(|tx| {
+            tx.function_selector.is_some_and(|selector| selector == C::SELECTOR)
+                || tx.calldata.as_ref().is_some_and(|calldata| calldata.starts_with(&C::SELECTOR))
+        })
+    }
+
+    /// Returns the hinted calls of the function `C` whose calldata is shared.
+    fn decode_calls<C: SolCall>(&self) -> Vec<C> {
+        self.calls::<C>()
+            .filter_map(|tx| tx.calldata.as_ref())
+            .filter_map(|calldata| C::abi_decode(calldata).ok())
+            .collect()
+    }
+}
+
+impl DecodeHint for Event {
+    fn hinted_transactions(&self) -> &[EventTransaction] {
+        &self.transactions
+    }
+
+    fn hinted_logs(&self) -> &[EventTransactionLog] {
+        &self.logs
+    }
+}
+
+impl DecodeHint for Hint {
+    fn hinted_transactions(&self) -> &[EventTransaction] {
+        &self.txs
+    }
+
+    fn hinted_logs(&self) -> &[EventTransactionLog] {
+        &self.logs
+    }
+}
+
+/// A builder for `mev_sendBundle` requests that backrun a hinted transaction or bundle.
+///
+/// The hinted transaction is the first item of the bundle body, followed by the backrun
+/// transactions. The request can be sent with
+/// [`MevApi::send_mev_bundle`](super::MevApi::send_mev_bundle).
+#[derive(Clone, Debug)]
+pub struct BackrunBuilder {
+    hash: TxHash,
+    block: u64,
+    max_block: Option<u64>,
+    transactions: Vec<BundleItem>,
+    privacy: Option<Privacy>,
+    refund: Option<Vec<Refund>>,
+    refund_config: Option<Vec<RefundConfig>>,
+}
+
+impl BackrunBuilder {
+    /// Creates a backrun of the hinted transaction or bundle with the given hash, valid from the
+    /// given block.
+    pub const fn new(hash: TxHash, block: u64) -> Self {
+        Self {
+            hash,
+            block,
+            max_block: None,
+            transactions: Vec::new(),
+            privacy: None,
+            refund: None,
+            refund_config: None,
+        }
+    }
+
+    /// Creates a backrun of the given event, valid from the given block.
+    pub const fn for_event(event: &Event, block: u64) -> Self {
+        Self::new(event.hash, block)
+    }
+
+    /// Sets the last block the backrun is valid for.
+    pub const fn max_block(mut self, max_block: u64) -> Self {
+        self.max_block = Some(max_block);
+        self
+    }
+
+    /// Adds a signed backrun transaction that must not revert.
+    pub fn transaction(mut self, tx: impl Into<Bytes>) -> Self {
+        self.transactions.push(BundleItem::Tx { tx: tx.into(), can_revert: false });
+        self
+    }
+
+    /// Adds a signed backrun transaction that may revert.
+    pub fn reverting_transaction(mut self, tx: impl Into<Bytes>) -> Self {
+        self.transactions.push(BundleItem::Tx { tx: tx.into(), can_revert: true });
+        self
+    }
+
+    /// Sets which data of the backrun is shared, and with which builders.
+    pub fn privacy(mut self, privacy: Privacy) -> Self {
+        self.privacy = Some(privacy);
+        self
+    }
+
+    /// Sets the minimum percent of the earnings of the backrun that is refunded to the sender of
+    /// the hinted transaction.
+    pub fn refund_percent(mut self, percent: u64) -> Self {
+        self.refund = Some(vec![Refund { body_idx: 0, percent }]);
+        self
+    }
+
+    /// Sets which addresses receive which percent of the refund of the backrun, if it is itself
+    /// backrun.
+    pub fn refund_config(mut self, refund_config: Vec<RefundConfig>) -> Self {
+        self.refund_config = Some(refund_config);
+        self
+    }
+
+    /// Returns the `mev_sendBundle` request.
+    pub fn build(self) -> MevSendBundle {
+        let mut bundle_body = Vec::with_capacity(self.transactions.len() + 1);
+        bundle_body.push(BundleItem::Hash { hash: self.hash });
+        bundle_body.extend(self.transactions);
+
+        let validity = (self.refund.is_some() || self.refund_config.is_some()).then(|| {
+            Validity::default().with_refund(self.refund).with_refund_config(self.refund_config)
+        });
+        MevSendBundle {
+            protocol_version: ProtocolVersion::V0_1,
+            inclusion: Inclusion { block: self.block, max_block: self.max_block },
+            bundle_body,
+            validity,
+            privacy: self.privacy,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, Address, B256, U256};
+    use alloy_sol_types::sol;
+
+    sol! {
+        #[derive(Debug, PartialEq)]
+        event Transfer(address indexed from, address indexed to, uint256 value);
+        event Sync(uint112 reserve0, uint112 reserve1);
+        function swap(uint256 amount0Out, uint256 amount1Out, address to, bytes data);
+    }
+
+    #[test]
+    fn decode_hinted_logs() {
+        let from = address!("0x0000000000000000000000000000000000000001");
+        let to = Address::ZERO;
+        let transfer = Transfer { from, to, value: U256::from(5) };
+        let log = transfer.encode_log_data();
+        let event = Event {
+            hash: B256::ZERO,
+            transactions: Vec::new(),
+            logs: vec![
+                EventTransactionLog {
+                    address: Address::ZERO,
+                    topics: log.topics().to_vec(),
+                    data: log.data.clone(),
+                },
+                // hinted without data
+                EventTransactionLog {
+                    address: Address::ZERO,
+                    topics: vec![Sync::SIGNATURE_HASH],
+                    data: Bytes::new(),
+                },
+            ],
+        };
+
+        assert_eq!(event.decode_logs::<Transfer>(), vec![transfer]);
+        assert!(event.decode_logs::<Sync>().is_empty());
+    }
+
+    #[test]
+    fn decode_hinted_calls() {
+        let call = swapCall {
+            amount0Out: U256::from(1),
+            amount1Out: U256::ZERO,
+            to: Address::ZERO,
+            data: Bytes::new(),
+        };
+        let hinted = |function_selector, calldata| EventTransaction {
+            to: Some(Address::ZERO),
+            function_selector,
+            calldata,
+        };
+        let hint = Hint {
+            txs: vec![
+                hinted(Some(swapCall::SELECTOR.into()), None),
+                hinted(None, Some(call.abi_encode().into())),
+                hinted(Some([0; 4].into()), None),
+            ],
+            hash: B256::ZERO,
+            logs: Vec::new(),
+            gas_used: None,
+            mev_gas_price: None,
+        };
+
+        assert_eq!(hint.calls::<swapCall>().count(), 2);
+        assert_eq!(hint.decode_calls::<swapCall>().len(), 1);
+    }
+
+    #[test]
+    fn build_backrun() {
+        let hash = B256::with_last_byte(1);
+        let refund_config = vec![RefundConfig { address: Address::ZERO, percent: 100 }];
+        let bundle = BackrunBuilder::new(hash, 10)
+            .max_block(12)
+            .transaction(Bytes::from_static(&[1]))
+            .refund_percent(90)
+            .refund_config(refund_config.clone())
+            .build();
+
+        assert_eq!(bundle.inclusion, Inclusion { block: 10, max_block: Some(12) });
+        assert_eq!(
+            bundle.bundle_body,
+            vec![
+                BundleItem::Hash { hash },
+                BundleItem::Tx { tx: Bytes::from_static(&[1]), can_revert: false }
+            ]
+        );
+        let validity = bundle.validity.unwrap();
+        assert_eq!(validity.refund, Some(vec![Refund { body_idx: 0, percent: 90 }]));
+        assert_eq!(validity.refund_config, Some(refund_config));
+    }
+}
@@ -0,0 +1,246 @@
+//! A client for the event stream and event history of a MEV-Share node.
+//!
+//! See <https://docs.flashbots.net/flashbots-mev-share/searchers/event-stream> for the MEV-Share
+//! event stream specification.
+
+use alloy_rpc_types_mev::mevshare::{Event, EventHistory, EventHistoryInfo, EventHistoryParams};
+use alloy_transport::{TransportError, TransportErrorKind, TransportResult};
+use alloy_transport_http::sse::{SseEvent, SseParser};
+use reqwest::{header::ACCEPT, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use url::Url;
+
+/// The URL of the Flashbots MEV-Share event stream on mainnet.
+pub const FLASHBOTS_MEV_SHARE_URL: &str = "https://mev-share.flashbots.net";
+
+/// A client for the event stream and event history of a MEV-Share node.
+///
+/// Hints can be decoded with [`DecodeHint`](super::DecodeHint), and backrun with
+/// [`BackrunBuilder`](super::BackrunBuilder).
+///
+/// ```no_run
+/// # async fn example() -> alloy_transport::TransportResult<()> {
+/// use alloy_provider::ext::MevShareClient;
+///
+/// let mev_share = MevShareClient::flashbots();
+/// let mut events = mev_share.events().await?;
+/// while let Some(event) = events.next_event().await {
+///     println!("pending transaction {}", event?.hash);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MevShareClient {
+    client: reqwest::Client,
+    url: Url,
+}
+
+impl MevShareClient {
+    /// Creates a new client for the MEV-Share node at the given URL.
+    pub fn new(url: Url) -> Self {
+        Self { client: reqwest::Client::new(), url }
+    }
+
+    /// Creates a new client for the Flashbots MEV-Share node on mainnet.
+    pub fn flashbots() -> Self {
+        Self::new(FLASHBOTS_MEV_SHARE_URL.parse().expect("valid URL"))
+    }
+
+    /// Sets the underlying [`reqwest::Client`], e.g. to configure timeouts.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Returns the URL of the MEV-Share node.
+    pub const fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Subscribes to the hints of pending transactions and bundles.
+    pub async fn events(&self) -> TransportResult<MevShareEventStream> {
+        let request = self.get("").header(ACCEPT, "text/event-stream");
+        let response = send(request).await?;
+        Ok(MevShareEventStream { response, parser: SseParser::default() })
+    }
+
+    /// Returns the range and size of the event history.
+    pub async fn event_history_info(&self) -> TransportResult<EventHistoryInfo> {
+        send_json(self.get("/api/v1/history/info")).await
+    }
+
+    /// Returns the historic events matching the query.
+    ///
+    /// See also [`event_history_pages`](Self::event_history_pages) to fetch more events than the
+    /// node returns at once.
+    pub async fn event_history(
+        &self,
+        params: &EventHistoryParams,
+    ) -> TransportResult<Vec<EventHistory>> {
+        send_json(self.get("/api/v1/history").query(params)).await
+    }
+
+    /// Returns a paginator over the historic events matching the query.
+    ///
+    /// The `offset` of the query is advanced after every page.
+    pub const fn event_history_pages(&self, params: EventHistoryParams) -> EventHistoryPages<'_> {
+        EventHistoryPages { client: self, params, done: false }
+    }
+
+    fn get(&self, path: &str) -> RequestBuilder {
+        let mut url = self.url.clone();
+        let base = url.path().trim_end_matches('/').to_string();
+        url.set_path(&format!("{base}{path}"));
+        self.client.get(url)
+    }
+}
+
+async fn send(request: RequestBuilder) -> TransportResult<Response> {
+    let response = request.send().await.map_err(TransportErrorKind::custom)?;
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(TransportErrorKind::http_error(status.as_u16(), body))
+}
+
+async fn send_json<T: DeserializeOwned>(request: RequestBuilder) -> TransportResult<T> {
+    let body = send(request).await?.text().await.map_err(TransportErrorKind::custom)?;
+    serde_json::from_str(&body).map_err(|err| TransportError::deser_err(err, &body))
+}
+
+/// A stream of MEV-Share events, see [`MevShareClient::events`].
+#[derive(Debug)]
+pub struct MevShareEventStream {
+    response: Response,
+    parser: SseParser,
+}
+
+impl MevShareEventStream {
+    /// Waits for the next event, or returns `None` if the node closed the stream.
+    pub async fn next_event(&mut self) -> Option<TransportResult<Event>> {
+        loop {
+            if let Some(SseEvent { data, .. }) = self.parser.next_event() {
+                return Some(
+                    serde_json::from_str(&data)
+                        .map_err(|err| TransportError::deser_err(err, &data)),
+                );
+            }
+            match self.response.chunk().await {
+                Ok(Some(chunk)) => self.parser.push(&chunk),
+                Ok(None) => return None,
+                Err(err) => return Some(Err(TransportErrorKind::custom(err))),
+            }
+        }
+    }
+}
+
+/// Paginates over historic MEV-Share events, see [`MevShareClient::event_history_pages`].
+#[derive(Debug)]
+pub struct EventHistoryPages<'a> {
+    client: &'a MevShareClient,
+    params: EventHistoryParams,
+    done: bool,
+}
+
+impl EventHistoryPages<'_> {
+    /// Fetches the next page, or returns `None` if all events were fetched.
+    pub async fn next_page(&mut self) -> TransportResult<Option<Vec<EventHistory>>> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let page = self.client.event_history(&self.params).await?;
+        self.advance(page.len());
+        Ok((!page.is_empty()).then_some(page))
+    }
+
+    /// Advances the query past a page of `len` events, and stops after the last page.
+    fn advance(&mut self, len: usize) {
+        let is_last_page = self.params.limit.is_some_and(|limit| (len as u64) < limit);
+        if len == 0 || is_last_page {
+            self.done = true;
+        }
+        self.params.offset = Some(self.params.offset.unwrap_or_default() + len as u64);
+    }
+
+    /// Fetches pages until all events were fetched, or at least `max` events.
+    pub async fn collect(mut self, max: usize) -> TransportResult<Vec<EventHistory>> {
+        let mut events = Vec::new();
+        while events.len() < max {
+            match self.next_page().await? {
+                Some(page) => events.extend(page),
+                None => break,
+            }
+        }
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ext::DecodeHint;
+    use alloy_primitives::B256;
+    use alloy_sol_types::sol;
+
+    sol! {
+        function swap(uint256 amount0Out, uint256 amount1Out, address to, bytes data);
+    }
+
+    #[tokio::test]
+    async fn event_stream() {
+        let body = concat!(
+            ":ping\r\r",
+            "data: {\"hash\":\"0x0000000000000000000000000000000000000000000000000000000000000001\",",
+            "\"logs\":null,\"txs\":[{\"to\":\"0x0000000000000000000000000000000000000002\",",
+            "\"functionSelector\":\"0x022c0d9f\"}]}\r\n\r\n",
+        );
+        let response = Response::from(http::Response::new(body));
+        let mut events = MevShareEventStream { response, parser: SseParser::default() };
+
+        let event = events.next_event().await.unwrap().unwrap();
+        assert_eq!(event.hash, B256::with_last_byte(1));
+        assert_eq!(event.calls::<swapCall>().count(), 1);
+        assert!(event.decode_calls::<swapCall>().is_empty());
+        assert!(events.next_event().await.is_none());
+    }
+
+    #[test]
+    fn request_urls() {
+        let mev_share = MevShareClient::new("http://localhost:8080/mev-share/".parse().unwrap());
+        let request = mev_share.get("").header(ACCEPT, "text/event-stream").build().unwrap();
+        assert_eq!(request.url().as_str(), "http://localhost:8080/mev-share");
+        assert_eq!(request.headers()[ACCEPT], "text/event-stream");
+
+        let params = EventHistoryParams::default().with_block_start(1).with_limit(2).with_offset(2);
+        let request = mev_share.get("/api/v1/history").query(&params).build().unwrap();
+        assert_eq!(
+            request.url().as_str(),
+            "http://localhost:8080/mev-share/api/v1/history?blockStart=1&limit=2&offset=2"
+        );
+    }
+
+    #[test]
+    fn paginate_event_history() {
+        let mev_share = MevShareClient::flashbots();
+        let params = EventHistoryParams::default().with_block_start(1).with_limit(2);
+        let mut pages = mev_share.event_history_pages(params);
+
+        pages.advance(2);
+        assert!(!pages.done);
+        assert_eq!(pages.params.offset, Some(2));
+
+        pages.advance(1);
+        assert!(pages.done);
+        assert_eq!(pages.params.offset, Some(3));
+
+        let mut pages = mev_share.event_history_pages(EventHistoryParams::default());
+        pages.advance(100);
+        assert!(!pages.done);
+        pages.advance(0);
+        assert!(pages.done);
+    }
+}
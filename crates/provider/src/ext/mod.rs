@@ -10,11 +10,22 @@ mod anvil;
 #[cfg(feature = "anvil-api")]
 pub use anvil::{AnvilApi, ImpersonateConfig};
 
+mod blob_fee;
+pub use blob_fee::{BlobFeeApi, BlobFeeError, BlobFeePolicy};
+
+mod eip7702;
+pub use eip7702::{DelegationApi, DelegationError, Sponsor};
+
 #[cfg(feature = "engine-api")]
 mod engine;
 #[cfg(feature = "engine-api")]
 pub use engine::EngineApi;
 
+#[cfg(feature = "engine-api")]
+mod engine_driver;
+#[cfg(feature = "engine-api")]
+pub use engine_driver::{EngineDriver, EngineDriverError};
+
 #[cfg(feature = "engine-api")]
 mod testing;
 #[cfg(feature = "engine-api")]
@@ -30,10 +41,18 @@ mod net;
 #[cfg(feature = "net-api")]
 pub use net::NetApi;
 
+mod signature;
+pub use signature::{
+    Erc6492Signature, SignatureVerificationApi, SignatureVerificationError, ERC1271_MAGIC_VALUE,
+    ERC6492_MAGIC_SUFFIX,
+};
+
 #[cfg(feature = "trace-api")]
 mod trace;
 #[cfg(feature = "trace-api")]
 pub use trace::{TraceApi, TraceBuilder, TraceCallList, TraceParams};
+#[cfg(all(feature = "trace-api", feature = "debug-api"))]
+pub use trace::{TraceBackend, TraceProvider};
 
 #[cfg(feature = "rpc-api")]
 mod rpc;
@@ -48,7 +67,15 @@ pub use txpool::TxPoolApi;
 #[cfg(feature = "erc4337-api")]
 mod erc4337;
 #[cfg(feature = "erc4337-api")]
-pub use erc4337::Erc4337Api;
+pub use erc4337::{
+    EntryPointVersion, Erc4337Api, LocalBundler, PaymasterHook, PendingUserOperation,
+    UserOperationBuilder, UserOperationError,
+};
+
+#[cfg(feature = "wallet-api")]
+mod wallet;
+#[cfg(feature = "wallet-api")]
+pub use wallet::{PendingCalls, WalletApi, WalletCallsError};
 
 #[cfg(feature = "tenderly-api")]
 mod tenderly;
@@ -65,9 +92,16 @@ mod mev;
 
 #[cfg(feature = "mev-api")]
 pub use mev::{
-    sign_flashbots_payload, verify_flashbots_signature, FlashbotsSignatureError, MevApi,
-    MevBuilder, FLASHBOTS_SIGNATURE_HEADER,
+    sign_flashbots_payload, verify_flashbots_signature, BackrunBuilder, BundleBuilder, BundleError,
+    BundleSimulation, BundleStatus, DecodeHint, FlashbotsSignatureError, MevApi, MevBuilder,
+    SignedBundle, SimulatedTransaction, FLASHBOTS_SIGNATURE_HEADER,
 };
+#[cfg(all(
+    feature = "mev-api",
+    feature = "reqwest",
+    not(all(target_os = "wasi", target_env = "p1"))
+))]
+pub use mev::{EventHistoryPages, MevShareClient, MevShareEventStream, FLASHBOTS_MEV_SHARE_URL};
 
 /// Reth related apis.
 pub mod reth;
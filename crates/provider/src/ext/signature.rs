@@ -0,0 +1,494 @@
+//! This module extends the Ethereum JSON-RPC provider with signature verification for externally
+//! owned accounts as well as [ERC-1271] and [ERC-6492] smart accounts.
+//!
+//! [ERC-1271]: https://eips.ethereum.org/EIPS/eip-1271
+//! [ERC-6492]: https://eips.ethereum.org/EIPS/eip-6492
+
+use crate::Provider;
+use alloy_eips::eip7702::delegation_address;
+use alloy_json_rpc::RpcError;
+use alloy_network::{Network, TransactionBuilder};
+use alloy_primitives::{b256, eip191_hash_message, Address, Bytes, Signature, B256};
+use alloy_signer::siwe::{SiweError, SiweMessage, SiweVerificationOpts};
+use alloy_sol_types::{sol, Eip712Domain, SolCall, SolStruct, SolValue};
+use alloy_transport::TransportError;
+
+sol! {
+    interface IERC1271 {
+        function isValidSignature(bytes32 hash, bytes signature) external view returns (bytes4 magicValue);
+    }
+}
+
+/// The value returned by `isValidSignature` if the signature is valid.
+pub const ERC1271_MAGIC_VALUE: [u8; 4] = IERC1271::isValidSignatureCall::SELECTOR;
+
+/// The suffix of signatures wrapped according to [ERC-6492].
+///
+/// [ERC-6492]: https://eips.ethereum.org/EIPS/eip-6492
+pub const ERC6492_MAGIC_SUFFIX: B256 =
+    b256!("0x6492649264926492649264926492649264926492649264926492649264926492");
+
+/// Error returned by [`SignatureVerificationApi`] methods.
+#[derive(Debug, thiserror::Error)]
+pub enum SignatureVerificationError {
+    /// The signature has the [`ERC6492_MAGIC_SUFFIX`] but could not be decoded.
+    #[error("invalid ERC-6492 signature: {0}")]
+    InvalidErc6492(#[from] alloy_sol_types::Error),
+    /// The ERC-6492 factory calldata and signature are too large to be validated in a single
+    /// deployless call.
+    #[error("ERC-6492 signature is too large to be validated")]
+    Erc6492TooLarge,
+    /// The Sign-In with Ethereum message is not valid.
+    #[error(transparent)]
+    Siwe(#[from] SiweError),
+    /// A request to the node failed.
+    #[error(transparent)]
+    Transport(#[from] TransportError),
+}
+
+/// A signature of a counterfactual smart account, wrapped according to [ERC-6492].
+///
+/// The wrapper contains everything needed to deploy the account, so the signature can be validated
+/// with [ERC-1271] before the account exists onchain.
+///
+/// [ERC-1271]: https://eips.ethereum.org/EIPS/eip-1271
+/// [ERC-6492]: https://eips.ethereum.org/EIPS/eip-6492
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Erc6492Signature {
+    /// The factory that deploys the account.
+    pub factory: Address,
+    /// The calldata passed to the factory to deploy the account.
+    pub factory_calldata: Bytes,
+    /// The signature to validate with the deployed account.
+    pub signature: Bytes,
+}
+
+impl Erc6492Signature {
+    /// Returns `true` if the signature ends with the [`ERC6492_MAGIC_SUFFIX`].
+    pub fn is_wrapped(signature: &[u8]) -> bool {
+        signature.ends_with(ERC6492_MAGIC_SUFFIX.as_slice())
+    }
+
+    /// Decodes a wrapped signature, ignoring the [`ERC6492_MAGIC_SUFFIX`] if present.
+    pub fn decode(signature: &[u8]) -> Result<Self, alloy_sol_types::Error> {
+        let data = signature.strip_suffix(ERC6492_MAGIC_SUFFIX.as_slice()).unwrap_or(signature);
+        let (factory, factory_calldata, signature) =
+            <(Address, Bytes, Bytes)>::abi_decode_params(data)?;
+        Ok(Self { factory, factory_calldata, signature })
+    }
+
+    /// Encodes the wrapped signature, including the [`ERC6492_MAGIC_SUFFIX`].
+    pub fn encode(&self) -> Bytes {
+        let mut out = (self.factory, self.factory_calldata.clone(), self.signature.clone())
+            .abi_encode_params();
+        out.extend_from_slice(ERC6492_MAGIC_SUFFIX.as_slice());
+        out.into()
+    }
+}
+
+/// Signature verification for any kind of account.
+///
+/// Signatures are checked the way [ERC-6492] describes:
+/// - signatures with the [`ERC6492_MAGIC_SUFFIX`] are validated by running the factory call and
+///   then calling `isValidSignature` within a single deployless `eth_call`. If the account is
+///   already deployed, the unwrapped signature is checked first without the factory call;
+/// - accounts with code are validated by calling `isValidSignature` as defined in [ERC-1271];
+/// - all other accounts are validated by recovering the signer of the signature.
+///
+/// Accounts delegated with EIP-7702 are first checked by recovering the signer, and then with
+/// [ERC-1271].
+///
+/// [ERC-1271]: https://eips.ethereum.org/EIPS/eip-1271
+/// [ERC-6492]: https://eips.ethereum.org/EIPS/eip-6492
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+pub trait SignatureVerificationApi<N: Network>: Send + Sync {
+    /// Returns `true` if `signature` is a valid signature of `signer` over the given hash.
+    async fn verify_hash(
+        &self,
+        signer: Address,
+        hash: B256,
+        signature: &[u8],
+    ) -> Result<bool, SignatureVerificationError>;
+
+    /// Returns `true` if `signature` is a valid [EIP-191] signature of `signer` over the given
+    /// message.
+    ///
+    /// [EIP-191]: https://eips.ethereum.org/EIPS/eip-191
+    async fn verify_message(
+        &self,
+        signer: Address,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<bool, SignatureVerificationError> {
+        self.verify_hash(signer, eip191_hash_message(message), signature).await
+    }
+
+    /// Returns `true` if `signature` is a valid [EIP-712] signature of `signer` over the given
+    /// typed data.
+    ///
+    /// [EIP-712]: https://eips.ethereum.org/EIPS/eip-712
+    async fn verify_typed_data<T: SolStruct + Send + Sync>(
+        &self,
+        signer: Address,
+        payload: &T,
+        domain: &Eip712Domain,
+        signature: &[u8],
+    ) -> Result<bool, SignatureVerificationError> {
+        self.verify_hash(signer, payload.eip712_signing_hash(domain), signature).await
+    }
+
+    /// Validates a Sign-In with Ethereum message against `opts`, and returns `true` if
+    /// `signature` is a valid signature of the message by its address.
+    ///
+    /// Unlike [`SiweMessage::verify_eoa`], this also accepts signatures of smart accounts.
+    async fn verify_siwe(
+        &self,
+        message: &SiweMessage,
+        signature: &[u8],
+        opts: &SiweVerificationOpts,
+    ) -> Result<bool, SignatureVerificationError> {
+        message.validate(opts)?;
+        self.verify_hash(message.address, message.eip191_hash(), signature).await
+    }
+}
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl<N, P> SignatureVerificationApi<N> for P
+where
+    N: Network,
+    P: Provider<N>,
+{
+    async fn verify_hash(
+        &self,
+        signer: Address,
+        hash: B256,
+        signature: &[u8],
+    ) -> Result<bool, SignatureVerificationError> {
+        let code = self.get_code_at(signer).await?;
+
+        if Erc6492Signature::is_wrapped(signature) {
+            let wrapped = Erc6492Signature::decode(signature)?;
+            // a deployed account may need the prepare call to become able to validate the
+            // signature, e.g. to upgrade its signers, so retry with it if the plain check fails
+            if !code.is_empty()
+                && verify_erc1271(self, signer, hash, wrapped.signature.clone()).await?
+            {
+                return Ok(true);
+            }
+            let code = deployless_validator(signer, hash, &wrapped)
+                .ok_or(SignatureVerificationError::Erc6492TooLarge)?;
+            let tx = N::TransactionRequest::default().with_deploy_code(code);
+            return Ok(is_magic_value(&call_or_revert(self.call(tx).await)?));
+        }
+
+        if code.is_empty() || delegation_address(&code).is_some() {
+            if ecrecover(hash, signature) == Some(signer) {
+                return Ok(true);
+            }
+            if code.is_empty() {
+                return Ok(false);
+            }
+        }
+
+        verify_erc1271(self, signer, hash, Bytes::copy_from_slice(signature)).await
+    }
+}
+
+/// Recovers the signer of a 65-byte or [EIP-2098] compact signature.
+///
+/// [EIP-2098]: https://eips.ethereum.org/EIPS/eip-2098
+fn ecrecover(hash: B256, signature: &[u8]) -> Option<Address> {
+    let signature = match signature.len() {
+        64 => Signature::from_erc2098(signature),
+        _ => Signature::try_from(signature).ok()?,
+    };
+    signature.recover_address_from_prehash(&hash).ok()
+}
+
+async fn verify_erc1271<N, P>(
+    provider: &P,
+    account: Address,
+    hash: B256,
+    signature: Bytes,
+) -> Result<bool, SignatureVerificationError>
+where
+    N: Network,
+    P: Provider<N>,
+{
+    let input = IERC1271::isValidSignatureCall { hash, signature }.abi_encode();
+    let tx = N::TransactionRequest::default().with_to(account).with_input(input);
+    Ok(is_magic_value(&call_or_revert(provider.call(tx).await)?))
+}
+
+/// Treats a reverted call as an empty result, since accounts may revert on invalid signatures.
+///
+/// All other errors, e.g. rate limits or unsupported methods, are returned.
+fn call_or_revert(result: Result<Bytes, TransportError>) -> Result<Bytes, TransportError> {
+    match result {
+        Err(RpcError::ErrorResp(err))
+            if err.code == 3
+                || err.message.contains("revert")
+                || err.as_revert_data().is_some() =>
+        {
+            Ok(Bytes::new())
+        }
+        result => result,
+    }
+}
+
+fn is_magic_value(output: &[u8]) -> bool {
+    output.get(..4) == Some(ERC1271_MAGIC_VALUE.as_slice())
+}
+
+/// Builds the init code of a contract that deploys the account through the ERC-6492 factory,
+/// calls `isValidSignature` on it and returns the 32-byte result, or zero if the call reverted.
+///
+/// The factory calldata and the `isValidSignature` calldata are appended to the code and copied
+/// into memory before the calls are made. Returns `None` if the calldata does not fit into the
+/// 16-bit offsets used by the code.
+fn deployless_validator(account: Address, hash: B256, wrapped: &Erc6492Signature) -> Option<Bytes> {
+    const CODE_LEN: u16 = 93;
+
+    let validate =
+        IERC1271::isValidSignatureCall { hash, signature: wrapped.signature.clone() }.abi_encode();
+    let factory_len = u16::try_from(wrapped.factory_calldata.len()).ok()?;
+    let validate_len = u16::try_from(validate.len()).ok()?;
+    let data_len = factory_len.checked_add(validate_len)?;
+
+    let push2 = |value: u16| {
+        let [hi, lo] = value.to_be_bytes();
+        [0x61, hi, lo]
+    };
+
+    let mut code = Vec::with_capacity(CODE_LEN as usize + data_len as usize);
+    // codecopy(0, CODE_LEN, data_len)
+    code.extend(push2(data_len));
+    code.extend(push2(CODE_LEN));
+    code.extend([0x60, 0x00, 0x39]);
+    // pop(call(gas(), factory, 0, 0, factory_len, 0, 0))
+    code.extend([0x60, 0x00, 0x60, 0x00]);
+    code.extend(push2(factory_len));
+    code.extend([0x60, 0x00, 0x60, 0x00, 0x73]);
+    code.extend(wrapped.factory);
+    code.extend([0x5a, 0xf1, 0x50]);
+    // success := staticcall(gas(), account, factory_len, validate_len, data_len, 32)
+    code.extend([0x60, 0x20]);
+    code.extend(push2(data_len));
+    code.extend(push2(validate_len));
+    code.extend(push2(factory_len));
+    code.push(0x73);
+    code.extend(account);
+    code.extend([0x5a, 0xfa]);
+    // mstore(data_len, mul(mload(data_len), success))
+    code.extend(push2(data_len));
+    code.extend([0x51, 0x02]);
+    code.extend(push2(data_len));
+    code.push(0x52);
+    // return(data_len, 32)
+    code.extend([0x60, 0x20]);
+    code.extend(push2(data_len));
+    code.push(0xf3);
+    debug_assert_eq!(code.len(), CODE_LEN as usize);
+
+    code.extend_from_slice(&wrapped.factory_calldata);
+    code.extend(validate);
+    Some(code.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mock::Asserter, ProviderBuilder};
+    use alloy_eips::eip7702::constants::EIP7702_DELEGATION_DESIGNATOR;
+    use alloy_json_rpc::ErrorPayload;
+    use alloy_signer::SignerSync;
+    use alloy_signer_local::PrivateKeySigner;
+
+    fn magic_word() -> B256 {
+        let mut word = B256::ZERO;
+        word[..4].copy_from_slice(&ERC1271_MAGIC_VALUE);
+        word
+    }
+
+    #[tokio::test]
+    async fn verify_eoa() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let signer = PrivateKeySigner::random();
+        let signature = signer.sign_message_sync(b"hello").unwrap().as_bytes();
+
+        asserter.push_success(&Bytes::new());
+        assert!(provider.verify_message(signer.address(), b"hello", &signature).await.unwrap());
+
+        asserter.push_success(&Bytes::new());
+        assert!(!provider.verify_message(signer.address(), b"world", &signature).await.unwrap());
+
+        let compact = signer.sign_message_sync(b"hello").unwrap().as_erc2098();
+        asserter.push_success(&Bytes::new());
+        assert!(provider.verify_message(signer.address(), b"hello", &compact).await.unwrap());
+        assert!(asserter.read_q().is_empty());
+    }
+
+    #[tokio::test]
+    async fn verify_delegated_eoa() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let signer = PrivateKeySigner::random();
+        let signature = signer.sign_message_sync(b"hello").unwrap().as_bytes();
+        let code = Bytes::from(
+            [EIP7702_DELEGATION_DESIGNATOR.as_slice(), Address::random().as_slice()].concat(),
+        );
+
+        asserter.push_success(&code);
+        assert!(provider.verify_message(signer.address(), b"hello", &signature).await.unwrap());
+        assert!(asserter.read_q().is_empty());
+
+        // falls back to the delegate's `isValidSignature`
+        asserter.push_success(&code);
+        asserter.push_success(&magic_word());
+        assert!(provider.verify_message(signer.address(), b"world", &signature).await.unwrap());
+        assert!(asserter.read_q().is_empty());
+    }
+
+    #[tokio::test]
+    async fn verify_erc1271() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let account = Address::random();
+
+        asserter.push_success(&Bytes::from_static(&[0x60, 0x00]));
+        asserter.push_success(&magic_word());
+        assert!(provider.verify_hash(account, B256::random(), &[0x01; 65]).await.unwrap());
+
+        asserter.push_success(&Bytes::from_static(&[0x60, 0x00]));
+        asserter.push_success(&B256::ZERO);
+        assert!(!provider.verify_hash(account, B256::random(), &[0x01; 65]).await.unwrap());
+
+        asserter.push_success(&Bytes::from_static(&[0x60, 0x00]));
+        asserter.push_failure_msg("execution reverted");
+        assert!(!provider.verify_hash(account, B256::random(), &[0x01; 65]).await.unwrap());
+
+        asserter.push_success(&Bytes::from_static(&[0x60, 0x00]));
+        asserter.push_failure(ErrorPayload {
+            code: 3,
+            message: "execution reverted: invalid signer".into(),
+            data: None,
+        });
+        assert!(!provider.verify_hash(account, B256::random(), &[0x01; 65]).await.unwrap());
+        assert!(asserter.read_q().is_empty());
+    }
+
+    #[tokio::test]
+    async fn verify_erc1271_rpc_errors() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let account = Address::random();
+
+        asserter.push_success(&Bytes::from_static(&[0x60, 0x00]));
+        asserter.push_failure(ErrorPayload {
+            code: 429,
+            message: "rate limit exceeded".into(),
+            data: None,
+        });
+        let err = provider.verify_hash(account, B256::random(), &[0x01; 65]).await.unwrap_err();
+        assert!(matches!(
+            err,
+            SignatureVerificationError::Transport(RpcError::ErrorResp(ref e)) if e.code == 429
+        ));
+
+        asserter.push_success(&Bytes::from_static(&[0x60, 0x00]));
+        asserter.push_failure(ErrorPayload::method_not_found());
+        let err = provider.verify_hash(account, B256::random(), &[0x01; 65]).await.unwrap_err();
+        assert!(matches!(
+            err,
+            SignatureVerificationError::Transport(RpcError::ErrorResp(ref e)) if e.code == -32601
+        ));
+        assert!(asserter.read_q().is_empty());
+    }
+
+    #[tokio::test]
+    async fn verify_erc6492() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let wrapped = Erc6492Signature {
+            factory: Address::random(),
+            factory_calldata: Bytes::from_static(&[0xde, 0xad, 0xbe, 0xef]),
+            signature: Bytes::from_static(&[0x01; 65]),
+        };
+        let signature = wrapped.encode();
+        assert!(Erc6492Signature::is_wrapped(&signature));
+        assert_eq!(Erc6492Signature::decode(&signature).unwrap(), wrapped);
+
+        // counterfactual account, validated with a deployless call
+        asserter.push_success(&Bytes::new());
+        asserter.push_success(&magic_word());
+        assert!(provider.verify_hash(Address::random(), B256::random(), &signature).await.unwrap());
+
+        // deployed account, validated with the unwrapped signature
+        asserter.push_success(&Bytes::from_static(&[0x60, 0x00]));
+        asserter.push_success(&magic_word());
+        assert!(provider.verify_hash(Address::random(), B256::random(), &signature).await.unwrap());
+        assert!(asserter.read_q().is_empty());
+
+        // deployed account, validated after the prepare call
+        asserter.push_success(&Bytes::from_static(&[0x60, 0x00]));
+        asserter.push_success(&B256::ZERO);
+        asserter.push_success(&magic_word());
+        assert!(provider.verify_hash(Address::random(), B256::random(), &signature).await.unwrap());
+        assert!(asserter.read_q().is_empty());
+
+        // deployed account, invalid even after the prepare call
+        asserter.push_success(&Bytes::from_static(&[0x60, 0x00]));
+        asserter.push_success(&B256::ZERO);
+        asserter.push_success(&B256::ZERO);
+        assert!(!provider
+            .verify_hash(Address::random(), B256::random(), &signature)
+            .await
+            .unwrap());
+        assert!(asserter.read_q().is_empty());
+    }
+
+    #[tokio::test]
+    async fn verify_siwe() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let account = Address::random();
+        let message =
+            SiweMessage::new("example.com", account, "https://example.com", 1, "abcdefgh");
+        let opts = SiweVerificationOpts { chain_id: Some(1), ..Default::default() };
+
+        asserter.push_success(&Bytes::from_static(&[0x60, 0x00]));
+        asserter.push_success(&magic_word());
+        assert!(provider.verify_siwe(&message, &[0x01; 65], &opts).await.unwrap());
+        assert!(asserter.read_q().is_empty());
+
+        let opts = SiweVerificationOpts { chain_id: Some(10), ..Default::default() };
+        let err = provider.verify_siwe(&message, &[0x01; 65], &opts).await.unwrap_err();
+        assert!(matches!(err, SignatureVerificationError::Siwe(SiweError::ChainIdMismatch { .. })));
+    }
+
+    #[test]
+    fn deployless_validator_layout() {
+        let account = Address::random();
+        let hash = B256::random();
+        let wrapped = Erc6492Signature {
+            factory: Address::random(),
+            factory_calldata: Bytes::from_static(&[0xde, 0xad, 0xbe, 0xef]),
+            signature: Bytes::from_static(&[0x01; 65]),
+        };
+        let code = deployless_validator(account, hash, &wrapped).unwrap();
+        let validate =
+            IERC1271::isValidSignatureCall { hash, signature: wrapped.signature.clone() }
+                .abi_encode();
+
+        assert_eq!(&code[21..41], wrapped.factory.as_slice());
+        assert_eq!(&code[56..76], account.as_slice());
+        assert_eq!(&code[93..97], wrapped.factory_calldata.as_ref());
+        assert_eq!(&code[97..], validate.as_slice());
+
+        let wrapped = Erc6492Signature { factory_calldata: vec![0; 65536].into(), ..wrapped };
+        assert!(deployless_validator(account, hash, &wrapped).is_none());
+    }
+}
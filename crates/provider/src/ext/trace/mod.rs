@@ -13,6 +13,11 @@ use alloy_transport::TransportResult;
 mod with_block;
 pub use with_block::{TraceBuilder, TraceParams};
 
+#[cfg(feature = "debug-api")]
+mod unified;
+#[cfg(feature = "debug-api")]
+pub use unified::{TraceBackend, TraceProvider};
+
 /// List of trace calls for use with [`TraceApi::trace_call_many`]
 pub type TraceCallList<'a, N> = &'a [(<N as Network>::TransactionRequest, &'a [TraceType])];
 
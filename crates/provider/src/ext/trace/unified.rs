@@ -0,0 +1,188 @@
+//! A tracing abstraction over the parity `trace` and geth `debug` namespaces.
+use crate::{
+    ext::{DebugApi, TraceApi},
+    Provider,
+};
+use alloy_network::{Ethereum, Network};
+use alloy_primitives::TxHash;
+use alloy_rpc_types_trace::{
+    geth::{CallConfig, CallFrame, GethDebugTracingOptions, PreStateConfig, PreStateFrame},
+    otterscan::TraceEntry,
+    parity::{StateDiff, TransactionTrace},
+};
+use alloy_transport::{TransportErrorKind, TransportResult};
+use std::{future::Future, marker::PhantomData, sync::OnceLock};
+
+/// The tracing API used by a [`TraceProvider`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraceBackend {
+    /// The parity `trace` namespace, e.g. of reth, erigon or nethermind.
+    Parity,
+    /// The geth `debug` namespace with the built-in tracers.
+    Geth,
+}
+
+/// Traces transactions with whichever tracing API the node supports, and normalizes the results.
+///
+/// The parity `trace` namespace is tried first. If the node doesn't support it, the geth `debug`
+/// namespace is used instead. The backend that worked is remembered for all later requests.
+///
+/// ```ignore
+/// let tracer = TraceProvider::new(provider);
+/// let call_tree = tracer.call_trace(tx_hash).await?;
+/// let state_diff = tracer.state_diff(tx_hash).await?;
+/// ```
+#[derive(Debug)]
+pub struct TraceProvider<P, N = Ethereum> {
+    provider: P,
+    backend: OnceLock<TraceBackend>,
+    _network: PhantomData<N>,
+}
+
+impl<P, N> TraceProvider<P, N>
+where
+    N: Network,
+    P: Provider<N>,
+{
+    /// Creates a new trace provider that detects the tracing API on first use.
+    pub const fn new(provider: P) -> Self {
+        Self { provider, backend: OnceLock::new(), _network: PhantomData }
+    }
+
+    /// Uses the given tracing API instead of detecting it.
+    pub fn with_backend(self, backend: TraceBackend) -> Self {
+        Self { backend: OnceLock::from(backend), ..self }
+    }
+
+    /// Returns the underlying provider.
+    pub const fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Returns the tracing API in use, if it has been detected yet.
+    pub fn backend(&self) -> Option<TraceBackend> {
+        self.backend.get().copied()
+    }
+
+    /// Returns the call tree of the transaction, in geth `callTracer` format.
+    pub async fn call_trace(&self, hash: TxHash) -> TransportResult<CallFrame> {
+        self.dispatch(
+            async {
+                let traces = self.provider.trace_transaction(hash).await?;
+                CallFrame::from_parity_traces(traces.iter().map(|trace| &trace.trace))
+                    .ok_or_else(|| TransportErrorKind::custom_str("transaction has no traces"))
+            },
+            self.geth_call_trace(hash),
+        )
+        .await
+    }
+
+    /// Returns the flat traces of the transaction, in parity `trace_transaction` format.
+    pub async fn parity_traces(&self, hash: TxHash) -> TransportResult<Vec<TransactionTrace>> {
+        self.dispatch(
+            async {
+                let traces = self.provider.trace_transaction(hash).await?;
+                Ok(traces.into_iter().map(|trace| trace.trace).collect())
+            },
+            async { Ok(self.geth_call_trace(hash).await?.to_parity_traces()) },
+        )
+        .await
+    }
+
+    /// Returns the calls of the transaction, in otterscan `ots_traceTransaction` format.
+    pub async fn otterscan_trace(&self, hash: TxHash) -> TransportResult<Vec<TraceEntry>> {
+        Ok(TraceEntry::from_call_frame(&self.call_trace(hash).await?))
+    }
+
+    /// Returns the state changes of the transaction, in parity `stateDiff` format.
+    pub async fn state_diff(&self, hash: TxHash) -> TransportResult<StateDiff> {
+        self.dispatch(
+            async {
+                let results = self.provider.trace_replay_transaction(hash).state_diff().await?;
+                Ok(results.state_diff.unwrap_or_default())
+            },
+            async {
+                let options = GethDebugTracingOptions::prestate_tracer(PreStateConfig {
+                    diff_mode: Some(true),
+                    ..Default::default()
+                });
+                let trace = self.provider.debug_trace_transaction(hash, options).await?;
+                match trace.try_into_pre_state_frame().map_err(TransportErrorKind::custom)? {
+                    PreStateFrame::Diff(diff) => Ok(diff.into()),
+                    PreStateFrame::Default(_) => {
+                        Err(TransportErrorKind::custom_str("prestate tracer ignored diff mode"))
+                    }
+                }
+            },
+        )
+        .await
+    }
+
+    async fn geth_call_trace(&self, hash: TxHash) -> TransportResult<CallFrame> {
+        let options = GethDebugTracingOptions::call_tracer(CallConfig::default().with_log());
+        let trace = self.provider.debug_trace_transaction(hash, options).await?;
+        trace.try_into_call_frame().map_err(TransportErrorKind::custom)
+    }
+
+    /// Runs the request of the detected backend, or detects the backend by trying the parity
+    /// request first and falling back to the geth request if the method is not found.
+    async fn dispatch<T>(
+        &self,
+        parity: impl Future<Output = TransportResult<T>>,
+        geth: impl Future<Output = TransportResult<T>>,
+    ) -> TransportResult<T> {
+        match self.backend() {
+            Some(TraceBackend::Parity) => parity.await,
+            Some(TraceBackend::Geth) => geth.await,
+            None => match parity.await {
+                Err(err) if err.as_error_resp().is_some_and(|e| e.code == -32601) => {
+                    let result = geth.await;
+                    if result.is_ok() {
+                        let _ = self.backend.set(TraceBackend::Geth);
+                    }
+                    result
+                }
+                result => {
+                    if result.is_ok() {
+                        let _ = self.backend.set(TraceBackend::Parity);
+                    }
+                    result
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProviderBuilder;
+    use alloy_json_rpc::ErrorPayload;
+    use alloy_primitives::{Address, U256};
+    use alloy_transport::mock::Asserter;
+
+    #[tokio::test]
+    async fn falls_back_to_geth() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let tracer = TraceProvider::new(provider);
+        let frame = CallFrame {
+            from: Address::with_last_byte(1),
+            to: Some(Address::with_last_byte(2)),
+            value: Some(U256::ZERO),
+            typ: "CALL".to_string(),
+            calls: vec![CallFrame { typ: "STATICCALL".to_string(), ..Default::default() }],
+            ..Default::default()
+        };
+
+        asserter.push_failure(ErrorPayload::method_not_found());
+        asserter.push_success(&frame);
+        assert_eq!(tracer.call_trace(TxHash::ZERO).await.unwrap(), frame);
+        assert_eq!(tracer.backend(), Some(TraceBackend::Geth));
+
+        asserter.push_success(&frame);
+        let traces = tracer.parity_traces(TxHash::ZERO).await.unwrap();
+        assert_eq!(traces, frame.to_parity_traces());
+        assert!(asserter.read_q().is_empty());
+    }
+}
@@ -0,0 +1,260 @@
+//! This module extends the Ethereum JSON-RPC provider with the Wallet Call API namespace's RPC
+//! methods.
+use crate::{utils::poll_until, Provider, RootProvider};
+use alloy_eip5792::{CallsStatus, SendCallsRequest, SendCallsResponse, WalletCapabilities};
+use alloy_network::Network;
+use alloy_primitives::{Address, ChainId, U64};
+use alloy_transport::{TransportError, TransportResult};
+use std::{ops::ControlFlow, time::Duration};
+
+/// Errors that may occur when sending a batch of calls with [`WalletApi::send_calls`].
+#[derive(Debug, thiserror::Error)]
+pub enum WalletCallsError {
+    /// Underlying transport error.
+    #[error(transparent)]
+    Transport(#[from] TransportError),
+    /// The wallet did not report any capabilities for the chain.
+    #[error("wallet reported no capabilities for chain {0}")]
+    MissingCapabilities(ChainId),
+    /// Atomic execution was required, but the wallet does not support it.
+    #[error("wallet does not support atomic execution on chain {0}")]
+    AtomicUnsupported(ChainId),
+    /// A paymaster service was requested, but the wallet does not support it.
+    #[error("wallet does not support paymaster services on chain {0}")]
+    PaymasterServiceUnsupported(ChainId),
+    /// The batch did not complete before the timeout elapsed.
+    #[error("calls {0} did not complete before the timeout")]
+    Timeout(String),
+}
+
+/// Wallet Call API namespace rpc interface, as defined in [EIP-5792].
+///
+/// [EIP-5792]: https://eips.ethereum.org/EIPS/eip-5792
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+pub trait WalletApi<N: Network>: Send + Sync {
+    /// Returns the capabilities of the wallet for the given account, optionally restricted to the
+    /// given chains.
+    async fn wallet_get_capabilities(
+        &self,
+        account: Address,
+        chain_ids: Option<Vec<ChainId>>,
+    ) -> TransportResult<WalletCapabilities>;
+
+    /// Submits a batch of calls to the wallet and returns the batch identifier.
+    async fn wallet_send_calls(
+        &self,
+        request: SendCallsRequest,
+    ) -> TransportResult<SendCallsResponse>;
+
+    /// Returns the status of a batch of calls.
+    async fn wallet_get_calls_status(&self, id: String) -> TransportResult<CallsStatus>;
+
+    /// Asks the wallet to present the status of a batch of calls to the user.
+    async fn wallet_show_calls_status(&self, id: String) -> TransportResult<()>;
+
+    /// Checks that the wallet supports the capabilities the request relies on, submits the batch
+    /// and returns a [`PendingCalls`] that resolves once the batch has completed.
+    ///
+    /// If the request has no chain ID, the chain ID of the provider is used. Atomic execution must
+    /// be supported if [`SendCallsRequest::atomic_required`] is set, and paymaster services must
+    /// be supported if the request asks for one.
+    async fn send_calls(
+        &self,
+        request: SendCallsRequest,
+    ) -> Result<PendingCalls<N>, WalletCallsError>;
+}
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl<N, P> WalletApi<N> for P
+where
+    N: Network,
+    P: Provider<N>,
+{
+    async fn wallet_get_capabilities(
+        &self,
+        account: Address,
+        chain_ids: Option<Vec<ChainId>>,
+    ) -> TransportResult<WalletCapabilities> {
+        match chain_ids {
+            Some(chain_ids) => {
+                let chain_ids = chain_ids.into_iter().map(U64::from).collect::<Vec<_>>();
+                self.client().request("wallet_getCapabilities", (account, chain_ids)).await
+            }
+            None => self.client().request("wallet_getCapabilities", (account,)).await,
+        }
+    }
+
+    async fn wallet_send_calls(
+        &self,
+        request: SendCallsRequest,
+    ) -> TransportResult<SendCallsResponse> {
+        self.client().request("wallet_sendCalls", (request,)).await
+    }
+
+    async fn wallet_get_calls_status(&self, id: String) -> TransportResult<CallsStatus> {
+        self.client().request("wallet_getCallsStatus", (id,)).await
+    }
+
+    async fn wallet_show_calls_status(&self, id: String) -> TransportResult<()> {
+        self.client().request("wallet_showCallsStatus", (id,)).await
+    }
+
+    async fn send_calls(
+        &self,
+        mut request: SendCallsRequest,
+    ) -> Result<PendingCalls<N>, WalletCallsError> {
+        let chain_id = match request.chain_id {
+            Some(chain_id) => chain_id,
+            None => self.get_chain_id().await?,
+        };
+        request.chain_id = Some(chain_id);
+
+        if request.atomic_required || request.requests_paymaster_service() {
+            let capabilities =
+                self.wallet_get_capabilities(request.from, Some(vec![chain_id])).await?;
+            let capabilities = capabilities
+                .get(chain_id)
+                .ok_or(WalletCallsError::MissingCapabilities(chain_id))?;
+            if request.atomic_required && !capabilities.supports_atomic() {
+                return Err(WalletCallsError::AtomicUnsupported(chain_id));
+            }
+            if request.requests_paymaster_service() && !capabilities.supports_paymaster_service() {
+                return Err(WalletCallsError::PaymasterServiceUnsupported(chain_id));
+            }
+        }
+
+        let response = self.wallet_send_calls(request).await?;
+        Ok(PendingCalls::new(self.root().clone(), response.id))
+    }
+}
+
+/// A batch of calls that has been submitted with `wallet_sendCalls`.
+///
+/// See [`get_status`](Self::get_status).
+#[derive(Debug)]
+#[must_use = "this type does nothing unless you call `get_status`"]
+pub struct PendingCalls<N: Network> {
+    provider: RootProvider<N>,
+    id: String,
+    poll_interval: Option<Duration>,
+    timeout: Option<Duration>,
+}
+
+impl<N: Network> PendingCalls<N> {
+    /// Creates a new pending batch for the given identifier.
+    pub const fn new(provider: RootProvider<N>, id: String) -> Self {
+        Self { provider, id, poll_interval: None, timeout: None }
+    }
+
+    /// Returns the identifier of the batch.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Sets the interval at which the wallet is polled for the status of the batch.
+    ///
+    /// Defaults to the poll interval of the client.
+    pub const fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = Some(poll_interval);
+        self
+    }
+
+    /// Sets the duration after which waiting for the batch is aborted.
+    ///
+    /// Defaults to no timeout.
+    pub const fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Polls `wallet_getCallsStatus` until the batch is no longer pending, and returns the final
+    /// status.
+    ///
+    /// The returned status may be a failure, see [`CallsStatus::is_failure`].
+    pub async fn get_status(self) -> Result<CallsStatus, WalletCallsError> {
+        let poll_interval =
+            self.poll_interval.unwrap_or_else(|| self.provider.client().poll_interval());
+
+        let status = poll_until(poll_interval, self.timeout, || async {
+            let status = self.provider.wallet_get_calls_status(self.id.clone()).await?;
+            if !status.is_pending() {
+                return Ok::<_, WalletCallsError>(ControlFlow::Break(status));
+            }
+            trace!(id = %self.id, status = status.status, "calls pending");
+            Ok(ControlFlow::Continue(()))
+        })
+        .await?;
+        status.map_err(|()| WalletCallsError::Timeout(self.id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mock::Asserter, ProviderBuilder};
+    use alloy_eip5792::{AtomicCapability, AtomicStatus, CallParams, Capabilities};
+    use alloy_primitives::map::HashMap;
+
+    fn request(atomic_required: bool) -> SendCallsRequest {
+        let calls = vec![CallParams::default().with_to(Address::repeat_byte(2))];
+        SendCallsRequest::new("2.0.0", Address::repeat_byte(1), calls)
+            .with_atomic_required(atomic_required)
+    }
+
+    fn capabilities(status: AtomicStatus) -> WalletCapabilities {
+        WalletCapabilities(HashMap::from_iter([(
+            1,
+            Capabilities::default().with_atomic(AtomicCapability { status }),
+        )]))
+    }
+
+    fn status(status: u16) -> CallsStatus {
+        CallsStatus {
+            version: "2.0.0".to_string(),
+            id: "0x01".to_string(),
+            chain_id: 1,
+            status,
+            atomic: true,
+            receipts: None,
+            capabilities: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn send_calls_and_poll_status() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+        asserter.push_success(&U64::from(1));
+        asserter.push_success(&capabilities(AtomicStatus::Ready));
+        asserter.push_success(&SendCallsResponse { id: "0x01".to_string(), capabilities: None });
+        let pending = provider.send_calls(request(true)).await.unwrap();
+        assert_eq!(pending.id(), "0x01");
+        assert!(asserter.read_q().is_empty());
+
+        asserter.push_success(&status(100));
+        asserter.push_success(&status(200));
+        let status = pending.with_poll_interval(Duration::from_millis(1)).get_status().await;
+        assert!(status.unwrap().is_confirmed());
+        assert!(asserter.read_q().is_empty());
+    }
+
+    #[tokio::test]
+    async fn send_calls_requires_atomic_support() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+        asserter.push_success(&U64::from(1));
+        asserter.push_success(&capabilities(AtomicStatus::Unsupported));
+        let err = provider.send_calls(request(true)).await.unwrap_err();
+        assert!(matches!(err, WalletCallsError::AtomicUnsupported(1)));
+
+        // capabilities are only queried when the request relies on them
+        asserter.push_success(&U64::from(1));
+        asserter.push_success(&SendCallsResponse { id: "0x02".to_string(), capabilities: None });
+        let pending = provider.send_calls(request(false)).await.unwrap();
+        assert_eq!(pending.id(), "0x02");
+    }
+}
@@ -0,0 +1,536 @@
+//! Client for flashblocks feeds.
+//!
+//! A flashblocks feed streams the block that is being built in increments of a fraction of the
+//! block time. Each [`FlashBlock`] appends transactions to the pending block and preconfirms their
+//! receipts, so a transaction can be considered included well before the full block is sealed.
+//!
+//! See the [Base documentation](https://docs.base.org/chain/flashblocks) for details.
+
+use crate::PendingTransactionBuilder;
+use alloy_consensus::Receipt;
+use alloy_network::Network;
+use alloy_primitives::{Address, Bloom, Bytes, TxHash, B256, B64, U256};
+use alloy_rpc_types_eth::Withdrawal;
+use alloy_rpc_types_mev::FlashBlock;
+use alloy_transport::utils::Spawnable;
+use futures::{Stream, StreamExt};
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+use tokio::{
+    select,
+    sync::{broadcast, watch},
+};
+use tokio_tungstenite::tungstenite::{self, client::IntoClientRequest, Message};
+
+/// The number of flashblocks buffered for each subscriber.
+const FLASHBLOCKS_CAPACITY: usize = 64;
+
+/// Errors which may occur when following a flashblocks feed.
+#[derive(Debug, thiserror::Error)]
+pub enum FlashblocksError {
+    /// Failed to connect to the feed.
+    #[error(transparent)]
+    WebSocket(#[from] tungstenite::Error),
+
+    /// The flashblock does not follow the pending block.
+    #[error("flashblock {index} of payload {payload_id} does not follow the pending block")]
+    OutOfSequence {
+        /// The payload ID of the flashblock.
+        payload_id: B64,
+        /// The index of the flashblock.
+        index: u64,
+    },
+
+    /// The feed was closed.
+    #[error("flashblocks feed closed")]
+    Closed,
+
+    /// The transaction was not preconfirmed within the timeout.
+    #[error("timed out waiting for the transaction to be preconfirmed")]
+    Timeout,
+}
+
+/// The pending block, built by applying the flashblocks of a payload in order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PendingFlashblock {
+    payload_id: B64,
+    index: u64,
+    block_number: u64,
+    block_hash: B256,
+    state_root: B256,
+    receipts_root: B256,
+    logs_bloom: Bloom,
+    gas_used: u64,
+    transactions: Vec<Bytes>,
+    withdrawals: Vec<Withdrawal>,
+    receipts: BTreeMap<TxHash, Receipt>,
+    balances: BTreeMap<Address, U256>,
+}
+
+impl PendingFlashblock {
+    /// Starts a pending block from the first flashblock of a payload.
+    ///
+    /// Returns an error if the flashblock is not the first of its payload.
+    pub fn new(flashblock: &FlashBlock) -> Result<Self, FlashblocksError> {
+        if flashblock.index != 0 {
+            return Err(FlashblocksError::OutOfSequence {
+                payload_id: flashblock.payload_id,
+                index: flashblock.index,
+            });
+        }
+
+        let mut block = Self { payload_id: flashblock.payload_id, ..Default::default() };
+        block.merge(flashblock);
+        Ok(block)
+    }
+
+    /// Applies the next flashblock to the pending block.
+    ///
+    /// The first flashblock of a payload replaces the pending block. Any other flashblock must
+    /// directly follow the last applied one, otherwise an error is returned and the pending block
+    /// is left unchanged.
+    pub fn apply(&mut self, flashblock: &FlashBlock) -> Result<(), FlashblocksError> {
+        if flashblock.index == 0 {
+            *self = Self::new(flashblock)?;
+            return Ok(());
+        }
+        if flashblock.payload_id != self.payload_id || flashblock.index != self.index + 1 {
+            return Err(FlashblocksError::OutOfSequence {
+                payload_id: flashblock.payload_id,
+                index: flashblock.index,
+            });
+        }
+
+        self.merge(flashblock);
+        Ok(())
+    }
+
+    fn merge(&mut self, flashblock: &FlashBlock) {
+        let FlashBlock { index, diff, metadata, .. } = flashblock;
+        self.index = *index;
+        self.block_number = metadata.block_number;
+        self.block_hash = diff.block_hash;
+        self.state_root = diff.state_root;
+        self.receipts_root = diff.receipts_root;
+        self.logs_bloom = diff.logs_bloom;
+        self.gas_used = diff.gas_used;
+        self.transactions.extend_from_slice(&diff.transactions);
+        self.withdrawals.extend_from_slice(&diff.withdrawals);
+        self.receipts
+            .extend(metadata.receipts.iter().map(|(hash, receipt)| (*hash, receipt.clone())));
+        self.balances.extend(&metadata.new_account_balances);
+    }
+
+    /// Returns the payload ID of the pending block.
+    pub const fn payload_id(&self) -> B64 {
+        self.payload_id
+    }
+
+    /// Returns the index of the last applied flashblock.
+    pub const fn index(&self) -> u64 {
+        self.index
+    }
+
+    /// Returns the number of the pending block.
+    pub const fn block_number(&self) -> u64 {
+        self.block_number
+    }
+
+    /// Returns the hash of the pending block as of the last applied flashblock.
+    pub const fn block_hash(&self) -> B256 {
+        self.block_hash
+    }
+
+    /// Returns the state root of the pending block as of the last applied flashblock.
+    pub const fn state_root(&self) -> B256 {
+        self.state_root
+    }
+
+    /// Returns the receipts root of the pending block as of the last applied flashblock.
+    pub const fn receipts_root(&self) -> B256 {
+        self.receipts_root
+    }
+
+    /// Returns the logs bloom of the pending block as of the last applied flashblock.
+    pub const fn logs_bloom(&self) -> &Bloom {
+        &self.logs_bloom
+    }
+
+    /// Returns the gas used by the pending block as of the last applied flashblock.
+    pub const fn gas_used(&self) -> u64 {
+        self.gas_used
+    }
+
+    /// Returns the EIP-2718 encoded transactions of the pending block.
+    pub fn transactions(&self) -> &[Bytes] {
+        &self.transactions
+    }
+
+    /// Returns the withdrawals of the pending block.
+    pub fn withdrawals(&self) -> &[Withdrawal] {
+        &self.withdrawals
+    }
+
+    /// Returns the preconfirmed receipts of the pending block, keyed by transaction hash.
+    pub const fn receipts(&self) -> &BTreeMap<TxHash, Receipt> {
+        &self.receipts
+    }
+
+    /// Returns the preconfirmed receipt of the transaction, if it is in the pending block.
+    pub fn receipt(&self, tx_hash: &TxHash) -> Option<&Receipt> {
+        self.receipts.get(tx_hash)
+    }
+
+    /// Returns the balances of the accounts touched by the pending block.
+    pub const fn balances(&self) -> &BTreeMap<Address, U256> {
+        &self.balances
+    }
+
+    /// Returns the preconfirmed balance of the account, if it was touched by the pending block.
+    pub fn balance(&self, address: &Address) -> Option<U256> {
+        self.balances.get(address).copied()
+    }
+}
+
+/// A receipt preconfirmed by a flashblock.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PreconfirmedReceipt {
+    /// The number of the block the transaction is preconfirmed in.
+    pub block_number: u64,
+    /// The index of the flashblock that preconfirmed the transaction.
+    pub index: u64,
+    /// The preconfirmed receipt.
+    pub receipt: Receipt,
+}
+
+/// A handle to a flashblocks feed, see the [module docs](self).
+///
+/// The feed is followed by a background task, which stops when the feed closes or all handles are
+/// dropped. The connection is not re-established.
+///
+/// ```no_run
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use alloy_provider::flashblocks::Flashblocks;
+///
+/// let flashblocks = Flashblocks::connect("wss://mainnet.flashblocks.base.org/ws").await?;
+/// if let Some(block) = flashblocks.pending_block().as_ref() {
+///     println!("block {} has {} transactions", block.block_number(), block.transactions().len());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Flashblocks {
+    pending: watch::Receiver<Option<PendingFlashblock>>,
+    flashblocks: broadcast::Receiver<Arc<FlashBlock>>,
+}
+
+impl Clone for Flashblocks {
+    fn clone(&self) -> Self {
+        Self { pending: self.pending.clone(), flashblocks: self.flashblocks.resubscribe() }
+    }
+}
+
+impl Flashblocks {
+    /// Connects to the flashblocks feed at the given URL.
+    pub async fn connect<R>(request: R) -> Result<Self, FlashblocksError>
+    where
+        R: IntoClientRequest + Unpin,
+    {
+        let (ws, _) = tokio_tungstenite::connect_async(request).await?;
+        Ok(Self::spawn(ws))
+    }
+
+    /// Follows the feed of flashblock messages in a background task.
+    fn spawn<S>(mut feed: S) -> Self
+    where
+        S: Stream<Item = Result<Message, tungstenite::Error>> + Send + Unpin + 'static,
+    {
+        let (pending_tx, pending) = watch::channel(None);
+        let (flashblocks_tx, flashblocks) = broadcast::channel(FLASHBLOCKS_CAPACITY);
+
+        let task = async move {
+            loop {
+                let message = select! {
+                    _ = pending_tx.closed() => break,
+                    message = feed.next() => message,
+                };
+                let flashblock = match message {
+                    Some(Ok(Message::Text(text))) => serde_json::from_str::<FlashBlock>(&text),
+                    Some(Ok(Message::Binary(data))) => serde_json::from_slice(&data),
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Ok(_)) => continue,
+                    Some(Err(err)) => {
+                        debug!(%err, "flashblocks feed failed");
+                        break;
+                    }
+                };
+                let flashblock = match flashblock {
+                    Ok(flashblock) => flashblock,
+                    Err(err) => {
+                        debug!(%err, "failed to deserialize flashblock");
+                        continue;
+                    }
+                };
+
+                trace!(
+                    block_number = flashblock.metadata.block_number,
+                    index = flashblock.index,
+                    "received flashblock"
+                );
+                pending_tx.send_modify(|pending: &mut Option<PendingFlashblock>| {
+                    let result = match pending {
+                        Some(block) => block.apply(&flashblock),
+                        None => PendingFlashblock::new(&flashblock).map(|block| {
+                            *pending = Some(block);
+                        }),
+                    };
+                    // wait for the next payload to start if flashblocks were missed
+                    if let Err(err) = result {
+                        trace!(%err, "discarding pending block");
+                        *pending = None;
+                    }
+                });
+                let _ = flashblocks_tx.send(Arc::new(flashblock));
+            }
+        };
+        task.spawn_task();
+
+        Self { pending, flashblocks }
+    }
+
+    /// Returns the pending block, or `None` if the first flashblock of the current payload was
+    /// not received yet.
+    ///
+    /// The returned reference blocks the feed, so it must not be held across an `.await`.
+    pub fn pending_block(&self) -> watch::Ref<'_, Option<PendingFlashblock>> {
+        self.pending.borrow()
+    }
+
+    /// Returns a receiver that is notified whenever the pending block changes.
+    pub fn watch_pending_block(&self) -> watch::Receiver<Option<PendingFlashblock>> {
+        self.pending.clone()
+    }
+
+    /// Subscribes to the flashblocks of the feed.
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<FlashBlock>> {
+        self.flashblocks.resubscribe()
+    }
+
+    /// Returns the preconfirmed receipt of the transaction, if it is in the pending block.
+    pub fn receipt(&self, tx_hash: &TxHash) -> Option<PreconfirmedReceipt> {
+        self.pending.borrow().as_ref().and_then(|block| {
+            block.receipt(tx_hash).map(|receipt| PreconfirmedReceipt {
+                block_number: block.block_number(),
+                index: block.index(),
+                receipt: receipt.clone(),
+            })
+        })
+    }
+
+    /// Returns the preconfirmed balance of the account, if it was touched by the pending block.
+    pub fn balance(&self, address: &Address) -> Option<U256> {
+        self.pending.borrow().as_ref().and_then(|block| block.balance(address))
+    }
+
+    /// Waits for the transaction to be preconfirmed by a flashblock.
+    pub async fn wait_for_receipt(
+        &self,
+        tx_hash: TxHash,
+    ) -> Result<PreconfirmedReceipt, FlashblocksError> {
+        let mut flashblocks = self.subscribe();
+        loop {
+            if let Some(receipt) = self.receipt(&tx_hash) {
+                return Ok(receipt);
+            }
+
+            loop {
+                match flashblocks.recv().await {
+                    Ok(flashblock) => {
+                        if let Some(receipt) = flashblock.metadata.receipts.get(&tx_hash) {
+                            return Ok(PreconfirmedReceipt {
+                                block_number: flashblock.metadata.block_number,
+                                index: flashblock.index,
+                                receipt: receipt.clone(),
+                            });
+                        }
+                    }
+                    // check the pending block for missed flashblocks
+                    Err(broadcast::error::RecvError::Lagged(_)) => break,
+                    Err(broadcast::error::RecvError::Closed) => {
+                        return Err(FlashblocksError::Closed)
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<N: Network> PendingTransactionBuilder<N> {
+    /// Waits for the transaction to be preconfirmed by a flashblock of the given feed, instead of
+    /// being included in a block.
+    ///
+    /// The timeout of the pending transaction is kept.
+    pub fn preconfirmed(self, flashblocks: &Flashblocks) -> PreconfirmedTransactionBuilder<N> {
+        PreconfirmedTransactionBuilder { inner: self, flashblocks: flashblocks.clone() }
+    }
+}
+
+/// A pending transaction that resolves once it is preconfirmed by a flashblock.
+///
+/// See [`PendingTransactionBuilder::preconfirmed`].
+///
+/// ```no_run
+/// # async fn example(provider: impl alloy_provider::Provider, flashblocks: alloy_provider::flashblocks::Flashblocks, tx: alloy_rpc_types_eth::TransactionRequest) -> Result<(), Box<dyn std::error::Error>> {
+/// let preconfirmed = provider
+///     .send_transaction(tx)
+///     .await?
+///     .with_timeout(Some(std::time::Duration::from_secs(2)))
+///     .preconfirmed(&flashblocks)
+///     .get_receipt()
+///     .await?;
+/// assert!(preconfirmed.receipt.status.coerce_status());
+/// # Ok(())
+/// # }
+/// ```
+#[must_use = "this type does nothing unless you call `watch` or `get_receipt`"]
+#[derive(Debug)]
+pub struct PreconfirmedTransactionBuilder<N: Network> {
+    inner: PendingTransactionBuilder<N>,
+    flashblocks: Flashblocks,
+}
+
+impl<N: Network> PreconfirmedTransactionBuilder<N> {
+    /// Returns the inner pending transaction builder.
+    pub const fn inner(&self) -> &PendingTransactionBuilder<N> {
+        &self.inner
+    }
+
+    /// Consumes this builder, returning the inner pending transaction builder, e.g. to wait for
+    /// the full block after the transaction was preconfirmed.
+    pub fn into_inner(self) -> PendingTransactionBuilder<N> {
+        self.inner
+    }
+
+    /// Returns the transaction hash.
+    pub const fn tx_hash(&self) -> &TxHash {
+        self.inner.tx_hash()
+    }
+
+    /// Returns the timeout.
+    pub const fn timeout(&self) -> Option<Duration> {
+        self.inner.timeout()
+    }
+
+    /// Sets the timeout.
+    pub const fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.inner.set_timeout(timeout);
+        self
+    }
+
+    /// Waits for the transaction to be preconfirmed, returning its hash.
+    pub async fn watch(self) -> Result<TxHash, FlashblocksError> {
+        let tx_hash = *self.tx_hash();
+        self.get_receipt().await.map(|_| tx_hash)
+    }
+
+    /// Waits for the transaction to be preconfirmed, returning its preconfirmed receipt.
+    pub async fn get_receipt(self) -> Result<PreconfirmedReceipt, FlashblocksError> {
+        let receipt = self.flashblocks.wait_for_receipt(*self.tx_hash());
+        match self.timeout() {
+            Some(timeout) => tokio::time::timeout(timeout, receipt)
+                .await
+                .map_err(|_| FlashblocksError::Timeout)?,
+            None => receipt.await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Provider, ProviderBuilder};
+    use alloy_primitives::address;
+    use alloy_rpc_types_mev::{FlashBlockDiff, Metadata};
+    use alloy_transport::mock::Asserter;
+    use futures::SinkExt;
+    use tokio::net::TcpListener;
+
+    fn flashblock(index: u64, tx_hash: TxHash, balance: u64) -> FlashBlock {
+        let receipt = Receipt { cumulative_gas_used: 21_000 * (index + 1), ..Default::default() };
+        FlashBlock {
+            payload_id: B64::with_last_byte(1),
+            index,
+            diff: FlashBlockDiff {
+                block_hash: B256::with_last_byte(index as u8),
+                gas_used: 21_000 * (index + 1),
+                transactions: vec![Bytes::from(vec![index as u8])],
+                ..Default::default()
+            },
+            metadata: Metadata {
+                block_number: 100,
+                new_account_balances: BTreeMap::from([(Address::ZERO, U256::from(balance))]),
+                receipts: BTreeMap::from([(tx_hash, receipt)]),
+            },
+        }
+    }
+
+    #[test]
+    fn apply_flashblocks() {
+        let (first, second) = (B256::with_last_byte(1), B256::with_last_byte(2));
+        let mut block = PendingFlashblock::new(&flashblock(0, first, 1)).unwrap();
+        block.apply(&flashblock(1, second, 2)).unwrap();
+
+        assert_eq!(block.index(), 1);
+        assert_eq!(block.block_number(), 100);
+        assert_eq!(block.block_hash(), B256::with_last_byte(1));
+        assert_eq!(block.gas_used(), 42_000);
+        assert_eq!(block.transactions(), [Bytes::from(vec![0]), Bytes::from(vec![1])]);
+        assert_eq!(block.receipt(&first).unwrap().cumulative_gas_used, 21_000);
+        assert_eq!(block.receipt(&second).unwrap().cumulative_gas_used, 42_000);
+        assert_eq!(block.balance(&Address::ZERO), Some(U256::from(2)));
+        assert_eq!(block.balance(&address!("0x0000000000000000000000000000000000000001")), None);
+
+        // flashblocks must not be skipped
+        let err = block.apply(&flashblock(3, first, 3)).unwrap_err();
+        assert!(matches!(err, FlashblocksError::OutOfSequence { index: 3, .. }));
+        assert_eq!(block.index(), 1);
+        assert!(PendingFlashblock::new(&flashblock(1, first, 1)).is_err());
+
+        // the next payload starts a new block
+        block.apply(&flashblock(0, second, 0)).unwrap();
+        assert_eq!(block.transactions().len(), 1);
+        assert!(block.receipt(&first).is_none());
+    }
+
+    #[tokio::test]
+    async fn preconfirm_transaction() {
+        let tx_hash = B256::with_last_byte(2);
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            for flashblock in [flashblock(0, B256::ZERO, 1), flashblock(1, tx_hash, 2)] {
+                let message = serde_json::to_string(&flashblock).unwrap();
+                ws.send(Message::Text(message.into())).await.unwrap();
+            }
+            ws.close(None).await.unwrap();
+        });
+
+        let flashblocks = Flashblocks::connect(url).await.unwrap();
+        let provider = ProviderBuilder::new().connect_mocked_client(Asserter::new());
+        let pending = PendingTransactionBuilder::new(provider.root().clone(), tx_hash)
+            .with_timeout(Some(Duration::from_secs(5)))
+            .preconfirmed(&flashblocks);
+
+        let preconfirmed = pending.get_receipt().await.unwrap();
+        assert_eq!(preconfirmed.block_number, 100);
+        assert_eq!(preconfirmed.index, 1);
+        assert_eq!(preconfirmed.receipt.cumulative_gas_used, 42_000);
+        server.await.unwrap();
+
+        let err = flashblocks.wait_for_receipt(B256::with_last_byte(3)).await.unwrap_err();
+        assert!(matches!(err, FlashblocksError::Closed));
+        assert_eq!(flashblocks.balance(&Address::ZERO), Some(U256::from(2)));
+    }
+}
@@ -18,15 +18,24 @@ pub use builder::*;
 
 mod blocks;
 
+#[cfg(feature = "mnemonic")]
+pub mod discovery;
+
 pub mod ext;
 
 pub mod fillers;
 
+#[cfg(all(feature = "flashblocks", not(target_family = "wasm")))]
+pub mod flashblocks;
+
 mod heart;
 pub use heart::*;
 
 pub mod layers;
 
+#[cfg(feature = "ots-indexer")]
+pub mod ots_indexer;
+
 mod provider;
 pub use provider::*;
 
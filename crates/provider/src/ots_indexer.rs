@@ -0,0 +1,826 @@
+//! An indexer that answers otterscan queries for nodes without the `ots` namespace.
+//!
+//! The [`OtsIndexer`] ingests blocks, receipts and call traces from any [`Provider`] into an
+//! [`OtsIndex`], which answers the same queries as `ots_searchTransactionsBefore`,
+//! `ots_searchTransactionsAfter`, `ots_getContractCreator`, `ots_getTransactionBySenderAndNonce`
+//! and `ots_getInternalOperations`. Traces are fetched through a [`TraceProvider`], so nodes with
+//! either the parity `trace` or the geth `debug` namespace are supported.
+//!
+//! The index is kept in memory and can be saved to and loaded from a file, so it can be resumed
+//! across restarts:
+//!
+//! ```ignore
+//! let mut indexer = OtsIndexer::new(provider).with_index(OtsIndex::load("ots.json")?);
+//! indexer.sync().await?;
+//! indexer.index().save("ots.json")?;
+//!
+//! let page = indexer.index().search_transactions_before(address, 0, 25);
+//! ```
+
+use crate::{
+    ext::{TraceBackend, TraceProvider},
+    Provider,
+};
+use alloy_consensus::Transaction as _;
+use alloy_network::TransactionResponse;
+use alloy_primitives::{
+    map::{AddressHashMap, B256HashMap, HashMap},
+    Address, BlockHash, TxHash,
+};
+use alloy_rpc_types_eth::{Transaction, TransactionReceipt};
+use alloy_rpc_types_trace::{
+    geth::CallFrame,
+    otterscan::{
+        ContractCreator, InternalOperation, OperationType, OtsReceipt, OtsTransactionReceipt,
+        TransactionsWithReceipts,
+    },
+};
+use alloy_transport::TransportError;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    io::{self, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+/// Errors that may occur when indexing blocks or loading and saving an [`OtsIndex`].
+#[derive(Debug, thiserror::Error)]
+pub enum OtsIndexerError {
+    /// Underlying transport error.
+    #[error(transparent)]
+    Transport(#[from] TransportError),
+    /// The node doesn't have the block.
+    #[error("block {0} not found")]
+    BlockNotFound(u64),
+    /// The node doesn't have the receipts of the block.
+    #[error("receipts of block {0} not found")]
+    ReceiptsNotFound(u64),
+    /// Failed to read or write the index file.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// Failed to serialize or deserialize the index.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// A block in an [`OtsIndex`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedBlock {
+    /// The block number.
+    pub number: u64,
+    /// The block hash.
+    pub hash: BlockHash,
+    /// The hash of the parent block.
+    pub parent_hash: BlockHash,
+    /// The block timestamp.
+    pub timestamp: u64,
+    /// The transactions of the block.
+    pub transactions: Vec<IndexedTransaction>,
+}
+
+/// A transaction in an [`OtsIndex`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedTransaction {
+    /// The transaction.
+    pub transaction: Transaction,
+    /// The receipt, in the format of otterscan.
+    pub receipt: OtsTransactionReceipt,
+    /// The addresses the transaction touched: the sender, the recipient, all created contracts
+    /// and the senders and recipients of all internal calls.
+    pub addresses: BTreeSet<Address>,
+    /// The contracts created by the transaction, mapped to their creators.
+    pub created_contracts: BTreeMap<Address, Address>,
+    /// The internal value transfers, creations and selfdestructs of the transaction.
+    pub internal_operations: Vec<InternalOperation>,
+}
+
+impl IndexedTransaction {
+    /// Extracts the indexed data of a transaction from its receipt and call trace.
+    pub fn new(
+        transaction: Transaction,
+        receipt: TransactionReceipt,
+        timestamp: u64,
+        trace: &CallFrame,
+    ) -> Self {
+        let mut addresses = BTreeSet::from([transaction.from()]);
+        addresses.extend(transaction.to());
+        addresses.extend(receipt.contract_address);
+
+        let mut created_contracts = BTreeMap::new();
+        let mut internal_operations = Vec::new();
+        for (index, frame) in trace.iter().enumerate() {
+            addresses.insert(frame.from);
+            addresses.extend(frame.to);
+
+            let to = frame.to.unwrap_or_default();
+            let value = frame.value.unwrap_or_default();
+            let operation = match frame.typ.as_str() {
+                "CALL" if !value.is_zero() => Some(OperationType::OpTransfer),
+                "CREATE" => Some(OperationType::OpCreate),
+                "CREATE2" => Some(OperationType::OpCreate2),
+                "SELFDESTRUCT" => Some(OperationType::OpSelfDestruct),
+                _ => None,
+            };
+            // like otterscan, the root call is not an internal operation
+            if let Some(r#type) = operation.filter(|_| index > 0) {
+                internal_operations.push(InternalOperation { r#type, from: frame.from, to, value });
+            }
+        }
+
+        // contracts created in a failed frame, or below one, are reverted
+        let mut frames = trace.iter();
+        while let Some(frame) = frames.next() {
+            if frame.error.is_some() {
+                frames.skip_children();
+                continue;
+            }
+            if matches!(frame.typ.as_str(), "CREATE" | "CREATE2") {
+                if let Some(contract) = frame.to {
+                    created_contracts.insert(contract, frame.from);
+                }
+            }
+        }
+
+        let receipt = OtsTransactionReceipt {
+            receipt: receipt.map_inner(|receipt| OtsReceipt {
+                status: receipt.status(),
+                cumulative_gas_used: receipt.cumulative_gas_used(),
+                logs: None,
+                logs_bloom: None,
+                r#type: receipt.tx_type() as u8,
+            }),
+            timestamp: Some(timestamp),
+        };
+        Self { transaction, receipt, addresses, created_contracts, internal_operations }
+    }
+
+    /// Returns the transaction hash.
+    pub fn hash(&self) -> TxHash {
+        self.transaction.tx_hash()
+    }
+}
+
+/// The position of a transaction in the index, as block number and transaction index.
+type Position = (u64, usize);
+
+/// An index of blocks that answers otterscan queries, see the [module docs](self).
+#[derive(Clone, Debug, Default)]
+pub struct OtsIndex {
+    blocks: BTreeMap<u64, IndexedBlock>,
+    transactions: B256HashMap<Position>,
+    appearances: AddressHashMap<BTreeSet<Position>>,
+    nonces: HashMap<(Address, u64), TxHash>,
+    creators: AddressHashMap<ContractCreator>,
+}
+
+impl OtsIndex {
+    /// Creates an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads an index from a file written by [`save`](Self::save).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, OtsIndexerError> {
+        let blocks: Vec<IndexedBlock> = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+        let mut index = Self::default();
+        blocks.into_iter().for_each(|block| index.insert_block(block));
+        Ok(index)
+    }
+
+    /// Saves the indexed blocks to a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), OtsIndexerError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, &self.blocks.values().collect::<Vec<_>>())?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Returns the indexed block with the highest number.
+    pub fn latest_block(&self) -> Option<&IndexedBlock> {
+        self.blocks.last_key_value().map(|(_, block)| block)
+    }
+
+    /// Returns the indexed block with the given number.
+    pub fn block(&self, number: u64) -> Option<&IndexedBlock> {
+        self.blocks.get(&number)
+    }
+
+    /// Returns the indexed transaction with the given hash.
+    pub fn transaction(&self, hash: TxHash) -> Option<&IndexedTransaction> {
+        self.transactions.get(&hash).map(|&position| self.get(position))
+    }
+
+    /// Adds a block to the index, replacing any indexed block with the same number.
+    pub fn insert_block(&mut self, block: IndexedBlock) {
+        self.remove_block(block.number);
+        for (index, transaction) in block.transactions.iter().enumerate() {
+            let hash = transaction.hash();
+            let position = (block.number, index);
+            self.transactions.insert(hash, position);
+            for address in &transaction.addresses {
+                self.appearances.entry(*address).or_default().insert(position);
+            }
+            let sender = transaction.transaction.from();
+            self.nonces.insert((sender, transaction.transaction.nonce()), hash);
+            for (contract, creator) in &transaction.created_contracts {
+                self.creators.insert(*contract, ContractCreator { hash, creator: *creator });
+            }
+        }
+        self.blocks.insert(block.number, block);
+    }
+
+    /// Removes all blocks starting at the given number, e.g. after a reorg.
+    pub fn rewind(&mut self, number: u64) {
+        let numbers: Vec<_> = self.blocks.range(number..).map(|(number, _)| *number).collect();
+        numbers.into_iter().for_each(|number| self.remove_block(number));
+    }
+
+    fn remove_block(&mut self, number: u64) {
+        let Some(block) = self.blocks.remove(&number) else { return };
+        for (index, transaction) in block.transactions.iter().enumerate() {
+            let hash = transaction.hash();
+            self.transactions.remove(&hash);
+            for address in &transaction.addresses {
+                if let Some(positions) = self.appearances.get_mut(address) {
+                    positions.remove(&(number, index));
+                    if positions.is_empty() {
+                        self.appearances.remove(address);
+                    }
+                }
+            }
+            let key = (transaction.transaction.from(), transaction.transaction.nonce());
+            if self.nonces.get(&key) == Some(&hash) {
+                self.nonces.remove(&key);
+            }
+            for contract in transaction.created_contracts.keys() {
+                if self.creators.get(contract).is_some_and(|creator| creator.hash == hash) {
+                    self.creators.remove(contract);
+                }
+            }
+        }
+    }
+
+    /// Returns the transactions of the address in blocks before the given block, or in the latest
+    /// blocks if the block number is zero, like `ots_searchTransactionsBefore`.
+    ///
+    /// The transactions are in descending order. A page contains at least `page_size`
+    /// transactions if there are enough, and is only cut off at block boundaries.
+    pub fn search_transactions_before(
+        &self,
+        address: Address,
+        block_number: u64,
+        page_size: usize,
+    ) -> TransactionsWithReceipts {
+        let end = if block_number == 0 { u64::MAX } else { block_number };
+        let positions = self.appearances.get(&address).into_iter().flatten();
+        let mut positions = positions.rev().skip_while(|(number, _)| *number >= end).peekable();
+        let page = take_page(&mut positions, page_size);
+        let last_page = positions.peek().is_none();
+        self.page(page, block_number == 0, last_page)
+    }
+
+    /// Returns the transactions of the address in blocks after the given block, like
+    /// `ots_searchTransactionsAfter`.
+    ///
+    /// The transactions are in descending order. A page contains at least `page_size`
+    /// transactions if there are enough, and is only cut off at block boundaries.
+    pub fn search_transactions_after(
+        &self,
+        address: Address,
+        block_number: u64,
+        page_size: usize,
+    ) -> TransactionsWithReceipts {
+        let positions = self.appearances.get(&address).into_iter().flatten();
+        let mut positions = positions.skip_while(|(number, _)| *number <= block_number).peekable();
+        let mut page = take_page(&mut positions, page_size);
+        let first_page = positions.peek().is_none();
+        page.reverse();
+        self.page(page, first_page, block_number == 0)
+    }
+
+    /// Returns the creator of a contract, like `ots_getContractCreator`.
+    pub fn contract_creator(&self, address: Address) -> Option<ContractCreator> {
+        self.creators.get(&address).copied()
+    }
+
+    /// Returns the hash of the transaction with the given sender and nonce, like
+    /// `ots_getTransactionBySenderAndNonce`.
+    pub fn transaction_by_sender_and_nonce(&self, sender: Address, nonce: u64) -> Option<TxHash> {
+        self.nonces.get(&(sender, nonce)).copied()
+    }
+
+    /// Returns the internal operations of a transaction, like `ots_getInternalOperations`.
+    pub fn internal_operations(&self, hash: TxHash) -> Option<&[InternalOperation]> {
+        self.transaction(hash).map(|transaction| transaction.internal_operations.as_slice())
+    }
+
+    fn get(&self, (number, index): Position) -> &IndexedTransaction {
+        &self.blocks[&number].transactions[index]
+    }
+
+    fn page(
+        &self,
+        positions: Vec<Position>,
+        first_page: bool,
+        last_page: bool,
+    ) -> TransactionsWithReceipts {
+        let (txs, receipts) = positions
+            .into_iter()
+            .map(|position| {
+                let transaction = self.get(position);
+                (transaction.transaction.clone(), transaction.receipt.clone())
+            })
+            .unzip();
+        TransactionsWithReceipts { txs, receipts, first_page, last_page }
+    }
+}
+
+/// Takes at least `page_size` positions, and then all remaining positions of the last block.
+fn take_page<'a>(
+    positions: &mut std::iter::Peekable<impl Iterator<Item = &'a Position>>,
+    page_size: usize,
+) -> Vec<Position> {
+    let mut page: Vec<Position> = Vec::new();
+    while let Some(&&position) = positions.peek() {
+        let same_block = page.last().is_some_and(|last| last.0 == position.0);
+        if page.len() >= page_size && !same_block {
+            break;
+        }
+        page.push(position);
+        positions.next();
+    }
+    page
+}
+
+/// Ingests blocks, receipts and call traces from a provider into an [`OtsIndex`], see the
+/// [module docs](self).
+#[derive(Debug)]
+pub struct OtsIndexer<P> {
+    tracer: TraceProvider<P>,
+    index: OtsIndex,
+    start_block: u64,
+}
+
+impl<P: Provider> OtsIndexer<P> {
+    /// Creates an indexer with an empty index that starts at the genesis block.
+    pub fn new(provider: P) -> Self {
+        Self { tracer: TraceProvider::new(provider), index: OtsIndex::default(), start_block: 0 }
+    }
+
+    /// Continues indexing after the latest block of the given index.
+    pub fn with_index(mut self, index: OtsIndex) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Sets the first block to index if the index is empty.
+    pub const fn with_start_block(mut self, number: u64) -> Self {
+        self.start_block = number;
+        self
+    }
+
+    /// Uses the given tracing API instead of detecting it.
+    pub fn with_trace_backend(mut self, backend: TraceBackend) -> Self {
+        self.tracer = self.tracer.with_backend(backend);
+        self
+    }
+
+    /// Returns the index.
+    pub const fn index(&self) -> &OtsIndex {
+        &self.index
+    }
+
+    /// Consumes the indexer and returns the index.
+    pub fn into_index(self) -> OtsIndex {
+        self.index
+    }
+
+    /// Indexes all blocks after the latest indexed block up to the latest block of the node, and
+    /// returns the number of the latest block.
+    ///
+    /// Indexed blocks after the latest block of the node, or whose hash doesn't match the block of
+    /// the node, are removed first. If the parent of a block doesn't match the indexed block, the
+    /// indexed block is removed and indexed again, until the index is back on the canonical chain.
+    pub async fn sync(&mut self) -> Result<u64, OtsIndexerError> {
+        let provider = self.tracer.provider();
+        let latest = provider.get_block_number().await?;
+        self.index.rewind(latest + 1);
+        while let Some(indexed) = self.index.latest_block() {
+            let number = indexed.number;
+            let block = provider
+                .get_block_by_number(number.into())
+                .await?
+                .ok_or(OtsIndexerError::BlockNotFound(number))?;
+            if block.header.hash == indexed.hash {
+                break;
+            }
+            debug!(number, "reorg detected, removing indexed block");
+            self.index.rewind(number);
+        }
+
+        let mut next = self.index.latest_block().map_or(self.start_block, |block| block.number + 1);
+        while next <= latest {
+            let block = self.fetch_block(next).await?;
+            let parent = next.checked_sub(1).and_then(|parent| self.index.block(parent));
+            if let Some(parent) = parent.filter(|parent| parent.hash != block.parent_hash) {
+                debug!(number = parent.number, "reorg detected, removing indexed block");
+                next = parent.number;
+                self.index.rewind(next);
+                continue;
+            }
+            self.index.insert_block(block);
+            next += 1;
+        }
+        Ok(latest)
+    }
+
+    /// Indexes the block with the given number, replacing it if it is already indexed.
+    pub async fn index_block(&mut self, number: u64) -> Result<&IndexedBlock, OtsIndexerError> {
+        let block = self.fetch_block(number).await?;
+        self.index.insert_block(block);
+        Ok(&self.index.blocks[&number])
+    }
+
+    async fn fetch_block(&self, number: u64) -> Result<IndexedBlock, OtsIndexerError> {
+        let provider = self.tracer.provider();
+        let block = provider
+            .get_block_by_number(number.into())
+            .full()
+            .await?
+            .ok_or(OtsIndexerError::BlockNotFound(number))?;
+        let receipts = provider
+            .get_block_receipts(number.into())
+            .await?
+            .ok_or(OtsIndexerError::ReceiptsNotFound(number))?;
+
+        if receipts.len() != block.transactions.len() {
+            return Err(OtsIndexerError::ReceiptsNotFound(number));
+        }
+
+        let timestamp = block.header.timestamp;
+        let mut transactions = Vec::with_capacity(receipts.len());
+        for (transaction, receipt) in block.transactions.into_transactions().zip(receipts) {
+            let trace = self.tracer.call_trace(transaction.tx_hash()).await?;
+            transactions.push(IndexedTransaction::new(transaction, receipt, timestamp, &trace));
+        }
+        Ok(IndexedBlock {
+            number,
+            hash: block.header.hash,
+            parent_hash: block.header.parent_hash,
+            timestamp,
+            transactions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProviderBuilder;
+    use alloy_consensus::{ReceiptEnvelope, ReceiptWithBloom, Signed, TxEnvelope, TxLegacy};
+    use alloy_primitives::{Signature, TxKind, B256, U256, U64};
+    use alloy_rpc_types_eth::{Block, BlockTransactions, Header};
+    use alloy_transport::mock::Asserter;
+
+    const ALICE: Address = Address::with_last_byte(1);
+    const BOB: Address = Address::with_last_byte(2);
+    const FACTORY: Address = Address::with_last_byte(3);
+    const CONTRACT: Address = Address::with_last_byte(4);
+
+    fn rpc_transaction(
+        number: u64,
+        from: Address,
+        nonce: u64,
+        to: Address,
+    ) -> (Transaction, TransactionReceipt, CallFrame) {
+        let tx = TxLegacy { nonce, gas_price: 1, to: TxKind::Call(to), ..Default::default() };
+        let signed = Signed::new_unhashed(tx, Signature::test_signature());
+        let transaction = Transaction {
+            inner: alloy_consensus::transaction::Recovered::new_unchecked(
+                TxEnvelope::Legacy(signed),
+                from,
+            ),
+            block_hash: Some(B256::with_last_byte(number as u8)),
+            block_number: Some(number),
+            transaction_index: None,
+            effective_gas_price: Some(1),
+            block_timestamp: None,
+        };
+        let receipt = TransactionReceipt {
+            inner: ReceiptEnvelope::Legacy(ReceiptWithBloom::default()),
+            transaction_hash: transaction.tx_hash(),
+            transaction_index: None,
+            block_hash: transaction.block_hash,
+            block_number: Some(number),
+            gas_used: 21_000,
+            effective_gas_price: 1,
+            blob_gas_used: None,
+            blob_gas_price: None,
+            from,
+            to: Some(to),
+            contract_address: None,
+        };
+        let trace = CallFrame {
+            from,
+            to: Some(to),
+            value: Some(U256::ZERO),
+            typ: "CALL".to_string(),
+            ..Default::default()
+        };
+        (transaction, receipt, trace)
+    }
+
+    fn transaction(number: u64, from: Address, nonce: u64, to: Address) -> IndexedTransaction {
+        let (transaction, receipt, trace) = rpc_transaction(number, from, nonce, to);
+        IndexedTransaction::new(transaction, receipt, number * 12, &trace)
+    }
+
+    fn block(number: u64, transactions: Vec<IndexedTransaction>) -> IndexedBlock {
+        IndexedBlock {
+            number,
+            hash: B256::with_last_byte(number as u8),
+            parent_hash: B256::with_last_byte(number as u8 - 1),
+            timestamp: number * 12,
+            transactions,
+        }
+    }
+
+    fn rpc_block(
+        number: u64,
+        hash: B256,
+        parent_hash: B256,
+        transactions: Vec<Transaction>,
+    ) -> Block {
+        Block {
+            header: Header {
+                hash,
+                inner: alloy_consensus::Header {
+                    number,
+                    parent_hash,
+                    timestamp: number * 12,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            transactions: BlockTransactions::Full(transactions),
+            ..Default::default()
+        }
+    }
+
+    fn numbers(page: &TransactionsWithReceipts) -> Vec<u64> {
+        page.txs.iter().map(|tx| tx.block_number.unwrap()).collect()
+    }
+
+    fn index() -> OtsIndex {
+        let mut index = OtsIndex::new();
+        index.insert_block(block(1, vec![transaction(1, ALICE, 0, BOB)]));
+        index.insert_block(block(
+            2,
+            vec![transaction(2, ALICE, 1, BOB), transaction(2, BOB, 0, ALICE)],
+        ));
+        index.insert_block(block(3, vec![transaction(3, BOB, 1, FACTORY)]));
+        index.insert_block(block(4, vec![transaction(4, ALICE, 2, FACTORY)]));
+        index
+    }
+
+    #[test]
+    fn search_transactions() {
+        let index = index();
+
+        let page = index.search_transactions_before(ALICE, 0, 2);
+        assert_eq!(numbers(&page), [4, 2, 2]);
+        assert!(page.first_page && !page.last_page);
+        assert_eq!(page.receipts[0].timestamp, Some(48));
+
+        let page = index.search_transactions_before(ALICE, 2, 2);
+        assert_eq!(numbers(&page), [1]);
+        assert!(!page.first_page && page.last_page);
+
+        let page = index.search_transactions_after(ALICE, 0, 1);
+        assert_eq!(numbers(&page), [1]);
+        assert!(!page.first_page && page.last_page);
+
+        let page = index.search_transactions_after(ALICE, 1, 2);
+        assert_eq!(numbers(&page), [2, 2]);
+        assert!(!page.first_page && !page.last_page);
+
+        let page = index.search_transactions_after(ALICE, 2, 2);
+        assert_eq!(numbers(&page), [4]);
+        assert!(page.first_page && !page.last_page);
+
+        assert!(index.search_transactions_before(FACTORY, 3, 10).txs.is_empty());
+    }
+
+    #[test]
+    fn lookups() {
+        let mut index = index();
+        let hash = index.block(2).unwrap().transactions[1].hash();
+        assert_eq!(index.transaction_by_sender_and_nonce(BOB, 0), Some(hash));
+        assert_eq!(index.transaction_by_sender_and_nonce(BOB, 2), None);
+
+        let mut deploy = transaction(5, ALICE, 3, FACTORY);
+        let create = CallFrame {
+            from: FACTORY,
+            to: Some(CONTRACT),
+            value: Some(U256::from(5)),
+            typ: "CREATE2".to_string(),
+            ..Default::default()
+        };
+        let trace = CallFrame {
+            from: ALICE,
+            to: Some(FACTORY),
+            value: Some(U256::from(5)),
+            typ: "CALL".to_string(),
+            calls: vec![create],
+            ..Default::default()
+        };
+        let receipt = index.block(4).unwrap().transactions[0].receipt.clone();
+        deploy = IndexedTransaction::new(
+            deploy.transaction,
+            receipt.receipt.map_inner(|_| ReceiptEnvelope::Legacy(ReceiptWithBloom::default())),
+            60,
+            &trace,
+        );
+        let hash = deploy.hash();
+        index.insert_block(block(5, vec![deploy]));
+
+        assert_eq!(
+            index.contract_creator(CONTRACT),
+            Some(ContractCreator { hash, creator: FACTORY })
+        );
+        assert_eq!(
+            index.internal_operations(hash).unwrap(),
+            [InternalOperation {
+                r#type: OperationType::OpCreate2,
+                from: FACTORY,
+                to: CONTRACT,
+                value: U256::from(5)
+            }]
+        );
+        assert_eq!(numbers(&index.search_transactions_before(CONTRACT, 0, 10)), [5]);
+
+        index.rewind(4);
+        assert_eq!(index.latest_block().unwrap().number, 3);
+        assert_eq!(index.internal_operations(hash), None);
+        assert_eq!(index.transaction_by_sender_and_nonce(ALICE, 2), None);
+        assert_eq!(numbers(&index.search_transactions_before(ALICE, 0, 10)), [2, 2, 1]);
+    }
+
+    #[test]
+    fn reverted_creations() {
+        let index = index();
+        let create = |to, error: Option<&str>, calls| CallFrame {
+            from: FACTORY,
+            to: Some(to),
+            typ: "CREATE".to_string(),
+            error: error.map(Into::into),
+            calls,
+            ..Default::default()
+        };
+        let reverted = CallFrame {
+            from: FACTORY,
+            to: Some(FACTORY),
+            typ: "CALL".to_string(),
+            error: Some("execution reverted".to_string()),
+            calls: vec![create(BOB, None, Vec::new())],
+            ..Default::default()
+        };
+        let trace = CallFrame {
+            from: ALICE,
+            to: Some(FACTORY),
+            typ: "CALL".to_string(),
+            calls: vec![
+                reverted,
+                create(CONTRACT, None, Vec::new()),
+                create(ALICE, Some("out of gas"), Vec::new()),
+            ],
+            ..Default::default()
+        };
+        let transaction = index.block(4).unwrap().transactions[0].clone();
+        let receipt = transaction
+            .receipt
+            .receipt
+            .map_inner(|_| ReceiptEnvelope::Legacy(ReceiptWithBloom::default()));
+        let transaction = IndexedTransaction::new(transaction.transaction, receipt, 60, &trace);
+
+        assert_eq!(transaction.created_contracts, BTreeMap::from([(CONTRACT, FACTORY)]));
+    }
+
+    #[test]
+    fn save_and_load() {
+        let index = index();
+        let path = std::env::temp_dir().join(format!("ots-index-{}.json", std::process::id()));
+        index.save(&path).unwrap();
+        let loaded = OtsIndex::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.blocks, index.blocks);
+        assert_eq!(
+            loaded.search_transactions_before(BOB, 0, 10),
+            index.search_transactions_before(BOB, 0, 10)
+        );
+        assert_eq!(
+            loaded.transaction_by_sender_and_nonce(ALICE, 2),
+            index.transaction_by_sender_and_nonce(ALICE, 2)
+        );
+    }
+
+    #[tokio::test]
+    async fn sync_and_fetch_blocks() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let mut indexer =
+            OtsIndexer::new(provider).with_start_block(1).with_trace_backend(TraceBackend::Geth);
+        let (tx, receipt, trace) = rpc_transaction(1, ALICE, 0, BOB);
+        let hash = tx.tx_hash();
+
+        asserter.push_success(&U64::from(2));
+        asserter.push_success(&rpc_block(1, B256::with_last_byte(1), B256::ZERO, vec![tx.clone()]));
+        asserter.push_success(&vec![receipt.clone()]);
+        asserter.push_success(&trace);
+        asserter.push_success(&rpc_block(
+            2,
+            B256::with_last_byte(2),
+            B256::with_last_byte(1),
+            Vec::new(),
+        ));
+        asserter.push_success(&Vec::<TransactionReceipt>::new());
+        assert_eq!(indexer.sync().await.unwrap(), 2);
+        assert!(asserter.read_q().is_empty());
+
+        assert_eq!(
+            indexer.index().block(1).unwrap(),
+            &block(1, vec![transaction(1, ALICE, 0, BOB)])
+        );
+        assert_eq!(indexer.index().latest_block().unwrap(), &block(2, Vec::new()));
+        assert_eq!(indexer.index().transaction_by_sender_and_nonce(ALICE, 0), Some(hash));
+
+        // The receipts of a block must match its transactions.
+        asserter.push_success(&rpc_block(
+            3,
+            B256::with_last_byte(3),
+            B256::with_last_byte(2),
+            vec![tx],
+        ));
+        asserter.push_success(&Vec::<TransactionReceipt>::new());
+        let err = indexer.index_block(3).await.unwrap_err();
+        assert!(matches!(err, OtsIndexerError::ReceiptsNotFound(3)));
+
+        asserter.push_success(&Option::<Block>::None);
+        let err = indexer.index_block(3).await.unwrap_err();
+        assert!(matches!(err, OtsIndexerError::BlockNotFound(3)));
+        assert!(asserter.read_q().is_empty());
+    }
+
+    #[tokio::test]
+    async fn sync_reorg() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let mut indexer = OtsIndexer::new(provider).with_index(index());
+        let block_4 = rpc_block(4, B256::repeat_byte(4), B256::with_last_byte(3), Vec::new());
+        let block_5 = rpc_block(5, B256::repeat_byte(5), B256::repeat_byte(4), Vec::new());
+
+        // The parent of block 5 doesn't match the indexed block 4, so block 4 is indexed again.
+        asserter.push_success(&U64::from(5));
+        asserter.push_success(&rpc_block(4, B256::with_last_byte(4), B256::ZERO, Vec::new()));
+        for block in [&block_5, &block_4, &block_5] {
+            asserter.push_success(block);
+            asserter.push_success(&Vec::<TransactionReceipt>::new());
+        }
+        assert_eq!(indexer.sync().await.unwrap(), 5);
+        assert!(asserter.read_q().is_empty());
+
+        let index = indexer.index();
+        assert_eq!(index.block(4).unwrap().hash, B256::repeat_byte(4));
+        assert_eq!(index.latest_block().unwrap().hash, B256::repeat_byte(5));
+        assert_eq!(index.transaction_by_sender_and_nonce(ALICE, 2), None);
+        assert!(index.transaction_by_sender_and_nonce(BOB, 1).is_some());
+    }
+
+    #[tokio::test]
+    async fn sync_shorter_chain() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let mut indexer = OtsIndexer::new(provider).with_index(index());
+        let block_2 = rpc_block(2, B256::repeat_byte(2), B256::with_last_byte(1), Vec::new());
+
+        // The node is back at block 2, which was replaced, so blocks 2 to 4 are removed.
+        asserter.push_success(&U64::from(2));
+        asserter.push_success(&block_2);
+        asserter.push_success(&rpc_block(1, B256::with_last_byte(1), B256::ZERO, Vec::new()));
+        asserter.push_success(&block_2);
+        asserter.push_success(&Vec::<TransactionReceipt>::new());
+        assert_eq!(indexer.sync().await.unwrap(), 2);
+        assert!(asserter.read_q().is_empty());
+
+        let index = indexer.index();
+        assert_eq!(index.latest_block().unwrap().hash, B256::repeat_byte(2));
+        assert!(index.latest_block().unwrap().transactions.is_empty());
+        assert!(index.block(3).is_none());
+        assert_eq!(index.transaction_by_sender_and_nonce(BOB, 0), None);
+        assert!(index.transaction_by_sender_and_nonce(ALICE, 0).is_some());
+    }
+}
@@ -11,9 +11,18 @@ use alloy_rpc_client::WeakClient;
 use alloy_transport::{TransportError, TransportResult};
 use std::{
     fmt::{self, Formatter},
+    future::Future,
+    ops::ControlFlow,
     sync::Arc,
+    time::Duration,
 };
 
+#[cfg(all(target_family = "wasm", target_os = "unknown"))]
+use wasmtimer::{std::Instant, tokio::sleep};
+
+#[cfg(not(all(target_family = "wasm", target_os = "unknown")))]
+use {std::time::Instant, tokio::time::sleep};
+
 pub use alloy_eips::eip1559::Eip1559Estimation;
 
 /// The number of blocks from the past for which the fee rewards are fetched for fee estimation.
@@ -178,6 +187,32 @@ pub(crate) async fn hashes_to_headers<
     Ok(headers)
 }
 
+/// Calls `poll` every `poll_interval` until it breaks with a value.
+///
+/// `poll` continues with the last observed state, which is returned as `Err` once the next poll
+/// would happen after `timeout`. Errors of `poll` are returned immediately.
+pub(crate) async fn poll_until<T, S, E, F, Fut>(
+    poll_interval: Duration,
+    timeout: Option<Duration>,
+    mut poll: F,
+) -> Result<Result<T, S>, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<ControlFlow<T, S>, E>>,
+{
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    loop {
+        let state = match poll().await? {
+            ControlFlow::Break(value) => return Ok(Ok(value)),
+            ControlFlow::Continue(state) => state,
+        };
+        if deadline.is_some_and(|deadline| Instant::now() + poll_interval > deadline) {
+            return Ok(Err(state));
+        }
+        sleep(poll_interval).await;
+    }
+}
+
 /// Helper type representing the joined recommended fillers i.e [`GasFiller`],
 /// [`BlobGasFiller`], [`NonceFiller`], and [`ChainIdFiller`].
 pub type JoinedRecommendedFillers = JoinFill<
@@ -190,6 +225,35 @@ mod tests {
     use super::*;
     use std::vec;
 
+    #[tokio::test]
+    async fn test_poll_until() {
+        let mut polls = 0;
+        let result = poll_until(Duration::from_millis(1), None, || {
+            polls += 1;
+            let polls = polls;
+            async move {
+                Ok::<_, ()>(match polls {
+                    3 => ControlFlow::Break(polls),
+                    _ => ControlFlow::Continue(()),
+                })
+            }
+        })
+        .await;
+        assert_eq!(result, Ok(Ok(3)));
+
+        let result = poll_until(Duration::from_millis(1), Some(Duration::ZERO), || async {
+            Ok::<ControlFlow<(), _>, ()>(ControlFlow::Continue("pending"))
+        })
+        .await;
+        assert_eq!(result, Ok(Err("pending")));
+
+        let result = poll_until(Duration::from_millis(1), None, || async {
+            Err::<ControlFlow<(), ()>, _>("failed")
+        })
+        .await;
+        assert_eq!(result, Err("failed"));
+    }
+
     #[test]
     fn test_estimate_priority_fee() {
         let rewards =
@@ -347,6 +347,88 @@ pub struct PayloadAttributesData {
     pub payload_attributes: PayloadAttributes,
 }
 
+/// An event of the beacon API node event stream, tagged by its topic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BeaconEvent {
+    /// A `payload_attributes` event.
+    PayloadAttributes(PayloadAttributesEvent),
+    /// A `head` event.
+    Head(HeadEvent),
+    /// A `block` event.
+    Block(BlockEvent),
+    /// An `attestation` event.
+    Attestation(AttestationEvent),
+    /// A `voluntary_exit` event.
+    VoluntaryExit(VoluntaryExitEvent),
+    /// A `bls_to_execution_change` event.
+    BlsToExecutionChange(BlsToExecutionChangeEvent),
+    /// A `finalized_checkpoint` event.
+    FinalizedCheckpoint(FinalizedCheckpointEvent),
+    /// A `chain_reorg` event.
+    ChainReorg(ChainReorgEvent),
+    /// A `contribution_and_proof` event.
+    ContributionAndProof(ContributionAndProofEvent),
+    /// A `light_client_finality_update` event.
+    LightClientFinalityUpdate(LightClientFinalityUpdateEvent),
+    /// A `light_client_optimistic_update` event.
+    LightClientOptimisticUpdate(LightClientOptimisticUpdateEvent),
+    /// A `blob_sidecar` event.
+    BlobSidecar(BlobSidecarEvent),
+    /// An event of a topic that is not known.
+    Unknown {
+        /// The name of the event.
+        event: String,
+        /// The raw data of the event.
+        data: String,
+    },
+}
+
+impl BeaconEvent {
+    /// Decodes an event from the `event` name and `data` fields of a server-sent event.
+    pub fn from_sse(event: &str, data: &str) -> Result<Self, serde_json::Error> {
+        Ok(match event {
+            "payload_attributes" => Self::PayloadAttributes(serde_json::from_str(data)?),
+            "head" => Self::Head(serde_json::from_str(data)?),
+            "block" => Self::Block(serde_json::from_str(data)?),
+            "attestation" => Self::Attestation(serde_json::from_str(data)?),
+            "voluntary_exit" => Self::VoluntaryExit(serde_json::from_str(data)?),
+            "bls_to_execution_change" => Self::BlsToExecutionChange(serde_json::from_str(data)?),
+            "finalized_checkpoint" => Self::FinalizedCheckpoint(serde_json::from_str(data)?),
+            "chain_reorg" => Self::ChainReorg(serde_json::from_str(data)?),
+            "contribution_and_proof" => Self::ContributionAndProof(serde_json::from_str(data)?),
+            "light_client_finality_update" => {
+                Self::LightClientFinalityUpdate(serde_json::from_str(data)?)
+            }
+            "light_client_optimistic_update" => {
+                Self::LightClientOptimisticUpdate(serde_json::from_str(data)?)
+            }
+            "blob_sidecar" => Self::BlobSidecar(serde_json::from_str(data)?),
+            _ => Self::Unknown { event: event.to_string(), data: data.to_string() },
+        })
+    }
+
+    /// Returns the topic of the event, or `None` if it is not known.
+    pub const fn topic(&self) -> Option<BeaconNodeEventTopic> {
+        Some(match self {
+            Self::PayloadAttributes(_) => BeaconNodeEventTopic::PayloadAttributes,
+            Self::Head(_) => BeaconNodeEventTopic::Head,
+            Self::Block(_) => BeaconNodeEventTopic::Block,
+            Self::Attestation(_) => BeaconNodeEventTopic::Attestation,
+            Self::VoluntaryExit(_) => BeaconNodeEventTopic::VoluntaryExit,
+            Self::BlsToExecutionChange(_) => BeaconNodeEventTopic::BlsToExecutionChange,
+            Self::FinalizedCheckpoint(_) => BeaconNodeEventTopic::FinalizedCheckpoint,
+            Self::ChainReorg(_) => BeaconNodeEventTopic::ChainReorg,
+            Self::ContributionAndProof(_) => BeaconNodeEventTopic::ContributionAndProof,
+            Self::LightClientFinalityUpdate(_) => BeaconNodeEventTopic::LightClientFinalityUpdate,
+            Self::LightClientOptimisticUpdate(_) => {
+                BeaconNodeEventTopic::LightClientOptimisticUpdate
+            }
+            Self::BlobSidecar(_) => BeaconNodeEventTopic::BlobSidecar,
+            Self::Unknown { .. } => return None,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -474,4 +556,16 @@ mod tests {
         let json = serde_json::to_value(event).unwrap();
         assert_eq!(input, json);
     }
+
+    #[test]
+    fn beacon_event_from_sse() {
+        let data = r#"{"block":"0x9a2fefd2fdb57f74993c7780ea5b9030d2897b615b89f808011ca5aebed54eaf","state":"0x600e852a08c1200654ddf11025f1ceacb3c2e74bdd5c630cde0838b2591b69f9","epoch":"2","execution_optimistic":false}"#;
+        let event = BeaconEvent::from_sse("finalized_checkpoint", data).unwrap();
+        assert_eq!(event.topic(), Some(BeaconNodeEventTopic::FinalizedCheckpoint));
+        assert!(matches!(event, BeaconEvent::FinalizedCheckpoint(ref e) if e.epoch == 2));
+
+        let event = BeaconEvent::from_sse("new_topic", "{}").unwrap();
+        assert_eq!(event.topic(), None);
+        assert!(BeaconEvent::from_sse("head", "{}").is_err());
+    }
 }
@@ -0,0 +1,105 @@
+//! Block and state identifiers used in the paths of the beacon API.
+
+use alloy_primitives::B256;
+use core::fmt;
+
+/// Identifies a block in the [`/eth/v1/beacon/blocks/{block_id}`](https://ethereum.github.io/beacon-APIs/#/Beacon/getBlockV2)
+/// style endpoints.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BlockId {
+    /// The canonical head in the node's view.
+    #[default]
+    Head,
+    /// The genesis block.
+    Genesis,
+    /// The latest finalized block.
+    Finalized,
+    /// The block at the given slot.
+    Slot(u64),
+    /// The block with the given root.
+    Root(B256),
+}
+
+impl fmt::Display for BlockId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Head => f.write_str("head"),
+            Self::Genesis => f.write_str("genesis"),
+            Self::Finalized => f.write_str("finalized"),
+            Self::Slot(slot) => write!(f, "{slot}"),
+            Self::Root(root) => write!(f, "{root}"),
+        }
+    }
+}
+
+impl From<u64> for BlockId {
+    fn from(slot: u64) -> Self {
+        Self::Slot(slot)
+    }
+}
+
+impl From<B256> for BlockId {
+    fn from(root: B256) -> Self {
+        Self::Root(root)
+    }
+}
+
+/// Identifies a state in the [`/eth/v1/beacon/states/{state_id}`](https://ethereum.github.io/beacon-APIs/#/Beacon/getStateRoot)
+/// style endpoints.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum StateId {
+    /// The state of the canonical head in the node's view.
+    #[default]
+    Head,
+    /// The genesis state.
+    Genesis,
+    /// The latest finalized state.
+    Finalized,
+    /// The latest justified state.
+    Justified,
+    /// The state at the given slot.
+    Slot(u64),
+    /// The state with the given state root.
+    Root(B256),
+}
+
+impl fmt::Display for StateId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Head => f.write_str("head"),
+            Self::Genesis => f.write_str("genesis"),
+            Self::Finalized => f.write_str("finalized"),
+            Self::Justified => f.write_str("justified"),
+            Self::Slot(slot) => write!(f, "{slot}"),
+            Self::Root(root) => write!(f, "{root}"),
+        }
+    }
+}
+
+impl From<u64> for StateId {
+    fn from(slot: u64) -> Self {
+        Self::Slot(slot)
+    }
+}
+
+impl From<B256> for StateId {
+    fn from(root: B256) -> Self {
+        Self::Root(root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_ids() {
+        assert_eq!(BlockId::Head.to_string(), "head");
+        assert_eq!(BlockId::Slot(42).to_string(), "42");
+        assert_eq!(
+            BlockId::Root(B256::with_last_byte(1)).to_string(),
+            "0x0000000000000000000000000000000000000000000000000000000000000001"
+        );
+        assert_eq!(StateId::Justified.to_string(), "justified");
+    }
+}
@@ -14,6 +14,9 @@ pub mod constants;
 
 // -- Beacon endpoint types --
 
+/// Block and state identifiers used in the beacon API paths.
+pub mod id;
+
 /// Types for the [`/eth/v2/beacon/blocks`](https://ethereum.github.io/beacon-APIs/#/Beacon) endpoints.
 pub mod block;
 
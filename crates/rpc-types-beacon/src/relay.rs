@@ -13,6 +13,7 @@ use serde_with::{serde_as, DisplayFromStr};
 /// Represents an entry of the `/relay/v1/builder/validators` endpoint
 #[serde_as]
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ssz", derive(ssz_derive::Encode, ssz_derive::Decode))]
 pub struct Validator {
     /// The slot number for the validator entry.
     #[serde_as(as = "DisplayFromStr")]
@@ -26,6 +27,7 @@ pub struct Validator {
 
 /// Details of a validator registration.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ssz", derive(ssz_derive::Encode, ssz_derive::Decode))]
 pub struct ValidatorRegistration {
     /// The registration message.
     pub message: ValidatorRegistrationMessage,
@@ -36,6 +38,7 @@ pub struct ValidatorRegistration {
 /// Represents the message of a validator registration.
 #[serde_as]
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ssz", derive(ssz_derive::Encode, ssz_derive::Decode))]
 pub struct ValidatorRegistrationMessage {
     /// The fee recipient's address.
     pub fee_recipient: Address,
@@ -492,6 +495,9 @@ pub struct ProposerPayloadsDeliveredQuery {
     /// A specific slot
     #[serde(skip_serializing_if = "Option::is_none")]
     pub slot: Option<u64>,
+    /// A starting slot for multiple results, entries are returned for this slot and earlier ones
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<u64>,
     /// Maximum number of entries (200 max)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<u64>,
@@ -519,6 +525,12 @@ impl ProposerPayloadsDeliveredQuery {
         self
     }
 
+    /// Sets the starting slot for multiple results
+    pub const fn cursor(mut self, cursor: u64) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+
     /// Sets the maximum number of entries (200 max)
     pub const fn limit(mut self, limit: u64) -> Self {
         self.limit = Some(limit);
@@ -4,6 +4,7 @@ use alloy_primitives::{Bytes, B256, U256};
 
 /// Error that can occur when handling payloads.
 #[derive(Debug, derive_more::Display)]
+#[non_exhaustive]
 pub enum PayloadError {
     /// Invalid payload extra data.
     #[display("invalid payload extra data: {_0}")]
@@ -56,6 +57,98 @@ pub enum PayloadError {
     /// requests present in pre-prague payload.
     #[display("requests present in pre-prague payload")]
     PrePragueBlockRequests,
+    /// requests missing in post-prague payload.
+    #[display("requests missing in post-prague payload")]
+    PostPragueBlockWithoutRequests,
+    /// block access list present in pre-amsterdam payload.
+    #[display("block access list present in pre-amsterdam payload")]
+    PreAmsterdamBlockWithBlockAccessList,
+    /// block access list missing in post-amsterdam payload.
+    #[display("block access list missing in post-amsterdam payload")]
+    PostAmsterdamBlockWithoutBlockAccessList,
+    /// The execution requests are empty, unordered or contain duplicate request types.
+    #[display("execution requests must be non-empty and strictly ordered by request type")]
+    InvalidRequests,
+    /// The payload uses more gas than its gas limit.
+    #[display("gas used {gas_used} exceeds gas limit {gas_limit}")]
+    GasUsedExceedsGasLimit {
+        /// The gas used by the payload.
+        gas_used: u64,
+        /// The gas limit of the payload.
+        gas_limit: u64,
+    },
+    /// The gas limit changed by too much compared to the parent, or is below the minimum.
+    #[display("gas limit {gas_limit} is out of bounds for parent gas limit {parent_gas_limit}")]
+    GasLimit {
+        /// The gas limit of the parent block.
+        parent_gas_limit: u64,
+        /// The gas limit of the payload.
+        gas_limit: u64,
+    },
+    /// The base fee does not match the base fee derived from the parent.
+    #[display("base fee mismatch: want {expected}, got {got}")]
+    BaseFeeMismatch {
+        /// The base fee derived from the parent.
+        expected: u64,
+        /// The base fee of the payload.
+        got: u64,
+    },
+    /// The blob gas used does not match the blobs of the transactions.
+    #[display("blob gas used mismatch: want {expected}, got {got}")]
+    BlobGasUsedMismatch {
+        /// The blob gas used by the blob transactions.
+        expected: u64,
+        /// The blob gas used of the payload.
+        got: u64,
+    },
+    /// The excess blob gas does not match the excess blob gas derived from the parent.
+    #[display("excess blob gas mismatch: want {expected}, got {got}")]
+    ExcessBlobGasMismatch {
+        /// The excess blob gas derived from the parent.
+        expected: u64,
+        /// The excess blob gas of the payload.
+        got: u64,
+    },
+    /// The payload contains more blobs than allowed per block.
+    #[display("too many blobs: {count} exceeds the maximum of {max}")]
+    TooManyBlobs {
+        /// The number of blobs in the payload.
+        count: u64,
+        /// The maximum number of blobs per block.
+        max: u64,
+    },
+    /// A transaction of the payload contains more blobs than allowed per transaction.
+    #[display("too many blobs in transaction: {count} exceeds the maximum of {max}")]
+    TooManyBlobsPerTransaction {
+        /// The number of blobs in the transaction.
+        count: u64,
+        /// The maximum number of blobs per transaction.
+        max: u64,
+    },
+    /// The payload does not build on the given parent.
+    #[display("parent hash mismatch: want {expected}, got {got}")]
+    ParentHash {
+        /// The hash of the parent block.
+        expected: B256,
+        /// The parent hash of the payload.
+        got: B256,
+    },
+    /// The block number is not the successor of the parent block number.
+    #[display("block number mismatch: want {expected}, got {got}")]
+    BlockNumber {
+        /// The successor of the parent block number.
+        expected: u64,
+        /// The block number of the payload.
+        got: u64,
+    },
+    /// The timestamp is not after the parent timestamp.
+    #[display("timestamp {timestamp} is not after parent timestamp {parent_timestamp}")]
+    Timestamp {
+        /// The timestamp of the parent block.
+        parent_timestamp: u64,
+        /// The timestamp of the payload.
+        timestamp: u64,
+    },
     /// Invalid payload block hash.
     #[display("block hash mismatch: want {execution}, got {consensus}")]
     BlockHash {
@@ -21,7 +21,7 @@ mod forkchoice;
 pub use forkchoice::*;
 
 mod version;
-pub use version::ForkchoiceUpdateVersion;
+pub use version::{EngineApiMessageVersion, ForkchoiceUpdateVersion};
 
 mod identification;
 pub use identification::*;
@@ -37,6 +37,9 @@ pub use payload::*;
 mod error;
 pub use error::*;
 
+mod validation;
+pub use validation::PayloadValidator;
+
 mod transition;
 pub use transition::*;
 
@@ -0,0 +1,451 @@
+//! Validation of execution payloads against the consensus rules.
+
+use crate::{EngineApiMessageVersion, ExecutionPayload, ExecutionPayloadSidecar, PayloadError};
+use alloc::vec::Vec;
+use alloy_consensus::{
+    constants::MAXIMUM_EXTRA_DATA_SIZE, Block, BlockHeader, Header, Transaction,
+};
+use alloy_eips::{
+    eip1559::{BaseFeeParams, GAS_LIMIT_BOUND_DIVISOR},
+    eip2718::Decodable2718,
+    eip4844::DATA_GAS_PER_BLOB,
+    eip7840::BlobParams,
+};
+use alloy_primitives::Sealed;
+
+/// The minimum gas limit of a block.
+const MINIMUM_GAS_LIMIT: u64 = 5000;
+
+/// Validates execution payloads against the consensus rules of the fork that introduced an
+/// [`EngineApiMessageVersion`].
+///
+/// This performs all checks that don't require executing the transactions, so payloads can be
+/// rejected offline: the presence of fork-specific fields, the extra data size, the block hash
+/// (which commits to the transactions root, withdrawals root and requests hash), the blob
+/// versioned hashes and blob limits, the EIP-7685 requests and, given the parent header, the gas
+/// limit, base fee and excess blob gas.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PayloadValidator {
+    version: EngineApiMessageVersion,
+    base_fee_params: BaseFeeParams,
+    blob_params: BlobParams,
+}
+
+impl PayloadValidator {
+    /// Creates a new validator for the given version, with the mainnet base fee and blob
+    /// parameters of the corresponding fork.
+    pub const fn new(version: EngineApiMessageVersion) -> Self {
+        let blob_params = match version {
+            EngineApiMessageVersion::V1
+            | EngineApiMessageVersion::V2
+            | EngineApiMessageVersion::V3 => BlobParams::cancun(),
+            EngineApiMessageVersion::V4 => BlobParams::prague(),
+            EngineApiMessageVersion::V5 | EngineApiMessageVersion::V6 => BlobParams::osaka(),
+        };
+        Self { version, base_fee_params: BaseFeeParams::ethereum(), blob_params }
+    }
+
+    /// Sets the base fee parameters.
+    pub const fn with_base_fee_params(mut self, base_fee_params: BaseFeeParams) -> Self {
+        self.base_fee_params = base_fee_params;
+        self
+    }
+
+    /// Sets the blob parameters, e.g. for a blob parameter only fork.
+    pub const fn with_blob_params(mut self, blob_params: BlobParams) -> Self {
+        self.blob_params = blob_params;
+        self
+    }
+
+    /// Returns the engine API message version.
+    pub const fn version(&self) -> EngineApiMessageVersion {
+        self.version
+    }
+
+    /// Checks that the payload and sidecar contain exactly the fields of the fork.
+    pub fn validate_fork_fields(
+        &self,
+        payload: &ExecutionPayload,
+        sidecar: &ExecutionPayloadSidecar,
+    ) -> Result<(), PayloadError> {
+        let shanghai = self.version >= EngineApiMessageVersion::V2;
+        let cancun = self.version >= EngineApiMessageVersion::V3;
+        let prague = self.version >= EngineApiMessageVersion::V4;
+        let amsterdam = self.version >= EngineApiMessageVersion::V6;
+
+        match (payload.withdrawals().is_some(), shanghai) {
+            (true, false) => return Err(PayloadError::PreShanghaiBlockWithWithdrawals),
+            (false, true) => return Err(PayloadError::PostShanghaiBlockWithoutWithdrawals),
+            _ => {}
+        }
+        match (payload.as_v3().is_some(), cancun) {
+            (true, false) => return Err(PayloadError::PreCancunBlockWithBlobGasUsed),
+            (false, true) => return Err(PayloadError::PostCancunBlockWithoutBlobGasUsed),
+            _ => {}
+        }
+        match (sidecar.cancun().is_some(), cancun) {
+            (true, false) => return Err(PayloadError::PreCancunBlockWithParentBeaconBlockRoot),
+            (false, true) => return Err(PayloadError::PostCancunBlockWithoutParentBeaconBlockRoot),
+            _ => {}
+        }
+        match (sidecar.prague().is_some(), prague) {
+            (true, false) => return Err(PayloadError::PrePragueBlockRequests),
+            (false, true) => return Err(PayloadError::PostPragueBlockWithoutRequests),
+            _ => {}
+        }
+        match (payload.as_v4().is_some(), amsterdam) {
+            (true, false) => Err(PayloadError::PreAmsterdamBlockWithBlockAccessList),
+            (false, true) => Err(PayloadError::PostAmsterdamBlockWithoutBlockAccessList),
+            _ => Ok(()),
+        }
+    }
+
+    /// Validates the payload on its own and converts it into a block.
+    ///
+    /// In addition to [`validate_fork_fields`](Self::validate_fork_fields), this checks:
+    ///  - the extra data is at most [`MAXIMUM_EXTRA_DATA_SIZE`] bytes,
+    ///  - the transaction types allowed in the fork,
+    ///  - the EIP-7685 requests are non-empty and strictly ordered by type,
+    ///  - the block hash matches the header computed from the payload,
+    ///  - the gas used doesn't exceed the gas limit,
+    ///  - no transaction has more blobs than allowed per transaction (6 since Osaka),
+    ///  - the blob versioned hashes match the blob transactions, and the blob gas used matches the
+    ///    number of blobs.
+    pub fn validate<T>(
+        &self,
+        payload: ExecutionPayload,
+        sidecar: &ExecutionPayloadSidecar,
+    ) -> Result<Block<T>, PayloadError>
+    where
+        T: Decodable2718 + Transaction,
+    {
+        self.validate_fork_fields(&payload, sidecar)?;
+        let extra_data = &payload.as_v1().extra_data;
+        if extra_data.len() > MAXIMUM_EXTRA_DATA_SIZE {
+            return Err(PayloadError::ExtraData(extra_data.clone()));
+        }
+        if let Some(requests) = sidecar.requests() {
+            let mut last_type = None;
+            for request in requests.iter() {
+                match request.split_first() {
+                    Some((&ty, data)) if !data.is_empty() && last_type < Some(ty) => {
+                        last_type = Some(ty);
+                    }
+                    _ => return Err(PayloadError::InvalidRequests),
+                }
+            }
+        }
+
+        let block_hash = payload.block_hash();
+        let block = payload.try_into_block_with_sidecar::<T>(sidecar)?;
+        let computed = block.header.hash_slow();
+        if computed != block_hash {
+            return Err(PayloadError::BlockHash { execution: computed, consensus: block_hash });
+        }
+
+        let header = &block.header;
+        if header.gas_used > header.gas_limit {
+            return Err(PayloadError::GasUsedExceedsGasLimit {
+                gas_used: header.gas_used,
+                gas_limit: header.gas_limit,
+            });
+        }
+
+        let transactions = &block.body.transactions;
+        if self.version < EngineApiMessageVersion::V3
+            && transactions.iter().any(|tx| tx.is_eip4844())
+        {
+            return Err(PayloadError::PreCancunBlockWithBlobTransactions);
+        }
+        if self.version < EngineApiMessageVersion::V4
+            && transactions.iter().any(|tx| tx.is_eip7702())
+        {
+            return Err(PayloadError::PrePragueBlockWithEip7702Transactions);
+        }
+
+        let max_blobs_per_tx = self.blob_params.max_blobs_per_tx;
+        for hashes in transactions.iter().filter_map(|tx| tx.blob_versioned_hashes()) {
+            let count = hashes.len() as u64;
+            if count > max_blobs_per_tx {
+                return Err(PayloadError::TooManyBlobsPerTransaction {
+                    count,
+                    max: max_blobs_per_tx,
+                });
+            }
+        }
+
+        if let Some(expected_hashes) = sidecar.versioned_hashes() {
+            let versioned_hashes: Vec<_> = transactions
+                .iter()
+                .filter_map(|tx| tx.blob_versioned_hashes())
+                .flatten()
+                .copied()
+                .collect();
+            if &versioned_hashes != expected_hashes {
+                return Err(PayloadError::InvalidVersionedHashes);
+            }
+
+            let count = versioned_hashes.len() as u64;
+            if count > self.blob_params.max_blob_count {
+                return Err(PayloadError::TooManyBlobs {
+                    count,
+                    max: self.blob_params.max_blob_count,
+                });
+            }
+            let blob_gas_used = header.blob_gas_used.unwrap_or_default();
+            if blob_gas_used != count * DATA_GAS_PER_BLOB {
+                return Err(PayloadError::BlobGasUsedMismatch {
+                    expected: count * DATA_GAS_PER_BLOB,
+                    got: blob_gas_used,
+                });
+            }
+        }
+
+        Ok(block)
+    }
+
+    /// Validates a header against its parent: the block number, parent hash and timestamp, the
+    /// gas limit bounds, the base fee and, since Cancun, the excess blob gas.
+    pub fn validate_against_parent<H: BlockHeader>(
+        &self,
+        header: &Header,
+        parent: &Sealed<H>,
+    ) -> Result<(), PayloadError> {
+        if header.number != parent.number() + 1 {
+            return Err(PayloadError::BlockNumber {
+                expected: parent.number() + 1,
+                got: header.number,
+            });
+        }
+        if header.parent_hash != parent.hash() {
+            return Err(PayloadError::ParentHash {
+                expected: parent.hash(),
+                got: header.parent_hash,
+            });
+        }
+        if header.timestamp <= parent.timestamp() {
+            return Err(PayloadError::Timestamp {
+                parent_timestamp: parent.timestamp(),
+                timestamp: header.timestamp,
+            });
+        }
+
+        let parent_gas_limit = parent.gas_limit();
+        if header.gas_limit.abs_diff(parent_gas_limit) >= parent_gas_limit / GAS_LIMIT_BOUND_DIVISOR
+            || header.gas_limit < MINIMUM_GAS_LIMIT
+        {
+            return Err(PayloadError::GasLimit { parent_gas_limit, gas_limit: header.gas_limit });
+        }
+
+        if let Some(expected) = parent.next_block_base_fee(self.base_fee_params) {
+            let got = header.base_fee_per_gas.unwrap_or_default();
+            if got != expected {
+                return Err(PayloadError::BaseFeeMismatch { expected, got });
+            }
+        }
+
+        if self.version >= EngineApiMessageVersion::V3 {
+            // The first Cancun block starts with zero excess blob gas.
+            let expected = parent.next_block_excess_blob_gas(self.blob_params).unwrap_or_default();
+            let got = header.excess_blob_gas.unwrap_or_default();
+            if got != expected {
+                return Err(PayloadError::ExcessBlobGasMismatch { expected, got });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CancunPayloadFields, ExecutionPayloadV3, PraguePayloadFields};
+    use alloy_consensus::{
+        BlockBody, Signed, TxEip4844, TxEip4844Variant, TxEnvelope, EMPTY_ROOT_HASH,
+    };
+    use alloy_eips::eip7685::{Requests, EMPTY_REQUESTS_HASH};
+    use alloy_primitives::{Bytes, Sealable, Signature, B256};
+
+    fn parent() -> Sealed<Header> {
+        Header {
+            number: 9,
+            timestamp: 108,
+            gas_limit: 30_000_000,
+            gas_used: 15_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            blob_gas_used: Some(0),
+            excess_blob_gas: Some(0),
+            ..Default::default()
+        }
+        .seal_slow()
+    }
+
+    fn blob_tx(versioned_hashes: Vec<B256>) -> TxEnvelope {
+        let tx = TxEip4844 { blob_versioned_hashes: versioned_hashes, ..Default::default() };
+        let signed =
+            Signed::new_unhashed(TxEip4844Variant::TxEip4844(tx), Signature::test_signature());
+        TxEnvelope::Eip4844(signed)
+    }
+
+    /// Returns a valid Prague payload with one blob transaction and its sidecar.
+    fn prague_payload() -> (ExecutionPayload, ExecutionPayloadSidecar) {
+        payload_with_blobs(vec![B256::with_last_byte(1)])
+    }
+
+    /// Returns a valid Prague payload with a blob transaction carrying the given blobs.
+    fn payload_with_blobs(
+        versioned_hashes: Vec<B256>,
+    ) -> (ExecutionPayload, ExecutionPayloadSidecar) {
+        let parent = parent();
+        let mut block = Block {
+            header: Header {
+                parent_hash: parent.hash(),
+                number: 10,
+                timestamp: 120,
+                gas_limit: 30_000_000,
+                base_fee_per_gas: Some(1_000_000_000),
+                withdrawals_root: Some(EMPTY_ROOT_HASH),
+                blob_gas_used: Some(versioned_hashes.len() as u64 * DATA_GAS_PER_BLOB),
+                excess_blob_gas: Some(0),
+                parent_beacon_block_root: Some(B256::ZERO),
+                requests_hash: Some(EMPTY_REQUESTS_HASH),
+                ..Default::default()
+            },
+            body: BlockBody {
+                transactions: vec![blob_tx(versioned_hashes.clone())],
+                ommers: Vec::new(),
+                withdrawals: Some(Default::default()),
+            },
+        };
+        block.header.transactions_root =
+            alloy_consensus::proofs::calculate_transaction_root(&block.body.transactions);
+
+        let payload = ExecutionPayload::V3(ExecutionPayloadV3::from_block_slow(&block));
+        let sidecar = ExecutionPayloadSidecar::v4(
+            CancunPayloadFields::new(B256::ZERO, versioned_hashes),
+            PraguePayloadFields::new(Requests::default()),
+        );
+        (payload, sidecar)
+    }
+
+    #[test]
+    fn valid_prague_payload() {
+        let (payload, sidecar) = prague_payload();
+        let validator = PayloadValidator::new(EngineApiMessageVersion::V4);
+        let block = validator.validate::<TxEnvelope>(payload, &sidecar).unwrap();
+        validator.validate_against_parent(&block.header, &parent()).unwrap();
+    }
+
+    #[test]
+    fn fork_fields() {
+        let (payload, sidecar) = prague_payload();
+        assert!(matches!(
+            PayloadValidator::new(EngineApiMessageVersion::V3)
+                .validate_fork_fields(&payload, &sidecar),
+            Err(PayloadError::PrePragueBlockRequests)
+        ));
+        assert!(matches!(
+            PayloadValidator::new(EngineApiMessageVersion::V6)
+                .validate_fork_fields(&payload, &sidecar),
+            Err(PayloadError::PostAmsterdamBlockWithoutBlockAccessList)
+        ));
+        assert!(matches!(
+            PayloadValidator::new(EngineApiMessageVersion::V4)
+                .validate_fork_fields(&payload, &ExecutionPayloadSidecar::none()),
+            Err(PayloadError::PostCancunBlockWithoutParentBeaconBlockRoot)
+        ));
+    }
+
+    #[test]
+    fn invalid_payloads() {
+        let validator = PayloadValidator::new(EngineApiMessageVersion::V4);
+
+        let (mut payload, sidecar) = prague_payload();
+        payload.as_v1_mut().gas_used = 1;
+        let err = validator.validate::<TxEnvelope>(payload, &sidecar).unwrap_err();
+        assert!(err.is_block_hash_mismatch());
+
+        let (payload, _) = prague_payload();
+        let sidecar = ExecutionPayloadSidecar::v4(
+            CancunPayloadFields::new(B256::ZERO, vec![B256::with_last_byte(2)]),
+            PraguePayloadFields::new(Requests::default()),
+        );
+        let err = validator.validate::<TxEnvelope>(payload, &sidecar).unwrap_err();
+        assert!(err.is_invalid_versioned_hashes());
+
+        let (payload, _) = prague_payload();
+        let sidecar = ExecutionPayloadSidecar::v4(
+            CancunPayloadFields::new(B256::ZERO, vec![B256::with_last_byte(1)]),
+            PraguePayloadFields::new(Requests::new(vec![
+                Bytes::from_static(&[1, 0xaa]),
+                Bytes::from_static(&[0, 0xbb]),
+            ])),
+        );
+        let err = validator.validate::<TxEnvelope>(payload, &sidecar).unwrap_err();
+        assert!(matches!(err, PayloadError::InvalidRequests));
+
+        let (mut payload, sidecar) = prague_payload();
+        payload.as_v1_mut().extra_data = Bytes::from(vec![0; MAXIMUM_EXTRA_DATA_SIZE + 1]);
+        let err = validator.validate::<TxEnvelope>(payload, &sidecar).unwrap_err();
+        assert!(matches!(err, PayloadError::ExtraData(data) if data.len() == 33));
+    }
+
+    #[test]
+    fn blobs_per_transaction() {
+        let versioned_hashes: Vec<_> = (1..=7).map(B256::with_last_byte).collect();
+
+        // Prague only limits the blobs per block
+        let (payload, sidecar) = payload_with_blobs(versioned_hashes.clone());
+        PayloadValidator::new(EngineApiMessageVersion::V4)
+            .validate::<TxEnvelope>(payload, &sidecar)
+            .unwrap();
+
+        let (payload, sidecar) = payload_with_blobs(versioned_hashes);
+        let err = PayloadValidator::new(EngineApiMessageVersion::V5)
+            .validate::<TxEnvelope>(payload, &sidecar)
+            .unwrap_err();
+        assert!(matches!(err, PayloadError::TooManyBlobsPerTransaction { count: 7, max: 6 }));
+
+        let (payload, sidecar) = payload_with_blobs((1..=6).map(B256::with_last_byte).collect());
+        PayloadValidator::new(EngineApiMessageVersion::V5)
+            .validate::<TxEnvelope>(payload, &sidecar)
+            .unwrap();
+    }
+
+    #[test]
+    fn invalid_against_parent() {
+        let validator = PayloadValidator::new(EngineApiMessageVersion::V4);
+        let (payload, sidecar) = prague_payload();
+        let block = validator.validate::<TxEnvelope>(payload, &sidecar).unwrap();
+        let parent = parent();
+
+        let mut header = block.header.clone();
+        header.gas_limit = 30_000_000 + 30_000_000 / 1024;
+        assert!(matches!(
+            validator.validate_against_parent(&header, &parent),
+            Err(PayloadError::GasLimit { parent_gas_limit: 30_000_000, .. })
+        ));
+
+        let mut header = block.header.clone();
+        header.base_fee_per_gas = Some(7);
+        assert!(matches!(
+            validator.validate_against_parent(&header, &parent),
+            Err(PayloadError::BaseFeeMismatch { expected: 1_000_000_000, got: 7 })
+        ));
+
+        let mut header = block.header.clone();
+        header.excess_blob_gas = Some(DATA_GAS_PER_BLOB);
+        assert!(matches!(
+            validator.validate_against_parent(&header, &parent),
+            Err(PayloadError::ExcessBlobGasMismatch { expected: 0, .. })
+        ));
+
+        let mut header = block.header;
+        header.number = 11;
+        assert!(matches!(
+            validator.validate_against_parent(&header, &parent),
+            Err(PayloadError::BlockNumber { expected: 10, got: 11 })
+        ));
+    }
+}
@@ -14,3 +14,56 @@ pub enum ForkchoiceUpdateVersion {
     /// Version 4 of the engine api.
     V4 = 4,
 }
+
+/// The version of the engine API messages exchanged for a fork.
+///
+/// Each fork introduces a new `engine_getPayload` version, but not necessarily new
+/// `engine_newPayload` and `engine_forkchoiceUpdated` versions; see
+/// [`forkchoice_updated_version`](Self::forkchoice_updated_version).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u32)]
+pub enum EngineApiMessageVersion {
+    /// Version 1, introduced in Paris.
+    V1 = 1,
+    /// Version 2, introduced in Shanghai.
+    V2 = 2,
+    /// Version 3, introduced in Cancun.
+    V3 = 3,
+    /// Version 4, introduced in Prague.
+    V4 = 4,
+    /// Version 5, introduced in Osaka.
+    V5 = 5,
+    /// Version 6, introduced in Amsterdam.
+    V6 = 6,
+}
+
+impl EngineApiMessageVersion {
+    /// Returns the version of `engine_forkchoiceUpdated`.
+    pub const fn forkchoice_updated_version(self) -> ForkchoiceUpdateVersion {
+        match self {
+            Self::V1 => ForkchoiceUpdateVersion::V1,
+            Self::V2 => ForkchoiceUpdateVersion::V2,
+            Self::V3 | Self::V4 | Self::V5 => ForkchoiceUpdateVersion::V3,
+            Self::V6 => ForkchoiceUpdateVersion::V4,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forkchoice_updated_versions() {
+        assert_eq!(
+            EngineApiMessageVersion::V5.forkchoice_updated_version(),
+            ForkchoiceUpdateVersion::V3
+        );
+        assert_eq!(
+            EngineApiMessageVersion::V6.forkchoice_updated_version(),
+            ForkchoiceUpdateVersion::V4
+        );
+        assert!(EngineApiMessageVersion::V3 < EngineApiMessageVersion::V4);
+    }
+}
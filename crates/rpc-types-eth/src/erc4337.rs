@@ -2,9 +2,17 @@ use crate::{Log, TransactionReceipt};
 use alloc::vec::Vec;
 use alloy_consensus::conditional::BlockConditionalAttributes;
 use alloy_primitives::{
+    address, keccak256,
     map::{AddressHashMap, HashMap},
     Address, BlockNumber, Bytes, B256, U256,
 };
+use alloy_sol_types::SolValue;
+
+/// The address of the ERC-4337 v0.6 `EntryPoint` contract.
+pub const ENTRY_POINT_V06_ADDRESS: Address = address!("0x5FF137D4b0FDCD49DcA30c7CF57E578a026d2789");
+
+/// The address of the ERC-4337 v0.7 `EntryPoint` contract.
+pub const ENTRY_POINT_V07_ADDRESS: Address = address!("0x0000000071727De22E5E9d8BAf0edAc6f37da032");
 
 /// Options for conditional raw transaction submissions.
 ///
@@ -212,6 +220,29 @@ pub struct UserOperation {
     pub signature: Bytes,
 }
 
+impl UserOperation {
+    /// Computes the hash of the user operation, as returned by `EntryPoint.getUserOpHash`.
+    ///
+    /// The hash commits to all fields except the signature, as well as the entry point address
+    /// and the chain ID.
+    pub fn hash(&self, entry_point: Address, chain_id: u64) -> B256 {
+        let packed = (
+            self.sender,
+            self.nonce,
+            keccak256(&self.init_code),
+            keccak256(&self.call_data),
+            self.call_gas_limit,
+            self.verification_gas_limit,
+            self.pre_verification_gas,
+            self.max_fee_per_gas,
+            self.max_priority_fee_per_gas,
+            keccak256(&self.paymaster_and_data),
+        )
+            .abi_encode();
+        keccak256((keccak256(packed), entry_point, U256::from(chain_id)).abi_encode())
+    }
+}
+
 /// [`PackedUserOperation`] in the spec: Entry Point V0.7
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -260,6 +291,85 @@ pub struct PackedUserOperation {
     pub signature: Bytes,
 }
 
+impl PackedUserOperation {
+    /// Returns the `initCode` of the on-chain representation, i.e. `factory || factoryData`.
+    ///
+    /// Empty if no factory is set.
+    pub fn init_code(&self) -> Bytes {
+        self.factory
+            .map(|factory| {
+                [factory.as_slice(), self.factory_data.as_ref().map_or(&[], |data| &data[..])]
+                    .concat()
+                    .into()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the `accountGasLimits` of the on-chain representation, i.e. the verification gas
+    /// limit and the call gas limit packed as two 16 byte values.
+    pub fn account_gas_limits(&self) -> B256 {
+        pack_u128s(self.verification_gas_limit, self.call_gas_limit)
+    }
+
+    /// Returns the `gasFees` of the on-chain representation, i.e. the max priority fee per gas and
+    /// the max fee per gas packed as two 16 byte values.
+    pub fn gas_fees(&self) -> B256 {
+        pack_u128s(self.max_priority_fee_per_gas, self.max_fee_per_gas)
+    }
+
+    /// Returns the `paymasterAndData` of the on-chain representation, i.e. `paymaster ||
+    /// paymasterVerificationGasLimit || paymasterPostOpGasLimit || paymasterData`.
+    ///
+    /// Empty if no paymaster is set.
+    pub fn paymaster_and_data(&self) -> Bytes {
+        self.paymaster
+            .map(|paymaster| {
+                let verification_gas_limit = self
+                    .paymaster_verification_gas_limit
+                    .unwrap_or_default()
+                    .saturating_to::<u128>();
+                let post_op_gas_limit =
+                    self.paymaster_post_op_gas_limit.unwrap_or_default().saturating_to::<u128>();
+                [
+                    paymaster.as_slice(),
+                    &verification_gas_limit.to_be_bytes(),
+                    &post_op_gas_limit.to_be_bytes(),
+                    self.paymaster_data.as_ref().map_or(&[], |data| &data[..]),
+                ]
+                .concat()
+                .into()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Computes the hash of the user operation, as returned by `EntryPoint.getUserOpHash`.
+    ///
+    /// The hash commits to all fields except the signature, as well as the entry point address
+    /// and the chain ID.
+    pub fn hash(&self, entry_point: Address, chain_id: u64) -> B256 {
+        let packed = (
+            self.sender,
+            self.nonce,
+            keccak256(self.init_code()),
+            keccak256(&self.call_data),
+            self.account_gas_limits(),
+            self.pre_verification_gas,
+            self.gas_fees(),
+            keccak256(self.paymaster_and_data()),
+        )
+            .abi_encode();
+        keccak256((keccak256(packed), entry_point, U256::from(chain_id)).abi_encode())
+    }
+}
+
+/// Packs two values into a single word, with `high` in the upper 16 bytes.
+fn pack_u128s(high: U256, low: U256) -> B256 {
+    let mut word = B256::ZERO;
+    word[..16].copy_from_slice(&high.saturating_to::<u128>().to_be_bytes());
+    word[16..].copy_from_slice(&low.saturating_to::<u128>().to_be_bytes());
+    word
+}
+
 /// Send User Operation
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -270,6 +380,62 @@ pub enum SendUserOperation {
     EntryPointV07(PackedUserOperation),
 }
 
+impl SendUserOperation {
+    /// Returns the address of the account making the operation.
+    pub const fn sender(&self) -> Address {
+        match self {
+            Self::EntryPointV06(op) => op.sender,
+            Self::EntryPointV07(op) => op.sender,
+        }
+    }
+
+    /// Returns the nonce of the operation.
+    pub const fn nonce(&self) -> U256 {
+        match self {
+            Self::EntryPointV06(op) => op.nonce,
+            Self::EntryPointV07(op) => op.nonce,
+        }
+    }
+
+    /// Returns the signature of the operation.
+    pub const fn signature(&self) -> &Bytes {
+        match self {
+            Self::EntryPointV06(op) => &op.signature,
+            Self::EntryPointV07(op) => &op.signature,
+        }
+    }
+
+    /// Sets the signature of the operation.
+    pub fn set_signature(&mut self, signature: Bytes) {
+        match self {
+            Self::EntryPointV06(op) => op.signature = signature,
+            Self::EntryPointV07(op) => op.signature = signature,
+        }
+    }
+
+    /// Computes the hash of the user operation for the given entry point and chain ID.
+    ///
+    /// See [`UserOperation::hash`] and [`PackedUserOperation::hash`].
+    pub fn hash(&self, entry_point: Address, chain_id: u64) -> B256 {
+        match self {
+            Self::EntryPointV06(op) => op.hash(entry_point, chain_id),
+            Self::EntryPointV07(op) => op.hash(entry_point, chain_id),
+        }
+    }
+}
+
+impl From<UserOperation> for SendUserOperation {
+    fn from(op: UserOperation) -> Self {
+        Self::EntryPointV06(op)
+    }
+}
+
+impl From<PackedUserOperation> for SendUserOperation {
+    fn from(op: PackedUserOperation) -> Self {
+        Self::EntryPointV07(op)
+    }
+}
+
 /// Response to sending a user operation.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -322,3 +488,102 @@ pub struct UserOperationGasEstimation {
     /// The gas limit for the call.
     pub call_gas_limit: U256,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{b256, bytes, hex};
+
+    fn packed_user_op() -> PackedUserOperation {
+        PackedUserOperation {
+            sender: Address::repeat_byte(0x11),
+            nonce: U256::from(1),
+            factory: Some(Address::repeat_byte(0x22)),
+            factory_data: Some(bytes!("abcd")),
+            call_data: bytes!("b61d27f6"),
+            call_gas_limit: U256::from(0x0200),
+            verification_gas_limit: U256::from(0x0100),
+            pre_verification_gas: U256::from(21_000),
+            max_fee_per_gas: U256::from(0x04),
+            max_priority_fee_per_gas: U256::from(0x03),
+            paymaster: Some(Address::repeat_byte(0x33)),
+            paymaster_verification_gas_limit: Some(U256::from(0x05)),
+            paymaster_post_op_gas_limit: Some(U256::from(0x06)),
+            paymaster_data: Some(bytes!("ef")),
+            signature: Bytes::new(),
+        }
+    }
+
+    #[test]
+    fn packed_user_op_fields() {
+        let op = packed_user_op();
+        assert_eq!(op.init_code(), bytes!("2222222222222222222222222222222222222222abcd"));
+        assert_eq!(
+            op.account_gas_limits(),
+            b256!("0x0000000000000000000000000000010000000000000000000000000000000200")
+        );
+        assert_eq!(
+            op.gas_fees(),
+            b256!("0x0000000000000000000000000000000300000000000000000000000000000004")
+        );
+        assert_eq!(
+            op.paymaster_and_data(),
+            Bytes::from(hex!(
+                "3333333333333333333333333333333333333333"
+                "00000000000000000000000000000005"
+                "00000000000000000000000000000006"
+                "ef"
+            ))
+        );
+
+        let op = PackedUserOperation { factory: None, paymaster: None, ..op };
+        assert!(op.init_code().is_empty());
+        assert!(op.paymaster_and_data().is_empty());
+    }
+
+    // The expected hashes were computed independently of this crate, by ABI encoding the
+    // operations word by word as `UserOperationLib.pack` (v0.6) and `UserOperationLib.encode`
+    // (v0.7) of the `EntryPoint` contracts do, and hashing with
+    // `keccak256(abi.encode(userOpHash, entryPoint, chainId))` as `EntryPoint.getUserOpHash` does.
+    #[test]
+    fn user_op_hash_vectors() {
+        let packed = packed_user_op();
+        assert_eq!(
+            packed.hash(ENTRY_POINT_V07_ADDRESS, 1),
+            b256!("0x9e0a903dc4bd11e00ac0bef7ea55c3b867de727e3ccaa2e1af7e1304eb0717d9")
+        );
+
+        let op = UserOperation {
+            sender: packed.sender,
+            nonce: packed.nonce,
+            init_code: packed.init_code(),
+            call_data: packed.call_data,
+            call_gas_limit: packed.call_gas_limit,
+            verification_gas_limit: packed.verification_gas_limit,
+            pre_verification_gas: packed.pre_verification_gas,
+            max_fee_per_gas: packed.max_fee_per_gas,
+            max_priority_fee_per_gas: packed.max_priority_fee_per_gas,
+            paymaster_and_data: bytes!("3333333333333333333333333333333333333333ef"),
+            signature: bytes!("01"),
+        };
+        assert_eq!(
+            op.hash(ENTRY_POINT_V06_ADDRESS, 1),
+            b256!("0x1bfbacd3fa985e35a0dcd267ac27a1015e799ad6f258ea2eeac9cb5ffabbdcca")
+        );
+    }
+
+    #[test]
+    fn user_op_hash_excludes_signature() {
+        let mut op = SendUserOperation::from(packed_user_op());
+        let hash = op.hash(ENTRY_POINT_V07_ADDRESS, 1);
+
+        op.set_signature(bytes!("01"));
+        assert_eq!(op.hash(ENTRY_POINT_V07_ADDRESS, 1), hash);
+        assert_ne!(op.hash(ENTRY_POINT_V07_ADDRESS, 10), hash);
+        assert_ne!(op.hash(ENTRY_POINT_V06_ADDRESS, 1), hash);
+
+        let SendUserOperation::EntryPointV07(mut packed) = op else { unreachable!() };
+        packed.paymaster_data = None;
+        assert_ne!(packed.hash(ENTRY_POINT_V07_ADDRESS, 1), hash);
+    }
+}
@@ -1,6 +1,9 @@
 //! Geth call tracer types.
 
-use crate::parity::{ActionType, CallType, CreationMethod, LocalizedTransactionTrace};
+use crate::parity::{
+    Action, ActionType, CallAction, CallOutput, CallType, CreateAction, CreateOutput,
+    CreationMethod, LocalizedTransactionTrace, SelfdestructAction, TraceOutput, TransactionTrace,
+};
 use alloy_primitives::{Address, Bytes, Selector, B256, U256};
 use serde::{Deserialize, Serialize};
 
@@ -112,6 +115,194 @@ impl CallFrame {
     pub fn is_auth_call(&self) -> bool {
         self.typ == CallKind::AuthCall
     }
+
+    /// Returns the [`CallKind`] of this frame, or `None` for `SELFDESTRUCT` and unknown frames.
+    pub fn kind(&self) -> Option<CallKind> {
+        Some(match self.typ.as_str() {
+            "CALL" => CallKind::Call,
+            "STATICCALL" => CallKind::StaticCall,
+            "CALLCODE" => CallKind::CallCode,
+            "DELEGATECALL" => CallKind::DelegateCall,
+            "AUTHCALL" => CallKind::AuthCall,
+            "CREATE" => CallKind::Create,
+            "CREATE2" => CallKind::Create2,
+            _ => return None,
+        })
+    }
+
+    /// Converts this call tree into flat parity traces in depth-first pre-order, as returned by
+    /// `trace_transaction`.
+    ///
+    /// Geth errors are converted to their parity equivalents. Logs are dropped, since parity
+    /// traces don't record them.
+    pub fn to_parity_traces(&self) -> Vec<TransactionTrace> {
+        let mut traces = Vec::new();
+        self.push_parity_traces(Vec::new(), &mut traces);
+        traces
+    }
+
+    fn push_parity_traces(&self, trace_address: Vec<usize>, traces: &mut Vec<TransactionTrace>) {
+        traces.push(self.to_parity_trace(trace_address.clone()));
+        for (index, call) in self.calls.iter().enumerate() {
+            let mut trace_address = trace_address.clone();
+            trace_address.push(index);
+            call.push_parity_traces(trace_address, traces);
+        }
+    }
+
+    fn to_parity_trace(&self, trace_address: Vec<usize>) -> TransactionTrace {
+        let gas = self.gas.saturating_to();
+        let gas_used = self.gas_used.saturating_to();
+        let value = self.value.unwrap_or_default();
+        let output = self.output.clone().unwrap_or_default();
+
+        let (action, result) = match self.kind() {
+            None if self.typ == "SELFDESTRUCT" => (
+                Action::Selfdestruct(SelfdestructAction {
+                    address: self.from,
+                    balance: value,
+                    refund_address: self.to.unwrap_or_default(),
+                }),
+                None,
+            ),
+            Some(kind @ (CallKind::Create | CallKind::Create2)) => (
+                Action::Create(CreateAction {
+                    from: self.from,
+                    gas,
+                    init: self.input.clone(),
+                    value,
+                    creation_method: kind.into(),
+                }),
+                // geth clears the address of failed creations
+                self.to.map(|address| {
+                    TraceOutput::Create(CreateOutput { address, code: output, gas_used })
+                }),
+            ),
+            kind => (
+                Action::Call(CallAction {
+                    from: self.from,
+                    call_type: kind.map(Into::into).unwrap_or_default(),
+                    gas,
+                    input: self.input.clone(),
+                    to: self.to.unwrap_or_default(),
+                    value,
+                }),
+                Some(TraceOutput::Call(CallOutput { gas_used, output })),
+            ),
+        };
+
+        // parity only reports the result of successful and reverted calls
+        let result = result.filter(|_| self.error.is_none() || self.is_revert());
+        TransactionTrace {
+            action,
+            error: self.error.as_deref().map(|error| geth_to_parity_error(error).into()),
+            result,
+            subtraces: self.calls.len(),
+            trace_address,
+        }
+    }
+
+    /// Builds the call tree of a transaction from its flat parity traces, as returned by
+    /// `trace_transaction`.
+    ///
+    /// The traces must be in depth-first pre-order, as returned by nodes. Parity errors are
+    /// converted to their geth equivalents, and block rewards are skipped.
+    ///
+    /// Returns `None` if there are no traces.
+    pub fn from_parity_traces<'a>(
+        traces: impl IntoIterator<Item = &'a TransactionTrace>,
+    ) -> Option<Self> {
+        let mut traces = traces.into_iter().filter(|trace| !trace.action.is_reward()).peekable();
+        let root = traces.next()?;
+        let mut frame = Self::from_parity_trace(root);
+        frame.push_parity_children(root.trace_address.len() + 1, &mut traces);
+        Some(frame)
+    }
+
+    fn push_parity_children<'a>(
+        &mut self,
+        depth: usize,
+        traces: &mut core::iter::Peekable<impl Iterator<Item = &'a TransactionTrace>>,
+    ) {
+        while let Some(trace) = traces.next_if(|trace| trace.trace_address.len() == depth) {
+            let mut frame = Self::from_parity_trace(trace);
+            frame.push_parity_children(depth + 1, traces);
+            self.calls.push(frame);
+        }
+    }
+
+    fn from_parity_trace(trace: &TransactionTrace) -> Self {
+        let error = trace.error.as_deref().map(|error| parity_to_geth_error(error).to_string());
+        let output = trace.result.as_ref().map(|result| result.output().clone());
+        let gas_used = |gas: u64| U256::from(trace.result.as_ref().map_or(gas, |r| r.gas_used()));
+
+        match &trace.action {
+            Action::Call(call) => Self {
+                from: call.from,
+                gas: U256::from(call.gas),
+                gas_used: gas_used(call.gas),
+                to: Some(call.to),
+                input: call.input.clone(),
+                output,
+                error,
+                value: (!matches!(call.call_type, CallType::StaticCall | CallType::DelegateCall))
+                    .then_some(call.value),
+                typ: match call.call_type {
+                    CallType::None => CallKind::Call.to_string(),
+                    call_type => call_type.to_string(),
+                },
+                ..Default::default()
+            },
+            Action::Create(create) => Self {
+                from: create.from,
+                gas: U256::from(create.gas),
+                gas_used: gas_used(create.gas),
+                to: trace.result.as_ref().and_then(TraceOutput::created_contract),
+                input: create.init.clone(),
+                output,
+                error,
+                value: Some(create.value),
+                typ: match create.creation_method {
+                    CreationMethod::Create2 => CallKind::Create2,
+                    _ => CallKind::Create,
+                }
+                .to_string(),
+                ..Default::default()
+            },
+            Action::Selfdestruct(selfdestruct) => Self {
+                from: selfdestruct.address,
+                to: Some(selfdestruct.refund_address),
+                value: Some(selfdestruct.balance),
+                typ: "SELFDESTRUCT".to_string(),
+                ..Default::default()
+            },
+            Action::Reward(reward) => Self {
+                to: Some(reward.author),
+                value: Some(reward.value),
+                typ: CallKind::Call.to_string(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Known error messages of geth and their parity equivalents.
+const ERRORS: &[(&str, &str)] = &[
+    ("execution reverted", "Reverted"),
+    ("out of gas", "Out of gas"),
+    ("invalid jump destination", "Bad jump destination"),
+    ("write protection", "Mutable Call In Static Context"),
+];
+
+fn geth_to_parity_error(error: &str) -> &str {
+    if error.starts_with("invalid opcode") {
+        return "Bad instruction";
+    }
+    ERRORS.iter().find(|(geth, _)| error == *geth).map_or(error, |(_, parity)| parity)
+}
+
+fn parity_to_geth_error(error: &str) -> &str {
+    ERRORS.iter().find(|(_, parity)| error == *parity).map_or(error, |(geth, _)| geth)
 }
 
 /// Represents a recorded log that is emitted during a trace call.
@@ -452,6 +643,65 @@ mod tests {
         assert_eq!(log_frame, log_frame_hex);
     }
 
+    #[test]
+    fn test_parity_traces_roundtrip() {
+        let frame = CallFrame {
+            from: Address::with_last_byte(1),
+            gas: U256::from(100_000),
+            gas_used: U256::from(50_000),
+            to: Some(Address::with_last_byte(2)),
+            input: Bytes::from_static(&[1, 2, 3, 4]),
+            output: Some(Bytes::from_static(&[5])),
+            value: Some(U256::ZERO),
+            typ: "CALL".to_string(),
+            calls: vec![
+                CallFrame {
+                    from: Address::with_last_byte(2),
+                    gas: U256::from(60_000),
+                    gas_used: U256::from(30_000),
+                    to: Some(Address::with_last_byte(3)),
+                    input: Bytes::from_static(&[0x60]),
+                    output: Some(Bytes::from_static(&[0x00])),
+                    value: Some(U256::from(1)),
+                    typ: "CREATE2".to_string(),
+                    calls: vec![CallFrame {
+                        from: Address::with_last_byte(3),
+                        to: Some(Address::with_last_byte(4)),
+                        value: Some(U256::from(1)),
+                        typ: "SELFDESTRUCT".to_string(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                CallFrame {
+                    from: Address::with_last_byte(2),
+                    gas: U256::from(10_000),
+                    gas_used: U256::from(1_000),
+                    to: Some(Address::with_last_byte(5)),
+                    input: Bytes::new(),
+                    output: Some(Bytes::from_static(&[0xde, 0xad])),
+                    error: Some("execution reverted".to_string()),
+                    typ: "STATICCALL".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let traces = frame.to_parity_traces();
+        let trace_addresses: Vec<_> = traces.iter().map(|t| t.trace_address.clone()).collect();
+        assert_eq!(trace_addresses, vec![vec![], vec![0], vec![0, 0], vec![1]]);
+        assert_eq!(traces[0].subtraces, 2);
+        assert!(traces[1].action.is_create());
+        assert_eq!(traces[1].result.as_ref().unwrap().created_contract(), frame.calls[0].to);
+        assert!(traces[2].action.is_selfdestruct());
+        assert_eq!(traces[3].error.as_deref(), Some("Reverted"));
+        assert_eq!(traces[3].result.as_ref().unwrap().gas_used(), 1_000);
+
+        assert_eq!(CallFrame::from_parity_traces(&traces).unwrap(), frame);
+        assert_eq!(CallFrame::from_parity_traces(&[]), None);
+    }
+
     /// Helper to build a call frame tree for testing.
     fn make_frame(label: &str, children: Vec<CallFrame>) -> CallFrame {
         CallFrame { typ: label.to_string(), calls: children, ..Default::default() }
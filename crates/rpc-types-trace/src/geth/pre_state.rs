@@ -1,6 +1,7 @@
 //! Pre-state Geth tracer types.
 
-use alloy_primitives::{Address, Bytes, B256, U256};
+use crate::parity::{AccountDiff, Delta, StateDiff};
+use alloy_primitives::{Address, Bytes, B256, U256, U64};
 use serde::{Deserialize, Serialize};
 use std::collections::{btree_map, BTreeMap};
 
@@ -130,6 +131,86 @@ impl DiffMode {
             state.storage.retain(|_, value| *value != B256::ZERO);
         }
     }
+
+    /// Converts this diff into a parity [`StateDiff`], as returned by `trace_replayTransaction`
+    /// with the `stateDiff` trace type.
+    ///
+    /// Accounts only present in the post state are created, and accounts only present in the pre
+    /// state are destroyed. For all other accounts, geth omits unchanged fields and cleared storage
+    /// slots from the post state, so these are treated as unchanged and zero respectively.
+    pub fn to_state_diff(&self) -> StateDiff {
+        let mut diff = BTreeMap::new();
+        for (address, post) in &self.post {
+            let account = match self.pre.get(address) {
+                Some(pre) => AccountDiff {
+                    balance: field_delta(pre.balance, post.balance),
+                    code: field_delta(pre.code.clone(), post.code.clone()),
+                    nonce: field_delta(pre.nonce.map(U64::from), post.nonce.map(U64::from)),
+                    storage: pre
+                        .storage
+                        .keys()
+                        .chain(post.storage.keys())
+                        .map(|slot| {
+                            let from = pre.storage.get(slot).copied().unwrap_or_default();
+                            let to = post.storage.get(slot).copied().unwrap_or_default();
+                            let delta = if from == to {
+                                Delta::Unchanged
+                            } else {
+                                Delta::changed(from, to)
+                            };
+                            (*slot, delta)
+                        })
+                        .collect(),
+                },
+                None => AccountDiff {
+                    balance: Delta::Added(post.balance.unwrap_or_default()),
+                    code: Delta::Added(post.code.clone().unwrap_or_default()),
+                    nonce: Delta::Added(U64::from(post.nonce.unwrap_or_default())),
+                    storage: post.storage.iter().map(|(k, v)| (*k, Delta::Added(*v))).collect(),
+                },
+            };
+            diff.insert(*address, account);
+        }
+        for (address, pre) in &self.pre {
+            if !self.post.contains_key(address) {
+                diff.insert(
+                    *address,
+                    AccountDiff {
+                        balance: Delta::Removed(pre.balance.unwrap_or_default()),
+                        code: Delta::Removed(pre.code.clone().unwrap_or_default()),
+                        nonce: Delta::Removed(U64::from(pre.nonce.unwrap_or_default())),
+                        storage: pre
+                            .storage
+                            .iter()
+                            .map(|(k, v)| (*k, Delta::Removed(*v)))
+                            .collect(),
+                    },
+                );
+            }
+        }
+        StateDiff(diff)
+    }
+}
+
+impl From<DiffMode> for StateDiff {
+    fn from(diff: DiffMode) -> Self {
+        diff.to_state_diff()
+    }
+}
+
+/// Returns the delta of an account field, which geth omits from the post state if unchanged.
+fn field_delta<T: Default + PartialEq>(pre: Option<T>, post: Option<T>) -> Delta<T> {
+    match post {
+        Some(post) => {
+            let pre = pre.unwrap_or_default();
+            if pre == post {
+                Delta::Unchanged
+            } else {
+                Delta::changed(pre, post)
+            }
+        }
+        None => Delta::Unchanged,
+    }
 }
 
 /// Helper type for [DiffMode] to represent a specific set
@@ -277,6 +358,7 @@ impl PreStateConfig {
 mod tests {
     use super::*;
     use crate::geth::*;
+    use alloy_primitives::{address, b256};
     use similar_asserts::assert_eq;
 
     // See <https://github.com/ethereum/go-ethereum/tree/master/eth/tracers/internal/tracetest/testdata>
@@ -416,4 +498,36 @@ mod tests {
         assert!(diff_changed.post.is_empty());
         assert!(diff_changed.pre.is_empty());
     }
+
+    #[test]
+    fn test_to_state_diff() {
+        let mut diff: DiffMode = serde_json::from_str(DIFF_MODE).unwrap();
+        let contract = address!("0x3b873a919aa0512d5a0f09e6dcceaa4a6727fafe");
+        let sender = address!("0xb436ba50d378d4bbc8660d312a13df6af6e89dfb");
+
+        let state_diff = diff.to_state_diff();
+        assert_eq!(state_diff.len(), 4);
+        let account = &state_diff[&contract];
+        assert!(account.balance.is_changed());
+        assert!(account.code.is_unchanged());
+        assert!(account.nonce.is_unchanged());
+        assert_eq!(
+            account.storage[&B256::with_last_byte(3)],
+            Delta::changed(
+                b256!("0x000000000000000000000000000000000000000000000000000000005a37b834"),
+                b256!("0x000000000000000000000000000000000000000000000000000000005a37b95e"),
+            )
+        );
+        assert_eq!(state_diff[&sender].nonce, Delta::changed(U64::from(29072), U64::from(29073)));
+
+        // created and destroyed accounts
+        let created = Address::with_last_byte(1);
+        let destroyed = Address::with_last_byte(2);
+        diff.post.insert(created, AccountState::from_account_info(1, U256::from(10), None));
+        diff.pre.insert(destroyed, AccountState::from_account_info(1, U256::from(20), None));
+        let state_diff = StateDiff::from(diff);
+        assert_eq!(state_diff[&created].balance, Delta::Added(U256::from(10)));
+        assert_eq!(state_diff[&created].nonce, Delta::Added(U64::from(1)));
+        assert_eq!(state_diff[&destroyed].balance, Delta::Removed(U256::from(20)));
+    }
 }
@@ -3,7 +3,7 @@
 //! <https://www.quicknode.com/docs/ethereum/ots_getBlockTransactions>
 //! <https://github.com/otterscan/otterscan/blob/v2.6.1/docs/custom-jsonrpc.md>
 
-use crate::parity::TransactionTrace;
+use crate::{geth::CallFrame, parity::TransactionTrace};
 use alloy_primitives::{Address, Bloom, Bytes, TxHash, B256, U256};
 use alloy_rpc_types_eth::{
     Block, BlockTransactions, Header, Log, Transaction, TransactionReceipt, Withdrawals,
@@ -110,6 +110,33 @@ impl TraceEntry {
             output,
         })
     }
+
+    /// Flattens a geth call tree into [`TraceEntry`]s in depth-first pre-order.
+    ///
+    /// Like [`from_transaction_trace`](Self::from_transaction_trace), only call frames are
+    /// included, but their depth still accounts for skipped parent frames.
+    pub fn from_call_frame(frame: &CallFrame) -> Vec<Self> {
+        fn push(frame: &CallFrame, depth: u32, entries: &mut Vec<TraceEntry>) {
+            if frame.kind().is_some_and(|kind| !kind.is_any_create()) {
+                entries.push(TraceEntry {
+                    r#type: frame.typ.clone(),
+                    depth,
+                    from: frame.from,
+                    to: frame.to.unwrap_or_default(),
+                    value: frame.value,
+                    input: frame.input.clone(),
+                    output: frame.output.clone().unwrap_or_default(),
+                });
+            }
+            for call in &frame.calls {
+                push(call, depth + 1, entries);
+            }
+        }
+
+        let mut entries = Vec::new();
+        push(frame, 0, &mut entries);
+        entries
+    }
 }
 
 /// Internal issuance struct for `BlockDetails` struct
@@ -486,4 +513,30 @@ mod tests {
         let value = serde_json::to_value(&block).unwrap();
         assert_eq!(value, expected, "Serialized value does not match expected value");
     }
+
+    #[test]
+    fn test_trace_entries_from_call_frame() {
+        let frame = CallFrame {
+            typ: "CALL".to_string(),
+            to: Some(Address::with_last_byte(1)),
+            value: Some(U256::from(1)),
+            calls: vec![CallFrame {
+                typ: "CREATE".to_string(),
+                calls: vec![CallFrame {
+                    typ: "STATICCALL".to_string(),
+                    to: Some(Address::with_last_byte(2)),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let entries = TraceEntry::from_call_frame(&frame);
+        assert_eq!(entries.len(), 2);
+        assert_eq!((entries[0].r#type.as_str(), entries[0].depth), ("CALL", 0));
+        assert_eq!(entries[0].value, Some(U256::from(1)));
+        assert_eq!((entries[1].r#type.as_str(), entries[1].depth), ("STATICCALL", 2));
+        assert_eq!(entries[1].to, Address::with_last_byte(2));
+    }
 }
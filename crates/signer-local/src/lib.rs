@@ -20,7 +20,9 @@ pub use error::LocalSignerError;
 #[cfg(feature = "mnemonic")]
 mod mnemonic;
 #[cfg(feature = "mnemonic")]
-pub use mnemonic::{MnemonicBuilder, MnemonicBuilderError, MnemonicKey, MnemonicSignerIter};
+pub use mnemonic::{
+    DerivationPathTemplate, MnemonicBuilder, MnemonicBuilderError, MnemonicKey, MnemonicSignerIter,
+};
 
 mod private_key;
 
@@ -18,6 +18,36 @@ use zeroize::{Zeroize, ZeroizeOnDrop};
 const DEFAULT_DERIVATION_PATH_PREFIX: &str = "m/44'/60'/0'/0/";
 const DEFAULT_DERIVATION_PATH: &str = "m/44'/60'/0'/0/0";
 
+/// Well-known derivation path layouts used by Ethereum wallets.
+///
+/// Each template maps an account index to a full derivation path. Wallet software differs in which
+/// path component it increments when creating additional accounts, so the same mnemonic can
+/// produce different sets of addresses depending on the template.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DerivationPathTemplate {
+    /// The [BIP-44] layout used by most wallets: `m/44'/60'/0'/0/{index}`.
+    ///
+    /// [BIP-44]: https://github.com/bitcoin/bips/blob/master/bip-0044.mediawiki
+    #[default]
+    Bip44,
+    /// The layout used by Ledger Live, which increments the account component:
+    /// `m/44'/60'/{index}'/0/0`.
+    LedgerLive,
+    /// The legacy layout used by MyEtherWallet and the Ledger Chrome app: `m/44'/60'/0'/{index}`.
+    LegacyMew,
+}
+
+impl DerivationPathTemplate {
+    /// Returns the derivation path for the account at the given index.
+    pub fn path(&self, index: u32) -> String {
+        match self {
+            Self::Bip44 => format!("{DEFAULT_DERIVATION_PATH_PREFIX}{index}"),
+            Self::LedgerLive => format!("m/44'/60'/{index}'/0/0"),
+            Self::LegacyMew => format!("m/44'/60'/0'/{index}"),
+        }
+    }
+}
+
 /// Represents a structure that can resolve into a `PrivateKeySigner`.
 #[cfg_attr(feature = "zeroize", derive(Zeroize, ZeroizeOnDrop))]
 #[derive(Clone, Debug, PartialEq)]
@@ -151,6 +181,16 @@ impl<W: Wordlist> MnemonicBuilder<W> {
         self.derivation_path(format!("{DEFAULT_DERIVATION_PATH_PREFIX}{index}"))
     }
 
+    /// Sets the derivation path of the child key to be derived, using the given
+    /// [`DerivationPathTemplate`] to map the index to a path.
+    pub fn index_with(
+        self,
+        template: DerivationPathTemplate,
+        index: u32,
+    ) -> Result<Self, LocalSignerError> {
+        self.derivation_path(template.path(index))
+    }
+
     /// Sets the derivation path of the child key to be derived.
     pub fn derivation_path<T: AsRef<str>>(mut self, path: T) -> Result<Self, LocalSignerError> {
         self.derivation_path = path.as_ref().parse()?;
@@ -237,6 +277,19 @@ impl<W: Wordlist> MnemonicBuilder<W> {
         Ok(MnemonicKey { key })
     }
 
+    /// Builds the master [`MnemonicKey`] of the mnemonic phrase, ignoring the configured
+    /// derivation path.
+    ///
+    /// Seed generation is expensive, so this should be preferred over repeatedly calling
+    /// [`build`](Self::build) when deriving many accounts, see [`MnemonicKey::signer_at`].
+    pub fn build_master_key(&self) -> Result<MnemonicKey, LocalSignerError> {
+        let mnemonic = match &self.phrase {
+            Some(phrase) => Mnemonic::<W>::new_from_phrase(phrase)?,
+            None => return Err(MnemonicBuilderError::ExpectedPhraseNotFound.into()),
+        };
+        Ok(MnemonicKey { key: mnemonic.master_key(self.password.as_deref())? })
+    }
+
     /// Builds a [`MnemonicKey`] by deriving the full configured derivation path from the
     /// mnemonic phrase.
     pub fn build_key(&self) -> Result<MnemonicKey, LocalSignerError> {
@@ -286,6 +339,23 @@ impl MnemonicKey {
         Ok(Self { key: self.key.derive_child(index)? })
     }
 
+    /// Derives the signer at the given index of a [`DerivationPathTemplate`], treating this key as
+    /// the master key.
+    ///
+    /// The master key can be obtained with [`MnemonicBuilder::build_master_key`].
+    pub fn signer_at(
+        &self,
+        template: DerivationPathTemplate,
+        index: u32,
+    ) -> Result<PrivateKeySigner, LocalSignerError> {
+        let path: DerivationPath = template.path(index).parse()?;
+        let mut key = self.key.clone();
+        for &i in path.iter() {
+            key = key.derive_child(i)?;
+        }
+        Ok(xpriv_to_signer(&key))
+    }
+
     /// Extracts a [`PrivateKeySigner`] from this key.
     pub fn signer(&self) -> PrivateKeySigner {
         xpriv_to_signer(&self.key)
@@ -450,6 +520,42 @@ mod tests {
         assert_eq!(from_child.address, from_build.address);
     }
 
+    #[test]
+    fn mnemonic_path_templates() {
+        let phrase =
+            "work man father plunge mystery proud hollow address reunion sauce theory bonus";
+        let master =
+            MnemonicBuilder::<English>::default().phrase(phrase).build_master_key().unwrap();
+
+        for template in [
+            DerivationPathTemplate::Bip44,
+            DerivationPathTemplate::LedgerLive,
+            DerivationPathTemplate::LegacyMew,
+        ] {
+            for index in 0..3 {
+                let expected = MnemonicBuilder::<English>::default()
+                    .phrase(phrase)
+                    .derivation_path(template.path(index))
+                    .unwrap()
+                    .build()
+                    .unwrap();
+                let from_master = master.signer_at(template, index).unwrap();
+                assert_eq!(from_master.address, expected.address);
+            }
+        }
+
+        // index 0 of the bip44 and ledger live layouts share the same path
+        assert_eq!(
+            master.signer_at(DerivationPathTemplate::Bip44, 0).unwrap().address,
+            master.signer_at(DerivationPathTemplate::LedgerLive, 0).unwrap().address
+        );
+        assert_ne!(
+            master.signer_at(DerivationPathTemplate::Bip44, 1).unwrap().address,
+            master.signer_at(DerivationPathTemplate::LedgerLive, 1).unwrap().address
+        );
+        assert_eq!(DerivationPathTemplate::LegacyMew.path(4), "m/44'/60'/0'/4");
+    }
+
     #[test]
     fn mnemonic_iterator() {
         let phrase =
@@ -12,6 +12,8 @@ pub use error::{Error, Result, UnsupportedSignerOperation};
 mod signer;
 pub use signer::{Either, Signer, SignerSync};
 
+pub mod siwe;
+
 pub mod utils;
 
 pub use alloy_primitives::Signature;
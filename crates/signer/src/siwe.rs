@@ -0,0 +1,694 @@
+//! Sign-In with Ethereum ([EIP-4361]) messages.
+//!
+//! [EIP-4361]: https://eips.ethereum.org/EIPS/eip-4361
+
+use alloy_primitives::{eip191_hash_message, Address, ChainId, Signature, SignatureError, B256};
+use std::{
+    fmt,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use thiserror::Error;
+
+const HEADER_SUFFIX: &str = " wants you to sign in with your Ethereum account:";
+const URI_TAG: &str = "URI: ";
+const VERSION_TAG: &str = "Version: ";
+const CHAIN_ID_TAG: &str = "Chain ID: ";
+const NONCE_TAG: &str = "Nonce: ";
+const ISSUED_AT_TAG: &str = "Issued At: ";
+const EXPIRATION_TIME_TAG: &str = "Expiration Time: ";
+const NOT_BEFORE_TAG: &str = "Not Before: ";
+const REQUEST_ID_TAG: &str = "Request ID: ";
+const RESOURCES_TAG: &str = "Resources:";
+
+/// The only message version defined by EIP-4361.
+pub const SIWE_VERSION: &str = "1";
+
+/// Error returned when parsing, validating or verifying a [`SiweMessage`].
+#[derive(Debug, Error)]
+pub enum SiweError {
+    /// A required line of the message is missing.
+    #[error("missing `{0}` in SIWE message")]
+    MissingField(&'static str),
+    /// A line of the message is malformed.
+    #[error("invalid `{0}` in SIWE message")]
+    InvalidField(&'static str),
+    /// The message contains unexpected trailing lines.
+    #[error("unexpected trailing content in SIWE message")]
+    TrailingContent,
+    /// The domain does not match the expected domain.
+    #[error("SIWE domain mismatch: expected {expected}, got {got}")]
+    DomainMismatch {
+        /// The expected domain.
+        expected: String,
+        /// The domain of the message.
+        got: String,
+    },
+    /// The URI does not match the expected URI.
+    #[error("SIWE URI mismatch: expected {expected}, got {got}")]
+    UriMismatch {
+        /// The expected URI.
+        expected: String,
+        /// The URI of the message.
+        got: String,
+    },
+    /// The chain ID does not match the expected chain ID.
+    #[error("SIWE chain ID mismatch: expected {expected}, got {got}")]
+    ChainIdMismatch {
+        /// The expected chain ID.
+        expected: ChainId,
+        /// The chain ID of the message.
+        got: ChainId,
+    },
+    /// The nonce does not match the expected nonce.
+    #[error("SIWE nonce mismatch: expected {expected}, got {got}")]
+    NonceMismatch {
+        /// The expected nonce.
+        expected: String,
+        /// The nonce of the message.
+        got: String,
+    },
+    /// The message has expired.
+    #[error("SIWE message expired at {0}")]
+    Expired(Timestamp),
+    /// The message is not valid yet.
+    #[error("SIWE message is not valid before {0}")]
+    NotYetValid(Timestamp),
+    /// The signature was not produced by the address of the message.
+    #[error("SIWE signature was signed by {recovered}, expected {expected}")]
+    SignerMismatch {
+        /// The address of the message.
+        expected: Address,
+        /// The address recovered from the signature.
+        recovered: Address,
+    },
+    /// Recovering the signer of the signature failed.
+    #[error(transparent)]
+    Signature(#[from] SignatureError),
+}
+
+/// An [RFC 3339] timestamp, as used in [`SiweMessage`]s.
+///
+/// The original text is kept, so that parsing and formatting a message is lossless. Fractional
+/// seconds are ignored when comparing timestamps.
+///
+/// [RFC 3339]: https://datatracker.ietf.org/doc/html/rfc3339
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Timestamp {
+    text: String,
+    seconds: i64,
+}
+
+impl Timestamp {
+    /// Creates a UTC timestamp from the given number of seconds since the unix epoch.
+    pub fn from_unix(seconds: i64) -> Self {
+        let (days, secs) = (seconds.div_euclid(86400), seconds.rem_euclid(86400));
+        let (year, month, day) = civil_from_days(days);
+        let text = format!(
+            "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+            secs / 3600,
+            secs % 3600 / 60,
+            secs % 60
+        );
+        Self { text, seconds }
+    }
+
+    /// Returns the current time.
+    pub fn now() -> Self {
+        Self::from_unix(unix_now())
+    }
+
+    /// Returns the number of seconds since the unix epoch.
+    pub const fn unix(&self) -> i64 {
+        self.seconds
+    }
+
+    /// Returns the timestamp as it appears in the message.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = SiweError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let seconds = parse_rfc3339(s).ok_or(SiweError::InvalidField("timestamp"))?;
+        Ok(Self { text: s.to_string(), seconds })
+    }
+}
+
+/// Expected values and the reference time used by [`SiweMessage::validate`].
+///
+/// Fields that are `None` are not checked.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SiweVerificationOpts {
+    /// The domain the message must have been issued for.
+    pub domain: Option<String>,
+    /// The URI the message must have been issued for.
+    pub uri: Option<String>,
+    /// The chain ID the message must have been issued for.
+    pub chain_id: Option<ChainId>,
+    /// The nonce the message must contain.
+    pub nonce: Option<String>,
+    /// The time, in seconds since the unix epoch, at which the validity window is checked.
+    ///
+    /// Defaults to the current system time.
+    pub timestamp: Option<i64>,
+}
+
+/// A Sign-In with Ethereum message, as defined in [EIP-4361].
+///
+/// The message is parsed from and formatted to the text format signed by the user with
+/// [`FromStr`] and [`Display`](fmt::Display).
+///
+/// # Examples
+///
+/// ```
+/// use alloy_signer::siwe::SiweMessage;
+///
+/// let text = "example.com wants you to sign in with your Ethereum account:
+/// 0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045
+///
+/// Sign in to Example.
+///
+/// URI: https://example.com/login
+/// Version: 1
+/// Chain ID: 1
+/// Nonce: 32891756
+/// Issued At: 2021-09-30T16:25:24Z";
+///
+/// let message: SiweMessage = text.parse()?;
+/// assert_eq!(message.domain, "example.com");
+/// assert_eq!(message.to_string(), text);
+/// # Ok::<_, alloy_signer::siwe::SiweError>(())
+/// ```
+///
+/// [EIP-4361]: https://eips.ethereum.org/EIPS/eip-4361
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SiweMessage {
+    /// The URI scheme of the origin of the request.
+    pub scheme: Option<String>,
+    /// The authority requesting the signing.
+    pub domain: String,
+    /// The address performing the signing.
+    pub address: Address,
+    /// A human-readable assertion the user signs.
+    pub statement: Option<String>,
+    /// The subject of the signing.
+    pub uri: String,
+    /// The version of the message, must be [`SIWE_VERSION`].
+    pub version: String,
+    /// The chain ID to which the session is bound.
+    pub chain_id: ChainId,
+    /// A random token used to prevent replay attacks, at least 8 alphanumeric characters.
+    pub nonce: String,
+    /// The time the message was generated.
+    pub issued_at: Timestamp,
+    /// The time after which the message is no longer valid.
+    pub expiration_time: Option<Timestamp>,
+    /// The time before which the message is not yet valid.
+    pub not_before: Option<Timestamp>,
+    /// A system-specific identifier for the sign-in request.
+    pub request_id: Option<String>,
+    /// Resources the user wishes to have resolved as part of authentication.
+    pub resources: Vec<String>,
+}
+
+impl SiweMessage {
+    /// Creates a new message issued now, without any of the optional fields.
+    pub fn new(
+        domain: impl Into<String>,
+        address: Address,
+        uri: impl Into<String>,
+        chain_id: ChainId,
+        nonce: impl Into<String>,
+    ) -> Self {
+        Self {
+            scheme: None,
+            domain: domain.into(),
+            address,
+            statement: None,
+            uri: uri.into(),
+            version: SIWE_VERSION.to_string(),
+            chain_id,
+            nonce: nonce.into(),
+            issued_at: Timestamp::now(),
+            expiration_time: None,
+            not_before: None,
+            request_id: None,
+            resources: Vec::new(),
+        }
+    }
+
+    /// Returns the [EIP-191] hash of the formatted message, which is what the user signs.
+    ///
+    /// [EIP-191]: https://eips.ethereum.org/EIPS/eip-191
+    pub fn eip191_hash(&self) -> B256 {
+        eip191_hash_message(self.to_string())
+    }
+
+    /// Checks the message against the expected values and validity window in `opts`.
+    ///
+    /// This does not check the signature, see [`verify_eoa`](Self::verify_eoa).
+    pub fn validate(&self, opts: &SiweVerificationOpts) -> Result<(), SiweError> {
+        if let Some(domain) = &opts.domain {
+            if *domain != self.domain {
+                return Err(SiweError::DomainMismatch {
+                    expected: domain.clone(),
+                    got: self.domain.clone(),
+                });
+            }
+        }
+        if let Some(uri) = &opts.uri {
+            if *uri != self.uri {
+                return Err(SiweError::UriMismatch {
+                    expected: uri.clone(),
+                    got: self.uri.clone(),
+                });
+            }
+        }
+        if let Some(chain_id) = opts.chain_id {
+            if chain_id != self.chain_id {
+                return Err(SiweError::ChainIdMismatch { expected: chain_id, got: self.chain_id });
+            }
+        }
+        if let Some(nonce) = &opts.nonce {
+            if *nonce != self.nonce {
+                return Err(SiweError::NonceMismatch {
+                    expected: nonce.clone(),
+                    got: self.nonce.clone(),
+                });
+            }
+        }
+
+        let timestamp = opts.timestamp.unwrap_or_else(unix_now);
+        if let Some(expiration_time) = &self.expiration_time {
+            if timestamp >= expiration_time.unix() {
+                return Err(SiweError::Expired(expiration_time.clone()));
+            }
+        }
+        if let Some(not_before) = &self.not_before {
+            if timestamp < not_before.unix() {
+                return Err(SiweError::NotYetValid(not_before.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates the message and checks that the signature was produced by the private key of
+    /// [`address`](Self::address).
+    ///
+    /// Only signatures of externally owned accounts can be verified this way. Smart contract
+    /// accounts can be verified with the provider's `SignatureVerificationApi`.
+    pub fn verify_eoa(
+        &self,
+        signature: &Signature,
+        opts: &SiweVerificationOpts,
+    ) -> Result<(), SiweError> {
+        self.validate(opts)?;
+        let recovered = signature.recover_address_from_prehash(&self.eip191_hash())?;
+        if recovered != self.address {
+            return Err(SiweError::SignerMismatch { expected: self.address, recovered });
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SiweMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(scheme) = &self.scheme {
+            write!(f, "{scheme}://")?;
+        }
+        writeln!(f, "{}{HEADER_SUFFIX}", self.domain)?;
+        writeln!(f, "{}", self.address.to_checksum(None))?;
+        writeln!(f)?;
+        if let Some(statement) = &self.statement {
+            writeln!(f, "{statement}")?;
+        }
+        writeln!(f)?;
+        writeln!(f, "{URI_TAG}{}", self.uri)?;
+        writeln!(f, "{VERSION_TAG}{}", self.version)?;
+        writeln!(f, "{CHAIN_ID_TAG}{}", self.chain_id)?;
+        writeln!(f, "{NONCE_TAG}{}", self.nonce)?;
+        write!(f, "{ISSUED_AT_TAG}{}", self.issued_at)?;
+        if let Some(expiration_time) = &self.expiration_time {
+            write!(f, "\n{EXPIRATION_TIME_TAG}{expiration_time}")?;
+        }
+        if let Some(not_before) = &self.not_before {
+            write!(f, "\n{NOT_BEFORE_TAG}{not_before}")?;
+        }
+        if let Some(request_id) = &self.request_id {
+            write!(f, "\n{REQUEST_ID_TAG}{request_id}")?;
+        }
+        if !self.resources.is_empty() {
+            write!(f, "\n{RESOURCES_TAG}")?;
+            for resource in &self.resources {
+                write!(f, "\n- {resource}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for SiweMessage {
+    type Err = SiweError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.split('\n').peekable();
+
+        let header = lines
+            .next()
+            .and_then(|line| line.strip_suffix(HEADER_SUFFIX))
+            .ok_or(SiweError::MissingField("header"))?;
+        let (scheme, domain) = match header.split_once("://") {
+            Some((scheme, domain)) => (Some(scheme.to_string()), domain),
+            None => (None, header),
+        };
+        if domain.is_empty() || domain.contains(char::is_whitespace) {
+            return Err(SiweError::InvalidField("domain"));
+        }
+        if scheme.as_ref().is_some_and(|scheme| !is_uri_scheme(scheme)) {
+            return Err(SiweError::InvalidField("scheme"));
+        }
+
+        let address = lines.next().ok_or(SiweError::MissingField("address"))?;
+        let address = Address::parse_checksummed(address, None)
+            .map_err(|_| SiweError::InvalidField("address"))?;
+
+        if lines.next() != Some("") {
+            return Err(SiweError::MissingField("address"));
+        }
+        let statement = match lines.next_if(|line| !line.starts_with(URI_TAG)) {
+            Some("") => None,
+            Some(statement) => {
+                if lines.next() != Some("") {
+                    return Err(SiweError::InvalidField("statement"));
+                }
+                Some(statement.to_string())
+            }
+            // messages from older versions of the spec omit the empty statement line
+            None => None,
+        };
+
+        let uri = tagged(&mut lines, URI_TAG).ok_or(SiweError::MissingField("URI"))?;
+        if !uri.split_once(':').is_some_and(|(scheme, _)| is_uri_scheme(scheme)) {
+            return Err(SiweError::InvalidField("URI"));
+        }
+        let version = tagged(&mut lines, VERSION_TAG).ok_or(SiweError::MissingField("Version"))?;
+        if version != SIWE_VERSION {
+            return Err(SiweError::InvalidField("Version"));
+        }
+        let chain_id = tagged(&mut lines, CHAIN_ID_TAG)
+            .ok_or(SiweError::MissingField("Chain ID"))?
+            .parse()
+            .map_err(|_| SiweError::InvalidField("Chain ID"))?;
+        let nonce = tagged(&mut lines, NONCE_TAG).ok_or(SiweError::MissingField("Nonce"))?;
+        if nonce.len() < 8 || !nonce.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return Err(SiweError::InvalidField("Nonce"));
+        }
+        let issued_at =
+            tagged(&mut lines, ISSUED_AT_TAG).ok_or(SiweError::MissingField("Issued At"))?;
+        let issued_at = issued_at.parse().map_err(|_| SiweError::InvalidField("Issued At"))?;
+        let expiration_time = tagged(&mut lines, EXPIRATION_TIME_TAG)
+            .map(|t| t.parse().map_err(|_| SiweError::InvalidField("Expiration Time")))
+            .transpose()?;
+        let not_before = tagged(&mut lines, NOT_BEFORE_TAG)
+            .map(|t| t.parse().map_err(|_| SiweError::InvalidField("Not Before")))
+            .transpose()?;
+        let request_id = tagged(&mut lines, REQUEST_ID_TAG).map(str::to_string);
+
+        let mut resources = Vec::new();
+        if lines.next_if_eq(&RESOURCES_TAG).is_some() {
+            while let Some(line) = lines.next_if(|line| line.starts_with("- ")) {
+                resources.push(line[2..].to_string());
+            }
+        }
+
+        if lines.next().is_some() {
+            return Err(SiweError::TrailingContent);
+        }
+
+        Ok(Self {
+            scheme,
+            domain: domain.to_string(),
+            address,
+            statement,
+            uri: uri.to_string(),
+            version: version.to_string(),
+            chain_id,
+            nonce: nonce.to_string(),
+            issued_at,
+            expiration_time,
+            not_before,
+            request_id,
+            resources,
+        })
+    }
+}
+
+/// Returns the value of the next line if it starts with `tag`.
+fn tagged<'a>(
+    lines: &mut std::iter::Peekable<impl Iterator<Item = &'a str>>,
+    tag: &str,
+) -> Option<&'a str> {
+    lines.next_if(|line| line.starts_with(tag)).map(|line| &line[tag.len()..])
+}
+
+/// Returns `true` if `scheme` is a valid [RFC 3986] URI scheme.
+///
+/// [RFC 3986]: https://datatracker.ietf.org/doc/html/rfc3986#section-3.1
+fn is_uri_scheme(scheme: &str) -> bool {
+    let mut chars = scheme.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as i64)
+}
+
+/// Parses an [RFC 3339] `date-time` into seconds since the unix epoch.
+///
+/// [RFC 3339]: https://datatracker.ietf.org/doc/html/rfc3339#section-5.6
+fn parse_rfc3339(s: &str) -> Option<i64> {
+    let b = s.as_bytes();
+    let num = |start: usize, len: usize| -> Option<i64> {
+        let digits = b.get(start..start + len)?;
+        digits
+            .iter()
+            .try_fold(0i64, |acc, &d| d.is_ascii_digit().then(|| acc * 10 + i64::from(d - b'0')))
+    };
+    let sep = |i: usize, c: &[u8]| b.get(i).is_some_and(|b| c.contains(b));
+
+    if !(sep(4, b"-") && sep(7, b"-") && sep(10, b"Tt") && sep(13, b":") && sep(16, b":")) {
+        return None;
+    }
+    let (year, month, day) = (num(0, 4)?, num(5, 2)?, num(8, 2)?);
+    let (hour, minute, second) = (num(11, 2)?, num(14, 2)?, num(17, 2)?);
+    if !(1..=12).contains(&month) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    let days = days_from_civil(year, month, day);
+    if day == 0 || civil_from_days(days) != (year, month, day) {
+        return None;
+    }
+
+    let mut rest = &b[19..];
+    if let Some(fraction) = rest.strip_prefix(b".") {
+        let digits = fraction.iter().take_while(|b| b.is_ascii_digit()).count();
+        if digits == 0 {
+            return None;
+        }
+        rest = &fraction[digits..];
+    }
+    let offset = match rest {
+        [b'Z' | b'z'] => 0,
+        [sign @ (b'+' | b'-'), ..] if rest.len() == 6 && rest[3] == b':' => {
+            let offset_start = b.len() - 5;
+            let (hours, minutes) = (num(offset_start, 2)?, num(offset_start + 3, 2)?);
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            let offset = hours * 3600 + minutes * 60;
+            if *sign == b'-' {
+                -offset
+            } else {
+                offset
+            }
+        }
+        _ => return None,
+    };
+
+    Some(days * 86400 + hour * 3600 + minute * 60 + second - offset)
+}
+
+/// Returns the number of days since the unix epoch of the given proleptic Gregorian date.
+const fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Returns the proleptic Gregorian date of the given number of days since the unix epoch.
+const fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let doe = days - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+    use assert_matches::assert_matches;
+    use k256::ecdsa::SigningKey;
+
+    const MESSAGE: &str = "https://example.com wants you to sign in with your Ethereum account:
+0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045
+
+I accept the ExampleOrg Terms of Service: https://example.com/tos
+
+URI: https://example.com/login
+Version: 1
+Chain ID: 1
+Nonce: 32891756
+Issued At: 2021-09-30T16:25:24Z
+Expiration Time: 2021-10-01T16:25:24.000+02:00
+Not Before: 2021-09-30T16:25:24Z
+Request ID: some-request-id
+Resources:
+- ipfs://bafybeiemxf5abjwjbikoz4mc3a3dla6ual3jsgpdr4cjr3oz3evfyavhwq/
+- https://example.com/my-web2-claim.json";
+
+    #[test]
+    fn parse_format_roundtrip() {
+        let message: SiweMessage = MESSAGE.parse().unwrap();
+        assert_eq!(message.scheme.as_deref(), Some("https"));
+        assert_eq!(message.domain, "example.com");
+        assert_eq!(message.address, address!("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"));
+        assert_eq!(message.chain_id, 1);
+        assert_eq!(message.issued_at.unix(), 1633019124);
+        assert_eq!(message.expiration_time.as_ref().unwrap().unix(), 1633019124 + 86400 - 7200);
+        assert_eq!(message.request_id.as_deref(), Some("some-request-id"));
+        assert_eq!(message.resources.len(), 2);
+        assert_eq!(message.to_string(), MESSAGE);
+
+        let minimal = SiweMessage {
+            scheme: None,
+            statement: None,
+            expiration_time: None,
+            not_before: None,
+            request_id: None,
+            resources: Vec::new(),
+            ..message
+        };
+        let text = minimal.to_string();
+        assert!(text.contains("A96045\n\n\nURI: "));
+        assert_eq!(text.parse::<SiweMessage>().unwrap(), minimal);
+
+        // older messages without the empty statement line are accepted
+        let legacy = text.replace("A96045\n\n\nURI: ", "A96045\n\nURI: ");
+        assert_eq!(legacy.parse::<SiweMessage>().unwrap(), minimal);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let parse = |s: String| s.parse::<SiweMessage>().unwrap_err();
+        assert_matches!(
+            parse(MESSAGE.replace(
+                "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+                "0xd8da6bf26964af9d7eed9e03e53415d37aa96046"
+            )),
+            SiweError::InvalidField("address")
+        );
+        assert_matches!(
+            parse(MESSAGE.replace("Nonce: 32891756", "Nonce: 1234")),
+            SiweError::InvalidField("Nonce")
+        );
+        assert_matches!(
+            parse(MESSAGE.replace("Version: 1", "Version: 2")),
+            SiweError::InvalidField("Version")
+        );
+        assert_matches!(
+            parse(MESSAGE.replace("2021-09-30T16:25:24Z\nExp", "2021-02-30T16:25:24Z\nExp")),
+            SiweError::InvalidField("Issued At")
+        );
+        assert_matches!(
+            parse(MESSAGE.replace("Chain ID: 1\n", "")),
+            SiweError::MissingField("Chain ID")
+        );
+        assert_matches!(parse(format!("{MESSAGE}\n")), SiweError::TrailingContent);
+    }
+
+    #[test]
+    fn timestamps() {
+        for seconds in [0, 951782400, 1633019124, 4102444800, -86401] {
+            let timestamp = Timestamp::from_unix(seconds);
+            assert_eq!(timestamp.as_str().parse::<Timestamp>().unwrap(), timestamp);
+        }
+        assert_eq!(Timestamp::from_unix(951782400).as_str(), "2000-02-29T00:00:00Z");
+        assert_eq!("1970-01-01T01:00:00+01:00".parse::<Timestamp>().unwrap().unix(), 0);
+        assert!("2021-09-30 16:25:24Z".parse::<Timestamp>().is_err());
+        assert!("2021-09-30T16:25:24".parse::<Timestamp>().is_err());
+    }
+
+    #[test]
+    fn validate_and_verify() {
+        let key = SigningKey::from_slice(&[0x42; 32]).unwrap();
+        let address = crate::utils::secret_key_to_address(&key);
+        let mut message =
+            SiweMessage::new("example.com", address, "https://example.com", 1, "abcdefgh1");
+        message.issued_at = Timestamp::from_unix(1000);
+        message.not_before = Some(Timestamp::from_unix(1000));
+        message.expiration_time = Some(Timestamp::from_unix(2000));
+
+        let (sig, recid) = key.sign_prehash_recoverable(message.eip191_hash().as_slice()).unwrap();
+        let signature = Signature::from((sig, recid));
+
+        let opts = SiweVerificationOpts {
+            domain: Some("example.com".to_string()),
+            chain_id: Some(1),
+            nonce: Some("abcdefgh1".to_string()),
+            timestamp: Some(1500),
+            ..Default::default()
+        };
+        message.verify_eoa(&signature, &opts).unwrap();
+
+        let at = |timestamp| SiweVerificationOpts { timestamp: Some(timestamp), ..opts.clone() };
+        assert_matches!(message.validate(&at(999)), Err(SiweError::NotYetValid(_)));
+        assert_matches!(message.validate(&at(2000)), Err(SiweError::Expired(_)));
+        assert_matches!(
+            message.validate(&SiweVerificationOpts { chain_id: Some(10), ..opts.clone() }),
+            Err(SiweError::ChainIdMismatch { expected: 10, got: 1 })
+        );
+        assert_matches!(
+            message
+                .validate(&SiweVerificationOpts { nonce: Some("other123".into()), ..opts.clone() }),
+            Err(SiweError::NonceMismatch { .. })
+        );
+
+        message.nonce = "abcdefgh2".to_string();
+        let opts = SiweVerificationOpts { nonce: None, ..opts };
+        assert_matches!(
+            message.verify_eoa(&signature, &opts),
+            Err(SiweError::SignerMismatch { expected, .. }) if expected == address
+        );
+    }
+}
@@ -21,6 +21,9 @@ pub use hyper;
 pub use hyper_util;
 
 mod layers;
+
+pub mod sse;
+
 #[cfg(all(not(target_family = "wasm"), feature = "jwt-auth"))]
 pub use layers::{AuthLayer, AuthService};
 #[cfg(all(not(target_family = "wasm"), feature = "traceparent"))]
@@ -0,0 +1,121 @@
+//! An incremental parser for `text/event-stream` bodies.
+//!
+//! See <https://html.spec.whatwg.org/multipage/server-sent-events.html#event-stream-interpretation>
+
+/// A server-sent event.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SseEvent {
+    /// The event name, `message` if the event has no `event` field.
+    pub event: String,
+    /// The data lines of the event, joined by newlines.
+    pub data: String,
+}
+
+/// Incrementally parses a `text/event-stream` body into events.
+///
+/// Lines may end with `\r\n`, `\n` or `\r`, and may be split across chunks at any byte.
+#[derive(Debug, Default)]
+pub struct SseParser {
+    buf: Vec<u8>,
+    /// Whether the last line ended with `\r`, so that a following `\n` belongs to it.
+    skip_lf: bool,
+    event: Option<String>,
+    data: Option<String>,
+}
+
+impl SseParser {
+    /// Appends a chunk of the body.
+    pub fn push(&mut self, chunk: &[u8]) {
+        self.buf.extend_from_slice(chunk);
+    }
+
+    /// Returns the next complete event, skipping events without data such as keep-alive
+    /// comments.
+    pub fn next_event(&mut self) -> Option<SseEvent> {
+        while let Some(line) = self.next_line() {
+            if line.is_empty() {
+                let event = self.event.take();
+                if let Some(data) = self.data.take() {
+                    return Some(SseEvent {
+                        event: event.unwrap_or_else(|| "message".to_string()),
+                        data,
+                    });
+                }
+                continue;
+            }
+
+            let line = String::from_utf8_lossy(&line);
+            let (field, value) = line.split_once(':').unwrap_or((&line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "event" => self.event = Some(value.to_string()),
+                "data" => match &mut self.data {
+                    Some(data) => {
+                        data.push('\n');
+                        data.push_str(value);
+                    }
+                    None => self.data = Some(value.to_string()),
+                },
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Removes the next complete line from the buffer, without its line ending.
+    fn next_line(&mut self) -> Option<Vec<u8>> {
+        if self.skip_lf && !self.buf.is_empty() {
+            self.skip_lf = false;
+            if self.buf[0] == b'\n' {
+                self.buf.remove(0);
+            }
+        }
+
+        let end = self.buf.iter().position(|&b| b == b'\r' || b == b'\n')?;
+        let mut line: Vec<u8> = self.buf.drain(..=end).collect();
+        match line.pop() {
+            Some(b'\r') if self.buf.first() == Some(&b'\n') => {
+                self.buf.remove(0);
+            }
+            Some(b'\r') => self.skip_lf = self.buf.is_empty(),
+            _ => {}
+        }
+        Some(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(event: &str, data: &str) -> Option<SseEvent> {
+        Some(SseEvent { event: event.to_string(), data: data.to_string() })
+    }
+
+    #[test]
+    fn parse_chunked_events() {
+        let mut parser = SseParser::default();
+        parser.push(b": keep-alive\n\nevent: head\ndata: {\"slot\":");
+        assert_eq!(parser.next_event(), None);
+
+        parser.push(b"\"1\"}\r\n\r\ndata: a\ndata: b\n\n");
+        assert_eq!(parser.next_event(), event("head", "{\"slot\":\"1\"}"));
+        assert_eq!(parser.next_event(), event("message", "a\nb"));
+        assert_eq!(parser.next_event(), None);
+    }
+
+    #[test]
+    fn parse_cr_line_endings() {
+        let mut parser = SseParser::default();
+        parser.push(b"event: head\rdata: a\rdata: b\r\r:ping\r\rdata: c\r");
+        assert_eq!(parser.next_event(), event("head", "a\nb"));
+        assert_eq!(parser.next_event(), None);
+
+        // A `\r` at the end of a chunk may be followed by the `\n` of a `\r\n` line ending.
+        parser.push(b"\n\r");
+        assert_eq!(parser.next_event(), event("message", "c"));
+        parser.push(b"\ndata: d\r\n\r");
+        assert_eq!(parser.next_event(), event("message", "d"));
+        assert_eq!(parser.next_event(), None);
+    }
+}